@@ -0,0 +1,142 @@
+// Renders a board or a replay to an image file: the svg of a position, the animated gif of a
+// replay. The board is a snapshot or a save of the app, the replay a shared link.
+//
+// usage: export (--snapshot FILE [--rules NAME] | --replay LINK) [--theme NAME] [--delay CENTISECONDS]
+//               --out FILE.svg|FILE.gif
+use std::env;
+use std::fs;
+use std::process;
+
+use rusty_2048::export::{board_svg, replay_gif, DEFAULT_GIF_DELAY};
+use rusty_2048::game::game_variants::game_variant_1::{SpecificGame, VariantRules};
+use rusty_2048::game::merge_rules::{ClassicMergeRule, FibonacciMergeRule, ThreesMergeRule, TriplingMergeRule};
+use rusty_2048::replay::Replay;
+use rusty_2048::storage::saves::save_from_text;
+use rusty_2048::storage::settings::SETTINGS_CAPACITY;
+use rusty_2048::theme::{Theme, THEMES};
+use rusty_2048::variants::{variant, Rules};
+
+enum Source {
+    // (file, rules)
+    Snapshot(String, Rules),
+    Replay(Replay),
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum OutputFormat {
+    Svg,
+    Gif,
+}
+
+struct Arguments {
+    source: Source,
+    theme: Theme,
+    delay: u16,
+    out: String,
+    format: OutputFormat,
+}
+
+fn usage() -> String {
+    format!(
+        "usage: export (--snapshot FILE [--rules {}] | --replay LINK) [--theme {}] [--delay CENTISECONDS] --out FILE.svg|FILE.gif",
+        Rules::names().join("|"),
+        THEMES.iter().map(|t| t.name).collect::<Vec<_>>().join("|")
+    )
+}
+
+fn parse_arguments(args: &[String]) -> Result<Arguments, String> {
+    let mut snapshot = None;
+    let mut rules = Rules::Classic;
+    let mut replay = None;
+    let mut theme = THEMES[0];
+    let mut delay = DEFAULT_GIF_DELAY;
+    let mut out = None;
+
+    let mut iter = args.iter();
+    while let Some(name) = iter.next() {
+        let value = iter.next().ok_or(format!("missing value for {name}"))?;
+        match name.as_str() {
+            "--snapshot" => snapshot = Some(value.clone()),
+            "--rules" => rules = Rules::from_name(value).ok_or(format!("unknown rules: {value}"))?,
+            "--replay" => replay = Some(Replay::from_link(value)?),
+            "--theme" => theme = THEMES.iter().find(|t| t.name == value).copied().ok_or(format!("unknown theme: {value}"))?,
+            "--delay" => delay = value.parse().map_err(|_| format!("invalid value for {name}: {value}"))?,
+            "--out" => out = Some(value.clone()),
+            _ => return Err(format!("unknown argument: {name}")),
+        }
+    }
+    let source = match (snapshot, replay) {
+        (Some(file), None) => Source::Snapshot(file, rules),
+        (None, Some(replay)) => Source::Replay(replay),
+        _ => return Err("one of --snapshot and --replay is needed".to_string()),
+    };
+    let out = out.ok_or("missing --out")?;
+    let format = match out.rsplit_once('.').map(|(_, extension)| extension.to_lowercase()).as_deref() {
+        Some("svg") => OutputFormat::Svg,
+        Some("gif") => OutputFormat::Gif,
+        _ => return Err(format!("the output shall be a .svg or a .gif file: {out}")),
+    };
+    Ok(Arguments { source, theme, delay, out, format })
+}
+
+// the svg of the last board, the gif of all of them
+fn render<R: VariantRules>(states: &[SpecificGame<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>], args: &Arguments) -> Result<Vec<u8>, String> {
+    match args.format {
+        OutputFormat::Svg => Ok(board_svg(states.last().ok_or("no board to export")?, &args.theme).into_bytes()),
+        OutputFormat::Gif => replay_gif(states, &args.theme, args.delay),
+    }
+}
+
+fn export<R: VariantRules>(args: &Arguments) -> Result<Vec<u8>, String> {
+    match &args.source {
+        Source::Snapshot(file, _) => {
+            let text = fs::read_to_string(file).map_err(|e| format!("cannot read {file}: {e}"))?;
+            let (g, _, _) = save_from_text::<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>(&text)?;
+            render(&[g], args)
+        }
+        Source::Replay(replay) => {
+            let def = variant(&replay.variant).ok_or(format!("unknown variant: {}", replay.variant))?;
+            render(&replay.states::<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>(def.setup)?, args)
+        }
+    }
+}
+
+fn run(args: &Arguments) -> Result<Vec<u8>, String> {
+    let rules = match &args.source {
+        Source::Snapshot(_, rules) => *rules,
+        Source::Replay(replay) => variant(&replay.variant).map(|def| def.rules).ok_or(format!("unknown variant: {}", replay.variant))?,
+    };
+    match rules {
+        Rules::Classic => export::<ClassicMergeRule>(args),
+        Rules::Fibonacci => export::<FibonacciMergeRule>(args),
+        Rules::Tripling => export::<TriplingMergeRule>(args),
+        Rules::Threes => export::<ThreesMergeRule>(args),
+    }
+}
+
+fn main() {
+    let raw_args: Vec<String> = env::args().skip(1).collect();
+    if raw_args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", usage());
+        return;
+    }
+    let args = match parse_arguments(&raw_args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let bytes = match run(&args) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(2);
+        }
+    };
+    if let Err(e) = fs::write(&args.out, bytes) {
+        eprintln!("cannot write {}: {e}", args.out);
+        process::exit(2);
+    }
+}
@@ -0,0 +1,229 @@
+// Relay server of the online races, it only listens on localhost
+//
+// usage: relay_server [--port N] [--seed S] [--goal N]
+//
+// A client speaks the line protocol of rusty_2048::net::protocol either directly over TCP (one
+// message per line, handy for scripts and tests) or over a websocket (the browsers).
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rusty_2048::game::rand_manager::RandManager;
+use rusty_2048::net::protocol::{ClientMessage, ServerMessage};
+use rusty_2048::net::relay::{Room, DEFAULT_RACE_GOAL};
+use rusty_2048::net::websocket;
+use rusty_2048::text::parse_number;
+
+const DEFAULT_PORT: u16 = 9048;
+
+struct Arguments {
+    port: u16,
+    // every room uses this seed when it is given, otherwise a random one
+    seed: Option<u64>,
+    goal: i32,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Transport {
+    Lines,
+    WebSocket,
+}
+
+struct Client {
+    room: String,
+    sender: Sender<String>,
+}
+
+#[derive(Default)]
+struct Relay {
+    rooms: HashMap<String, Room>,
+    clients: HashMap<usize, Client>,
+}
+
+impl Relay {
+
+    fn send(&self, client_id: usize, message: &ServerMessage) {
+        if let Some(client) = self.clients.get(&client_id) {
+            let _ = client.sender.send(message.to_line());
+        }
+    }
+
+    fn broadcast(&self, room: &str, message: &ServerMessage) {
+        for client in self.clients.values().filter(|c| c.room == room) {
+            let _ = client.sender.send(message.to_line());
+        }
+    }
+}
+
+fn usage() -> String {
+    "usage: relay_server [--port N] [--seed S] [--goal N]".to_string()
+}
+
+fn parse_arguments(args: &[String]) -> Result<Arguments, String> {
+    let mut arguments = Arguments { port: DEFAULT_PORT, seed: None, goal: DEFAULT_RACE_GOAL };
+    let mut iter = args.iter();
+    while let Some(name) = iter.next() {
+        let value = iter.next().ok_or(format!("missing value for {name}"))?;
+        match name.as_str() {
+            "--port" => arguments.port = parse_number(name, value)?,
+            "--seed" => arguments.seed = Some(parse_number(name, value)?),
+            "--goal" => arguments.goal = parse_number(name, value)?,
+            _ => return Err(format!("unknown argument: {name}")),
+        }
+    }
+    Ok(arguments)
+}
+
+fn write_message(stream: &mut TcpStream, transport: Transport, line: &str) -> io::Result<()> {
+    match transport {
+        Transport::Lines => {
+            stream.write_all(line.as_bytes())?;
+            stream.write_all(b"\n")?;
+            stream.flush()
+        }
+        Transport::WebSocket => websocket::write_text(stream, line),
+    }
+}
+
+// None when the client is gone
+fn read_message(reader: &mut BufReader<TcpStream>, writer: &Mutex<TcpStream>, transport: Transport) -> io::Result<Option<String>> {
+    match transport {
+        Transport::Lines => {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            Ok(Some(line.trim().to_string()))
+        }
+        Transport::WebSocket => websocket::read_text(reader).inspect_err(|e| {
+            // the client learns why before the connection ends
+            if websocket::is_message_too_big(e) {
+                if let Ok(mut writer) = writer.lock() {
+                    let _ = websocket::write_close(&mut *writer, websocket::CLOSE_MESSAGE_TOO_BIG);
+                }
+            }
+        }),
+    }
+}
+
+fn handle_message(relay: &Arc<Mutex<Relay>>, arguments: &Arguments, client_id: usize, joined: &mut Option<(String, String)>, line: &str) -> Result<(), String> {
+    let message = ClientMessage::parse(line)?;
+    let mut relay = relay.lock().map_err(|_| "the relay is down".to_string())?;
+    match (message, joined.clone()) {
+        (ClientMessage::Join { room, name, token }, None) => {
+            let seed = arguments.seed.unwrap_or_else(RandManager::entropy_seed);
+            let room_state = relay.rooms.entry(room.clone()).or_insert_with(|| Room::new(seed, arguments.goal));
+            let welcome = room_state.join(&name, token.as_deref())?;
+            let state = room_state.state_messages();
+            if let Some(client) = relay.clients.get_mut(&client_id) {
+                client.room = room.clone();
+            }
+            relay.send(client_id, &welcome);
+            for message in state {
+                relay.broadcast(&room, &message);
+            }
+            *joined = Some((room, name));
+            Ok(())
+        }
+        (ClientMessage::Join { .. }, Some(_)) => Err("already joined".to_string()),
+        (ClientMessage::Move(_), None) => Err("join a room first".to_string()),
+        (ClientMessage::Move(move_type), Some((room, name))) => {
+            let messages = relay.rooms.get_mut(&room).ok_or("unknown room".to_string())?.play(&name, move_type)?;
+            for message in messages {
+                relay.broadcast(&room, &message);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn handle_client(stream: TcpStream, client_id: usize, relay: Arc<Mutex<Relay>>, arguments: Arc<Arguments>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    // a websocket client starts with an http upgrade request
+    let mut first_line = String::new();
+    reader.read_line(&mut first_line)?;
+    let transport = if first_line.starts_with("GET ") {
+        websocket::accept(&mut reader, &mut writer)?;
+        Transport::WebSocket
+    } else {
+        Transport::Lines
+    };
+
+    let (sender, receiver) = mpsc::channel::<String>();
+    if let Ok(mut relay) = relay.lock() {
+        relay.clients.insert(client_id, Client { room: String::new(), sender });
+    }
+    // shared with the reading side, which may have to close the connection
+    let writer = Arc::new(Mutex::new(writer));
+    let broadcast_writer = writer.clone();
+    thread::spawn(move || {
+        for line in receiver {
+            let Ok(mut writer) = broadcast_writer.lock() else { break };
+            if write_message(&mut writer, transport, &line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut joined = None;
+    let mut next_line = match transport {
+        Transport::Lines => Some(first_line.trim().to_string()),
+        Transport::WebSocket => read_message(&mut reader, &writer, transport)?,
+    };
+    while let Some(line) = next_line {
+        if !line.is_empty() {
+            if let Err(e) = handle_message(&relay, &arguments, client_id, &mut joined, &line) {
+                if let Ok(relay) = relay.lock() {
+                    relay.send(client_id, &ServerMessage::Error(e));
+                }
+            }
+        }
+        next_line = read_message(&mut reader, &writer, transport)?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let raw_args: Vec<String> = env::args().skip(1).collect();
+    if raw_args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", usage());
+        return;
+    }
+    let arguments = match parse_arguments(&raw_args) {
+        Ok(arguments) => Arc::new(arguments),
+        Err(e) => {
+            eprintln!("{e}\n{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let listener = match TcpListener::bind(("127.0.0.1", arguments.port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("cannot listen on 127.0.0.1:{}: {e}", arguments.port);
+            process::exit(1);
+        }
+    };
+    println!("relay server listening on 127.0.0.1:{}", arguments.port);
+
+    let relay = Arc::new(Mutex::new(Relay::default()));
+    for (client_id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else { continue };
+        let relay = relay.clone();
+        let arguments = arguments.clone();
+        thread::spawn(move || {
+            // the connection is over on a read error too
+            let _ = handle_client(stream, client_id, relay.clone(), arguments);
+            if let Ok(mut relay) = relay.lock() {
+                relay.clients.remove(&client_id);
+            }
+        });
+    }
+}
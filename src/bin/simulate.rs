@@ -0,0 +1,172 @@
+// Plays N games of a variant with an automatic strategy and reports statistics
+//
+// usage: simulate [--games N] [--variant NAME] [--rules NAME] [--size ROWSxCOLUMNS] [--strategy NAME]
+//                 [--threads N] [--seed S] [--max-moves N] [--format table|csv|json]
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use rusty_2048::game::rand_manager::RandManager;
+use rusty_2048::game::simulation::{self, GameRecord, SimulationConfig, SimulationSummary, MAX_BOARD_SIDE};
+use rusty_2048::game::strategies::Strategy;
+use rusty_2048::text::{parse_number, parse_size, status_to_text};
+use rusty_2048::variants::{variant, variants_ids, Rules, VariantDef, VARIANTS};
+
+#[derive(Copy, Clone, PartialEq)]
+enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+struct Arguments {
+    config: SimulationConfig,
+    variant: &'static VariantDef,
+    format: OutputFormat,
+}
+
+fn usage() -> String {
+    format!(
+        "usage: simulate [--games N] [--variant {}] [--rules {}] [--size ROWSxCOLUMNS] [--strategy random|greedy|corner|ai] \
+         [--threads N] [--seed S] [--max-moves N] [--format table|csv|json]",
+        variants_ids().join("|"),
+        Rules::names().join("|")
+    )
+}
+
+fn parse_board_size(value: &str) -> Result<(usize, usize), String> {
+    let size = parse_size(value)?;
+    if size.0 < 2 || size.1 < 2 || size.0 > MAX_BOARD_SIDE || size.1 > MAX_BOARD_SIDE {
+        return Err(format!("the size shall be between 2x2 and {MAX_BOARD_SIDE}x{MAX_BOARD_SIDE}"));
+    }
+    Ok(size)
+}
+
+fn parse_arguments(args: &[String]) -> Result<Arguments, String> {
+    let mut def = &VARIANTS[0];
+    // the rules and the size of the variant unless given
+    let mut rules = None;
+    let mut size = None;
+    let mut format = OutputFormat::Table;
+    let mut config = SimulationConfig {
+        games: 100,
+        setup: def.setup,
+        rules: def.rules,
+        size: def.size,
+        strategy: Strategy::Corner,
+        threads: 1,
+        seed: RandManager::entropy_seed(),
+        max_moves: 100_000,
+    };
+
+    let mut iter = args.iter();
+    while let Some(name) = iter.next() {
+        let value = iter.next().ok_or(format!("missing value for {name}"))?;
+        match name.as_str() {
+            "--games" => config.games = parse_number(name, value)?,
+            "--variant" => def = variant(value).ok_or(format!("unknown variant: {value}"))?,
+            "--rules" => rules = Some(Rules::from_name(value).ok_or(format!("unknown rules: {value}"))?),
+            "--size" => size = Some(parse_board_size(value)?),
+            "--strategy" => config.strategy = Strategy::from_name(value).ok_or(format!("unknown strategy: {value}"))?,
+            "--threads" => config.threads = parse_number(name, value)?,
+            "--seed" => config.seed = parse_number(name, value)?,
+            "--max-moves" => config.max_moves = parse_number(name, value)?,
+            "--format" => {
+                format = match value.as_str() {
+                    "table" => OutputFormat::Table,
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    _ => return Err(format!("unknown format: {value}")),
+                }
+            }
+            _ => return Err(format!("unknown argument: {name}")),
+        }
+    }
+    config.setup = def.setup;
+    config.rules = rules.unwrap_or(def.rules);
+    config.size = size.unwrap_or(def.size);
+    Ok(Arguments { config, variant: def, format })
+}
+
+fn print_table(args: &Arguments, summary: &SimulationSummary) {
+    let config = &args.config;
+    println!("variant    {}  rules {}  size {}x{}  strategy {}  seed {}", args.variant.id, config.rules.name(), config.size.0, config.size.1, config.strategy.name(), config.seed);
+    println!();
+    println!("{:<14}{:>12}{:>12}{:>12}{:>12}", "", "min", "mean", "median", "max");
+    for (name, d) in [("score", &summary.scores), ("moves", &summary.moves)] {
+        println!("{:<14}{:>12.0}{:>12.1}{:>12.1}{:>12.0}", name, d.min, d.mean, d.median, d.max);
+    }
+    println!();
+    println!("{:<14}{:>12}{:>12}", "max tile", "games", "share");
+    for (tile, count) in &summary.max_tiles {
+        println!("{:<14}{:>12}{:>11.1}%", tile, count, 100.0 * *count as f64 / summary.games as f64);
+    }
+    println!();
+    println!("games         {}", summary.games);
+    println!("win rate      {:.1}%", 100.0 * summary.win_rate);
+    println!("moves/s       {:.0}", summary.moves_per_second);
+}
+
+fn print_csv(records: &[GameRecord]) {
+    println!("game,seed,score,max_tile,moves,status");
+    for (idx, r) in records.iter().enumerate() {
+        println!("{},{},{},{},{},{}", idx, r.seed, r.score, r.max_tile, r.moves, status_to_text(r.status));
+    }
+}
+
+fn print_json(args: &Arguments, summary: &SimulationSummary, records: &[GameRecord]) {
+    let config = &args.config;
+    let distribution = |d: &simulation::Distribution| {
+        format!("{{\"min\": {}, \"mean\": {:.3}, \"median\": {}, \"max\": {}}}", d.min, d.mean, d.median, d.max)
+    };
+    let max_tiles: Vec<String> = summary.max_tiles.iter().map(|(tile, count)| format!("\"{tile}\": {count}")).collect();
+    let games: Vec<String> = records
+        .iter()
+        .map(|r| {
+            format!(
+                "    {{\"seed\": {}, \"score\": {}, \"max_tile\": {}, \"moves\": {}, \"status\": \"{}\"}}",
+                r.seed, r.score, r.max_tile, r.moves, status_to_text(r.status)
+            )
+        })
+        .collect();
+
+    println!("{{");
+    println!("  \"variant\": \"{}\",", args.variant.id);
+    println!("  \"rules\": \"{}\",", config.rules.name());
+    println!("  \"size\": [{}, {}],", config.size.0, config.size.1);
+    println!("  \"strategy\": \"{}\",", config.strategy.name());
+    println!("  \"seed\": {},", config.seed);
+    println!("  \"games\": {},", summary.games);
+    println!("  \"win_rate\": {:.4},", summary.win_rate);
+    println!("  \"scores\": {},", distribution(&summary.scores));
+    println!("  \"moves\": {},", distribution(&summary.moves));
+    println!("  \"max_tiles\": {{{}}},", max_tiles.join(", "));
+    println!("  \"moves_per_second\": {:.1},", summary.moves_per_second);
+    println!("  \"records\": [\n{}\n  ]", games.join(",\n"));
+    println!("}}");
+}
+
+fn main() {
+    let raw_args: Vec<String> = env::args().skip(1).collect();
+    if raw_args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", usage());
+        return;
+    }
+    let args = match parse_arguments(&raw_args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let start = Instant::now();
+    let records = simulation::run_simulation(&args.config);
+    let summary = simulation::summarize(&records, start.elapsed().as_secs_f64());
+
+    match args.format {
+        OutputFormat::Table => print_table(&args, &summary),
+        OutputFormat::Csv => print_csv(&records),
+        OutputFormat::Json => print_json(&args, &summary, &records),
+    }
+}
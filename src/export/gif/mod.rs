@@ -0,0 +1,312 @@
+// GIF89a encoder for the replays: every frame has its own colour table and the animation loops
+// forever. The pixels are indices in the colours of their frame, packed with LZW.
+use std::collections::HashMap;
+
+// the codes of LZW are 12 bits at most
+const MAX_CODES: u16 = 4096;
+
+pub struct Frame {
+    // at most 256 colours
+    pub colours: Vec<[u8; 3]>,
+    // row by row, indices in the colours
+    pub pixels: Vec<u8>,
+    // in hundredths of a second
+    pub delay: u16,
+}
+
+// the codes go out from the low bits of the bytes
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn lzw(min_code_size: u8, pixels: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut code_size = min_code_size + 1;
+    let mut next = end + 1;
+    // (prefix code, pixel) -> code
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut writer = BitWriter { bytes: Vec::new(), buffer: 0, bits: 0 };
+    writer.write(clear, code_size);
+
+    let mut prefix: Option<u16> = None;
+    for &pixel in pixels {
+        let Some(code) = prefix else {
+            prefix = Some(pixel as u16);
+            continue;
+        };
+        if let Some(&longer) = table.get(&(code, pixel)) {
+            prefix = Some(longer);
+            continue;
+        }
+        writer.write(code, code_size);
+        if next < MAX_CODES {
+            table.insert((code, pixel), next);
+            next += 1;
+            // the decoder adds its code one step later
+            if next > (1 << code_size) && code_size < 12 {
+                code_size += 1;
+            }
+        } else {
+            // the table is full, both sides start over
+            writer.write(clear, code_size);
+            table.clear();
+            next = end + 1;
+            code_size = min_code_size + 1;
+        }
+        prefix = Some(pixel as u16);
+    }
+    if let Some(code) = prefix {
+        writer.write(code, code_size);
+        // the decoder adds its code for the last one too, the end code may need one more bit
+        if next == (1 << code_size) && code_size < 12 {
+            code_size += 1;
+        }
+    }
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+// the frames are compressed one at a time into the output, only its bytes grow with the replay
+// (the export still builds every state of the replay before drawing them)
+pub struct GifWriter {
+    width: u16,
+    height: u16,
+    out: Vec<u8>,
+}
+
+impl GifWriter {
+
+    pub fn new(width: u16, height: u16) -> GifWriter {
+        let mut out = b"GIF89a".to_vec();
+        push_u16(&mut out, width);
+        push_u16(&mut out, height);
+        // no global colour table, no background, square pixels
+        out.extend_from_slice(&[0, 0, 0]);
+        // loops forever
+        out.extend_from_slice(&[0x21, 0xff, 0x0b]);
+        out.extend_from_slice(b"NETSCAPE2.0");
+        out.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+        GifWriter { width, height, out }
+    }
+
+    pub fn frame(&mut self, frame: &Frame) -> Result<(), String> {
+        let pixels = self.width as usize * self.height as usize;
+        if frame.colours.is_empty() || frame.colours.len() > 256 {
+            return Err(format!("a frame has {} colours, 1 to 256 are allowed", frame.colours.len()));
+        }
+        if frame.pixels.len() != pixels {
+            return Err(format!("a frame has {} pixels instead of {pixels}", frame.pixels.len()));
+        }
+        if let Some(pixel) = frame.pixels.iter().find(|pixel| **pixel as usize >= frame.colours.len()) {
+            return Err(format!("a pixel has the colour {pixel} out of the {} colours", frame.colours.len()));
+        }
+        // the table has 2^depth colours
+        let depth = (1u8..=8).find(|depth| 1usize << depth >= frame.colours.len()).unwrap_or(8);
+        let out = &mut self.out;
+
+        // graphic control: the delay, no transparency
+        out.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        push_u16(out, frame.delay);
+        out.extend_from_slice(&[0x00, 0x00]);
+
+        // the frame covers the whole image with its local colour table
+        out.push(0x2c);
+        push_u16(out, 0);
+        push_u16(out, 0);
+        push_u16(out, self.width);
+        push_u16(out, self.height);
+        out.push(0x80 | (depth - 1));
+        for idx in 0..1usize << depth {
+            out.extend_from_slice(&frame.colours.get(idx).copied().unwrap_or([0, 0, 0]));
+        }
+
+        let min_code_size = depth.max(2);
+        out.push(min_code_size);
+        for block in lzw(min_code_size, &frame.pixels).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.out.push(0x3b);
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the codes of the stream back to the pixels, with the number of clear codes met
+    fn unlzw(min_code_size: u8, bytes: &[u8]) -> (Vec<u8>, usize) {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let mut code_size = min_code_size + 1;
+        let mut table: Vec<Vec<u8>> = (0..clear).map(|pixel| vec![pixel as u8]).collect();
+        table.resize(end + 1, Vec::new());
+        let (mut buffer, mut bits, mut bytes) = (0u32, 0u8, bytes.iter());
+        let (mut pixels, mut clears, mut previous) = (Vec::new(), 0, None::<Vec<u8>>);
+        loop {
+            while bits < code_size {
+                buffer |= (*bytes.next().expect("the stream ends before its end code") as u32) << bits;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << code_size) - 1)) as usize;
+            buffer >>= code_size;
+            bits -= code_size;
+            if code == clear {
+                clears += 1;
+                table.truncate(end + 1);
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return (pixels, clears);
+            }
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) if code == table.len() => [previous.clone(), vec![previous[0]]].concat(),
+                _ => panic!("unknown code {code}"),
+            };
+            if let Some(previous) = previous {
+                if table.len() < MAX_CODES as usize {
+                    table.push([previous, vec![entry[0]]].concat());
+                }
+                if table.len() == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            pixels.extend_from_slice(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    // splitmix steps, pixels without long runs fill the table fast
+    fn noise(count: usize, colours: u64) -> Vec<u8> {
+        let mut state = 7u64;
+        (0..count)
+            .map(|_| {
+                state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                ((z ^ (z >> 31)) % colours) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        for pixels in [vec![], vec![3], vec![0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 0, 1, 2, 3], vec![1; 1000], noise(500, 4)] {
+            assert_eq!(unlzw(2, &lzw(2, &pixels)), (pixels, 1));
+        }
+        // every length ends once at the code size changes
+        for count in 0..300 {
+            let pixels = noise(count, 4);
+            assert_eq!(unlzw(2, &lzw(2, &pixels)).0, pixels, "{count} pixels");
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip_across_the_table_reset() {
+        let pixels = noise(60_000, 256);
+        let (decoded, clears) = unlzw(8, &lzw(8, &pixels));
+        assert!(clears > 2, "{clears}");
+        assert_eq!(decoded, pixels);
+        let pixels = noise(60_000, 5);
+        let (decoded, clears) = unlzw(3, &lzw(3, &pixels));
+        assert!(clears > 1, "{clears}");
+        assert_eq!(decoded, pixels);
+        // the streams ending around the first reset
+        let pixels = noise(5000, 256);
+        let reset = (0..pixels.len()).collect::<Vec<_>>().partition_point(|count| unlzw(8, &lzw(8, &pixels[..*count])).1 == 1);
+        assert!(reset < pixels.len());
+        for count in reset - 3..reset + 3 {
+            assert_eq!(unlzw(8, &lzw(8, &pixels[..count])), (pixels[..count].to_vec(), if count < reset { 1 } else { 2 }));
+        }
+    }
+
+    // the frames of the file: their colours and their pixels
+    fn read_gif(gif: &[u8]) -> Vec<(Vec<[u8; 3]>, Vec<u8>)> {
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif.last(), Some(&0x3b));
+        let mut idx = 13 + 19;
+        let mut frames = Vec::new();
+        while gif[idx] == 0x21 {
+            let colours_count = 1 << ((gif[idx + 8 + 9] & 7) + 1);
+            idx += 8 + 10;
+            let colours = gif[idx..idx + 3 * colours_count].chunks(3).map(|c| [c[0], c[1], c[2]]).collect();
+            idx += 3 * colours_count;
+            let min_code_size = gif[idx];
+            idx += 1;
+            let mut stream = Vec::new();
+            while gif[idx] != 0 {
+                stream.extend_from_slice(&gif[idx + 1..idx + 1 + gif[idx] as usize]);
+                idx += 1 + gif[idx] as usize;
+            }
+            idx += 1;
+            frames.push((colours, unlzw(min_code_size, &stream).0));
+        }
+        assert_eq!(idx, gif.len() - 1);
+        frames
+    }
+
+    #[test]
+    fn gif_frames_read_back() {
+        let mut writer = GifWriter::new(30, 20);
+        let small = Frame { colours: vec![[255, 0, 0], [0, 0, 255], [1, 2, 3]], pixels: noise(600, 3), delay: 40 };
+        let large = Frame { colours: (0..=255).map(|c| [c, c, c]).collect(), pixels: noise(600, 256), delay: 200 };
+        writer.frame(&small).unwrap();
+        writer.frame(&large).unwrap();
+        let frames = read_gif(&writer.finish());
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].0[..3], small.colours[..]);
+        assert_eq!(frames[0].0[3], [0, 0, 0]);
+        assert_eq!(frames[0].1, small.pixels);
+        assert_eq!((&frames[1].0, &frames[1].1), (&large.colours, &large.pixels));
+    }
+
+    #[test]
+    fn invalid_frames_are_errors() {
+        let mut writer = GifWriter::new(2, 2);
+        let frame = |colours: usize, pixels: Vec<u8>| Frame { colours: vec![[0, 0, 0]; colours], pixels, delay: 10 };
+        assert!(writer.frame(&frame(0, vec![0; 4])).is_err());
+        assert!(writer.frame(&frame(257, vec![0; 4])).is_err());
+        assert!(writer.frame(&frame(2, vec![0; 3])).is_err());
+        assert!(writer.frame(&frame(2, vec![0; 5])).is_err());
+        assert!(writer.frame(&frame(2, vec![0, 1, 2, 1])).is_err());
+        assert!(writer.frame(&frame(2, vec![0, 1, 1, 0])).is_ok());
+        assert_eq!(read_gif(&writer.finish()).len(), 1);
+    }
+}
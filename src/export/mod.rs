@@ -0,0 +1,265 @@
+// Export of the boards to image files, without a browser: a standalone svg of a position and an
+// animated gif of a replay, one frame by move. The colours come from the themes of the UI, the
+// gif draws the numbers with a small pixel font since it has no fonts.
+pub mod gif;
+
+use crate::game::game_variants::game_variant_1::{SpecificElementType, SpecificGame, VariantRules};
+use crate::theme::Theme;
+use gif::{Frame, GifWriter};
+
+// sizes in pixels of the svg, it scales anyway
+const SVG_CELL: usize = 64;
+const SVG_GAP: usize = 8;
+// the gif frames are raster, smaller cells keep the files small
+const GIF_CELL: usize = 40;
+const GIF_GAP: usize = 5;
+// the last frame of a replay stays longer, in hundredths of a second
+const GIF_LAST_DELAY: u16 = 200;
+pub const DEFAULT_GIF_DELAY: u16 = 40;
+
+// 3x5 glyphs row by row from the high bit, the star is the joker
+const GLYPHS: [(char, u16); 11] = [
+    ('0', 0b111_101_101_101_111),
+    ('1', 0b010_110_010_010_111),
+    ('2', 0b111_001_111_100_111),
+    ('3', 0b111_001_111_001_111),
+    ('4', 0b101_101_111_001_001),
+    ('5', 0b111_100_111_001_111),
+    ('6', 0b111_100_111_101_111),
+    ('7', 0b111_001_001_001_001),
+    ('8', 0b111_101_111_101_111),
+    ('9', 0b111_101_111_001_111),
+    ('*', 0b101_010_111_010_101),
+];
+
+// the text of a cell in the svg, as on the board of the UI
+fn cell_label(element: Option<SpecificElementType>) -> String {
+    match element {
+        Some(SpecificElementType::Some(a)) | Some(SpecificElementType::Timed(a, _)) => a.to_string(),
+        Some(SpecificElementType::Joker) => "★".to_string(),
+        Some(SpecificElementType::Bomb(countdown)) => format!("💣{countdown}"),
+        _ => String::new(),
+    }
+}
+
+// the text of a cell in the gif, only digits and the star
+fn glyph_label(element: Option<SpecificElementType>) -> String {
+    match element {
+        Some(SpecificElementType::Some(a)) | Some(SpecificElementType::Timed(a, _)) => a.to_string(),
+        Some(SpecificElementType::Joker) => "*".to_string(),
+        Some(SpecificElementType::Bomb(countdown)) => countdown.to_string(),
+        _ => String::new(),
+    }
+}
+
+// `#rrggbb`, `#rgb` and `hsl(h, s%, l%)`, the colours of the themes
+pub fn css_to_rgb(colour: &str) -> Result<[u8; 3], String> {
+    let colour = colour.trim();
+    let invalid = || format!("invalid colour: {colour}");
+    if let Some(hex) = colour.strip_prefix('#') {
+        let digits = hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect::<Option<Vec<u8>>>().ok_or_else(invalid)?;
+        return match digits.as_slice() {
+            [r, g, b] => Ok([r * 17, g * 17, b * 17]),
+            [r1, r2, g1, g2, b1, b2] => Ok([r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2]),
+            _ => Err(invalid()),
+        };
+    }
+    let values = colour.strip_prefix("hsl(").and_then(|rest| rest.strip_suffix(')')).ok_or_else(invalid)?;
+    let values = values
+        .split(',')
+        .map(|value| value.trim().trim_end_matches('%').parse::<f64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<f64>, String>>()?;
+    let [hue, saturation, lightness] = values.as_slice() else {
+        return Err(invalid());
+    };
+    let (saturation, lightness) = (saturation / 100.0, lightness / 100.0);
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let channel = |n: f64| {
+        let k = (n + hue / 30.0) % 12.0;
+        let value = lightness - chroma / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0);
+        (value * 255.0).round() as u8
+    };
+    Ok([channel(0.0), channel(8.0), channel(4.0)])
+}
+
+// the css font of the theme for a file out of the page
+fn svg_font(theme: &Theme) -> &'static str {
+    if theme.font == "inherit" { "Arial, Helvetica, sans-serif" } else { theme.font }
+}
+
+fn board_pixels(size: (usize, usize), cell: usize, gap: usize) -> (usize, usize) {
+    (size.1 * cell + (size.1 + 1) * gap, size.0 * cell + (size.0 + 1) * gap)
+}
+
+// a standalone svg of the board, in the colours and the font of the theme
+pub fn board_svg<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, theme: &Theme) -> String {
+    let (width, height) = board_pixels(g.board_size, SVG_CELL, SVG_GAP);
+    let outline = theme.outline_stroke().map(|(width, colour)| format!(" stroke=\"{colour}\" stroke-width=\"{width}\"")).unwrap_or_default();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"{width}\" height=\"{height}\" rx=\"8\" fill=\"{}\"/>\n",
+        theme.board
+    );
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            let element = g.game_variant_data.array[id_x][id_y];
+            let (x, y) = (SVG_GAP + id_y * (SVG_CELL + SVG_GAP), SVG_GAP + id_x * (SVG_CELL + SVG_GAP));
+            if let Some(texture) = theme.texture(element) {
+                let pixel = SVG_CELL / 8;
+                for (idx, colour) in texture.cells().into_iter().enumerate() {
+                    svg += &format!("<rect x=\"{}\" y=\"{}\" width=\"{pixel}\" height=\"{pixel}\" fill=\"{colour}\"/>\n", x + idx % 8 * pixel, y + idx / 8 * pixel);
+                }
+                continue;
+            }
+            let (background, text) = theme.cell_colours::<R>(element);
+            let stroke = if element.is_some() { outline.as_str() } else { "" };
+            svg += &format!("<rect x=\"{x}\" y=\"{y}\" width=\"{SVG_CELL}\" height=\"{SVG_CELL}\" rx=\"4\" fill=\"{background}\"{stroke}/>\n");
+            let label = cell_label(element);
+            if !label.is_empty() {
+                let font_size = match label.chars().count() {
+                    0..=3 => 28,
+                    4 => 24,
+                    5 => 20,
+                    _ => 16,
+                };
+                svg += &format!(
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-family=\"{}\" font-size=\"{font_size}\" font-weight=\"bold\" fill=\"{text}\">{label}</text>\n",
+                    x + SVG_CELL / 2,
+                    y + SVG_CELL / 2,
+                    svg_font(theme)
+                );
+            }
+        }
+    }
+    svg += "</svg>\n";
+    svg
+}
+
+// a frame being drawn, its colours are added as they come
+struct Canvas {
+    width: usize,
+    colours: Vec<[u8; 3]>,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+
+    fn colour(&mut self, css: &str) -> Result<u8, String> {
+        let rgb = css_to_rgb(css)?;
+        if let Some(idx) = self.colours.iter().position(|c| *c == rgb) {
+            return Ok(idx as u8);
+        }
+        if self.colours.len() == 256 {
+            return Err("a frame has more than 256 colours".to_string());
+        }
+        self.colours.push(rgb);
+        Ok((self.colours.len() - 1) as u8)
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, colour: u8) {
+        for row in y..y + height {
+            self.pixels[row * self.width + x..row * self.width + x + width].fill(colour);
+        }
+    }
+
+    // the glyphs scaled to fit the cell, centered
+    fn text(&mut self, x: usize, y: usize, cell: usize, label: &str, colour: u8) {
+        let chars = label.chars().count();
+        let scale = (cell * 7 / 10 / (4 * chars - 1)).min(cell * 2 / 5 / 5).max(1);
+        let (text_width, text_height) = ((4 * chars - 1) * scale, 5 * scale);
+        let (left, top) = (x + cell.saturating_sub(text_width) / 2, y + cell.saturating_sub(text_height) / 2);
+        for (idx, c) in label.chars().enumerate() {
+            let Some((_, bits)) = GLYPHS.iter().find(|(glyph, _)| *glyph == c) else {
+                continue;
+            };
+            for bit in 0..15 {
+                let pixel_x = left + (idx * 4 + bit % 3) * scale;
+                // the longest numbers are cut at the border of the cell
+                if bits & (1 << (14 - bit)) != 0 && pixel_x + scale <= x + cell {
+                    self.fill(pixel_x, top + bit / 3 * scale, scale, scale, colour);
+                }
+            }
+        }
+    }
+}
+
+fn board_frame<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, theme: &Theme, delay: u16) -> Result<Frame, String> {
+    let (width, height) = board_pixels(g.board_size, GIF_CELL, GIF_GAP);
+    let mut canvas = Canvas { width, colours: Vec::new(), pixels: vec![0; width * height] };
+    let board = canvas.colour(theme.board)?;
+    canvas.fill(0, 0, width, height, board);
+    let outline = match theme.outline_stroke() {
+        Some((stroke, colour)) => Some((stroke as usize, canvas.colour(colour)?)),
+        None => None,
+    };
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            let element = g.game_variant_data.array[id_x][id_y];
+            let (x, y) = (GIF_GAP + id_y * (GIF_CELL + GIF_GAP), GIF_GAP + id_x * (GIF_CELL + GIF_GAP));
+            if let Some(texture) = theme.texture(element) {
+                let pixel = GIF_CELL / 8;
+                for (idx, css) in texture.cells().into_iter().enumerate() {
+                    let colour = canvas.colour(css)?;
+                    canvas.fill(x + idx % 8 * pixel, y + idx / 8 * pixel, pixel, pixel, colour);
+                }
+                continue;
+            }
+            let (background, text) = theme.cell_colours::<R>(element);
+            let (background, text) = (canvas.colour(&background)?, canvas.colour(&text)?);
+            match outline {
+                Some((stroke, colour)) if element.is_some() => {
+                    canvas.fill(x, y, GIF_CELL, GIF_CELL, colour);
+                    canvas.fill(x + stroke, y + stroke, GIF_CELL - 2 * stroke, GIF_CELL - 2 * stroke, background);
+                }
+                _ => canvas.fill(x, y, GIF_CELL, GIF_CELL, background),
+            }
+            let label = glyph_label(element);
+            if !label.is_empty() {
+                canvas.text(x, y, GIF_CELL, &label, text);
+            }
+        }
+    }
+    Ok(Frame { colours: canvas.colours, pixels: canvas.pixels, delay })
+}
+
+// an animated gif of the boards, one frame every delay (in hundredths of a second), the last
+// board stays longer before the loop
+pub fn replay_gif<const C_W: usize, const C_H: usize, R: VariantRules>(states: &[SpecificGame<C_W, C_H, R>], theme: &Theme, delay: u16) -> Result<Vec<u8>, String> {
+    let first = states.first().ok_or("the replay has no board")?;
+    let (width, height) = board_pixels(first.board_size, GIF_CELL, GIF_GAP);
+    let mut writer = GifWriter::new(width as u16, height as u16);
+    for (idx, g) in states.iter().enumerate() {
+        let delay = if idx + 1 == states.len() { GIF_LAST_DELAY.max(delay) } else { delay };
+        writer.frame(&board_frame(g, theme, delay)?)?;
+    }
+    Ok(writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colours() {
+        assert_eq!(css_to_rgb("#fff"), Ok([255, 255, 255]));
+        assert_eq!(css_to_rgb("#0a3"), Ok([0, 170, 51]));
+        assert_eq!(css_to_rgb("#edc22e"), Ok([237, 194, 46]));
+        assert_eq!(css_to_rgb(" #776E65 "), Ok([119, 110, 101]));
+    }
+
+    #[test]
+    fn hsl_colours() {
+        assert_eq!(css_to_rgb("hsl(0, 100%, 50%)"), Ok([255, 0, 0]));
+        assert_eq!(css_to_rgb("hsl(120, 100%, 25%)"), Ok([0, 128, 0]));
+        assert_eq!(css_to_rgb("hsl(240,100%,50%)"), Ok([0, 0, 255]));
+        assert_eq!(css_to_rgb("hsl(300, 0%, 50%)"), Ok([128, 128, 128]));
+        assert_eq!(css_to_rgb("hsl(30, 100%, 100%)"), Ok([255, 255, 255]));
+    }
+
+    #[test]
+    fn invalid_colours() {
+        for colour in ["", "#", "#ggg", "#ffff", "#1234567", "red", "rgb(1, 2, 3)", "hsl(1, 2%)", "hsl(a, 1%, 2%)", "hsl(10, 20%, 30%", "hsl(1, 2%, 3%, 4%)"] {
+            assert!(css_to_rgb(colour).is_err(), "{colour}");
+        }
+    }
+}
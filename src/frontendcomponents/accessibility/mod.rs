@@ -0,0 +1,172 @@
+// Accessibility of the boards: the labels of the cells, the announcement of every move for the
+// screen readers (built from the traces of the move) and the keyboard focus of the game
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+
+use crate::game::game_variants::game_variant_1::{AllowedMoves, GameStatus, RetainerMergerInfo, SpecificElementType, SpecificGame, VariantRules};
+
+// the positions are spoken from 1
+fn position(idx: (usize, usize)) -> String {
+    format!("row {} column {}", idx.0 + 1, idx.1 + 1)
+}
+
+fn element_label(element: SpecificElementType) -> String {
+    match element {
+        SpecificElementType::Some(a) | SpecificElementType::Timed(a, _) => a.to_string(),
+        SpecificElementType::Block(1) => "block, 1 hit left".to_string(),
+        SpecificElementType::Block(hp) => format!("block, {hp} hits left"),
+        SpecificElementType::BlockFixed => "fixed block".to_string(),
+        SpecificElementType::Joker => "joker".to_string(),
+        SpecificElementType::Bomb(countdown) => format!("bomb, explodes in {countdown} moves"),
+    }
+}
+
+pub fn cell_label<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, idx: (usize, usize)) -> String {
+    let content = match g.game_variant_data.array[idx.0][idx.1] {
+        None => "empty".to_string(),
+        Some(element @ SpecificElementType::Timed(_, age)) => match g.game_variant_data.spawn_config.timed.moves_left(age) {
+            Some(moves_left) => format!("{}, expires in {moves_left} moves", element_label(element)),
+            None => element_label(element),
+        },
+        Some(element) => element_label(element),
+    };
+    format!("{}: {content}", position(idx))
+}
+
+fn direction(move_type: AllowedMoves) -> &'static str {
+    match move_type {
+        AllowedMoves::LEFT => "Left",
+        AllowedMoves::RIGHT => "Right",
+        AllowedMoves::UP => "Up",
+        AllowedMoves::DOWN => "Down",
+    }
+}
+
+fn merge_text(a: SpecificElementType, b: SpecificElementType, merged: Option<SpecificElementType>) -> String {
+    let result = merged.map(element_label).unwrap_or("nothing".to_string());
+    match (a.number(), b.number()) {
+        (Some(x), Some(y)) if x == y => format!("merged two {x}s into {result}"),
+        _ => format!("merged {} and {} into {result}", element_label(a), element_label(b)),
+    }
+}
+
+// what the last move did, e.g. "Left: merged two 8s into 16, new 2 at row 3 column 1. Score 120."
+pub fn move_announcement<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, move_type: AllowedMoves, moved: bool) -> String {
+    if !moved {
+        return format!("{}: nothing moved.", direction(move_type));
+    }
+    let mut merges = Vec::new();
+    let mut spawns = Vec::new();
+    let mut cleared = 0;
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            let element = g.game_variant_data.array[id_x][id_y];
+            match (g.game_variant_data.mergers_infos[id_x][id_y], element) {
+                (Some(RetainerMergerInfo::Merged((_, a), (_, b))), _) => merges.push(merge_text(a, b, element)),
+                (Some(RetainerMergerInfo::Detonated(_, _)) | Some(RetainerMergerInfo::Destroyed(_)), _) => cleared += 1,
+                (None | Some(RetainerMergerInfo::None), Some(spawned)) => spawns.push(format!("new {} at {}", element_label(spawned), position((id_x, id_y)))),
+                _ => {}
+            }
+        }
+    }
+    let mut parts = merges;
+    if cleared > 0 {
+        parts.push(format!("{cleared} cleared"));
+    }
+    parts.extend(spawns);
+    if parts.is_empty() {
+        parts.push("tiles slid".to_string());
+    }
+    let mut text = format!("{}: {}. Score {}.", direction(move_type), parts.join(", "), g.game_variant_data.score);
+    match g.game_status {
+        GameStatus::END_SUCCESS => text.push_str(" You win!"),
+        GameStatus::END_FAIL => text.push_str(" Game over."),
+        GameStatus::INPROGRESS => {}
+    }
+    text
+}
+
+// keyboard focus of a game container, given on mount and back after the clicks on the buttons
+#[derive(Copy, Clone, PartialEq)]
+pub struct GameFocus {
+    element: Signal<Option<Rc<MountedData>>>,
+}
+
+pub fn use_game_focus() -> GameFocus {
+    GameFocus { element: use_signal(|| None) }
+}
+
+impl GameFocus {
+
+    pub fn mounted(&mut self, evt: MountedEvent) {
+        self.element.set(Some(evt.data()));
+        self.focus();
+    }
+
+    pub fn focus(&self) {
+        if let Some(element) = self.element.peek().clone() {
+            spawn(async move {
+                let _ = element.set_focus(true).await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::TimedTiles;
+    use crate::game::levels::Level;
+
+    // the board of a level without starting tiles, the spawns are the given values
+    fn game(board: &str, spawns: &str) -> SpecificGame<4, 4> {
+        let rows = board.lines().count();
+        let columns = board.lines().next().unwrap().split_whitespace().count();
+        let level = Level::parse(&format!("size {rows}x{columns}\nstart_tiles 0\nspawns {spawns}\nboard\n{board}")).unwrap();
+        level.build(3).unwrap()
+    }
+
+    fn announce(board: &str, spawns: &str, move_type: AllowedMoves) -> String {
+        let mut g = game(board, spawns);
+        let moves_before = g.game_variant_data.moves;
+        g.move_generic(move_type);
+        move_announcement(&g, move_type, g.game_variant_data.moves != moves_before)
+    }
+
+    #[test]
+    fn merge_and_spawn_are_announced() {
+        assert_eq!(
+            announce("8 8 4\n2 4 2", "2", AllowedMoves::LEFT),
+            "Left: merged two 8s into 16, new 2 at row 1 column 3. Score 16."
+        );
+        assert_eq!(announce("2 . .\n4 8 .", "", AllowedMoves::RIGHT), "Right: tiles slid. Score 0.");
+    }
+
+    #[test]
+    fn nothing_moved_is_announced() {
+        assert_eq!(announce("8 4\n2 16", "2", AllowedMoves::LEFT), "Left: nothing moved.");
+    }
+
+    #[test]
+    fn detonation_is_announced() {
+        assert_eq!(announce("2 @5 .\n8 16 32", "", AllowedMoves::LEFT), "Left: 3 cleared. Score 24.");
+    }
+
+    #[test]
+    fn fixed_blocks_are_not_new() {
+        assert_eq!(announce("2 #\n. 4", "2", AllowedMoves::DOWN), "Down: new 2 at row 1 column 1. Score 0.");
+    }
+
+    #[test]
+    fn cells_are_labelled() {
+        let mut g = game("B1 B3\n# .", "");
+        g.game_variant_data.array[1][1] = Some(SpecificElementType::Timed(4, 1));
+        assert_eq!(cell_label(&g, (0, 0)), "row 1 column 1: block, 1 hit left");
+        assert_eq!(cell_label(&g, (0, 1)), "row 1 column 2: block, 3 hits left");
+        assert_eq!(cell_label(&g, (1, 0)), "row 2 column 1: fixed block");
+        assert_eq!(cell_label(&g, (1, 1)), "row 2 column 2: 4");
+        g.game_variant_data.spawn_config.timed = TimedTiles::Decaying(5);
+        assert_eq!(cell_label(&g, (1, 1)), "row 2 column 2: 4, expires in 4 moves");
+    }
+}
@@ -0,0 +1,150 @@
+// Adversarial mode page: the player moves with the bound keys, then the spawner (a second human
+// clicking a highlighted cell, or the evil ai) places the new tile
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::accessibility::use_game_focus;
+use crate::frontendcomponents::board_view;
+use crate::input::{GameAction, InputEvent};
+use crate::storage::settings::Settings;
+use crate::game::adversary::{AdversarialGame, SpawnChoice, Turn};
+use crate::game::game_variants::game_variant_1::{AllowedMoves, GameStatus, SpecificElementType};
+use crate::game::rand_manager::RandManager;
+
+const ADVERSARY_SIZE: (usize, usize) = (4, 4);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Spawner {
+    Human,
+    EvilAi,
+}
+
+fn new_game() -> Result<AdversarialGame<4, 4>, String> {
+    AdversarialGame::new(ADVERSARY_SIZE, RandManager::entropy_seed())
+}
+
+fn element_label(element: SpecificElementType) -> String {
+    match element {
+        SpecificElementType::Block(_) => "Block".to_string(),
+        _ => element.number().map(|a| a.to_string()).unwrap_or_default(),
+    }
+}
+
+#[component]
+pub fn adversary_2048() -> Element {
+    let mut game = match use_hook(new_game) {
+        Ok(first_game) => use_signal(move || first_game),
+        Err(e) => {
+            return rsx! {
+                p { class: "pt-20 text-center text-[#776e65]", "The game cannot start: {e}" }
+            };
+        }
+    };
+    let mut restart_error = use_signal(|| None::<String>);
+    let mut spawner = use_signal(|| Spawner::EvilAi);
+    let mut selected = use_signal(|| SpecificElementType::Some(2));
+    let mut game_focus = use_game_focus();
+
+    let mut evil_turn = move || {
+        let choice = game.read().evil_spawn();
+        if let Some(choice) = choice {
+            let _ = game.write().spawn(choice);
+        }
+    };
+
+    let mut play = move |move_type: AllowedMoves| {
+        if game.write().player_move(move_type) && spawner() == Spawner::EvilAi {
+            evil_turn();
+        }
+    };
+
+    let status = game.read().game.game_status;
+    let turn = game.read().turn;
+    let candidates = game.read().candidate_cells();
+    let elements = game.read().candidate_elements();
+    // the selected value may be a block that is not available anymore
+    let selected_element = if elements.contains(&selected()) { selected() } else { elements[0] };
+
+    let settings = use_context::<Signal<Settings>>();
+
+    rsx! {
+        div {
+            tabindex: "0",
+            class: "flex flex-col items-center min-h-[100dvh] pt-20 text-[#776e65]",
+            id: "game-container",
+            onmounted: move |evt| game_focus.mounted(evt),
+
+            // only the moves of the player's bindings, the other actions are not offered here
+            onkeydown: move |evt| {
+                if let Some(GameAction::Move(move_type)) = settings.read().bindings.resolve(&InputEvent::Key(evt.key().to_string())) {
+                    evt.prevent_default();
+                    play(move_type);
+                }
+            },
+
+            h1 { class: "text-4xl font-bold mb-2", "Adversary" }
+            div { class: "flex flex-wrap gap-4 mb-4 items-center",
+                label { "Spawner "
+                    select {
+                        onchange: move |evt| {
+                            spawner.set(if evt.value() == "human" { Spawner::Human } else { Spawner::EvilAi });
+                            // the ai takes over a pending spawn
+                            if spawner() == Spawner::EvilAi && game.read().turn == Turn::Spawner {
+                                evil_turn();
+                            }
+                        },
+                        option { value: "evil_ai", selected: spawner() == Spawner::EvilAi, "Evil AI" }
+                        option { value: "human", selected: spawner() == Spawner::Human, "Human" }
+                    }
+                }
+                button {
+                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                    onclick: move |_| {
+                        match new_game() {
+                            Ok(g) => {
+                                game.set(g);
+                                restart_error.set(None);
+                            }
+                            Err(e) => restart_error.set(Some(e)),
+                        }
+                        game_focus.focus();
+                    },
+                    "Restart"
+                }
+                div { "Score : {game.read().game.game_variant_data.score}" }
+                div { "Blocks left : {game.read().blocks_left}" }
+            }
+            if let Some(e) = restart_error() {
+                p { class: "mb-4 text-red-700", "The game cannot restart: {e}" }
+            }
+
+            if status != GameStatus::INPROGRESS {
+                p { class: "mb-4 text-2xl font-bold",
+                    if status == GameStatus::END_SUCCESS { "The player wins!" } else { "The spawner wins!" }
+                }
+            } else if turn == Turn::Player {
+                p { class: "mb-4", "Player's turn: move with {settings.read().bindings.move_keys()}." }
+            } else {
+                div { class: "mb-4 flex gap-2 items-center",
+                    "Spawner's turn: choose a tile and click a highlighted cell."
+                    for element in elements {
+                        button {
+                            class: if element == selected_element { "px-3 py-1 rounded bg-[#edc22e] text-white" } else { "px-3 py-1 rounded bg-[#eee4da]" },
+                            onclick: move |_| selected.set(element),
+                            "{element_label(element)}"
+                        }
+                    }
+                }
+            }
+
+            board_view {
+                g_signal: game.map(|g| &g.game),
+                candidates: if spawner() == Spawner::Human { candidates } else { Vec::new() },
+                on_cell_click: move |idx| {
+                    if spawner() == Spawner::Human {
+                        let _ = game.write().spawn(SpawnChoice { idx, element: selected_element });
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Animations of the tiles after a move, driven by the traces of the move (mergers_infos): the
+// moved tiles slide from their old cell, the merged ones pop at the end of the slide and the
+// spawned ones fade in. The keyframes are in main.css.
+use crate::game::game_variants::game_variant_1::{RetainerMergerInfo, SpecificGame, VariantRules};
+
+// moves kept while an animation runs, the others are dropped
+pub const MAX_QUEUED_MOVES: usize = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TileAnimation {
+    Still,
+    // offset (rows, columns) of the old cell
+    Slide(isize, isize),
+    // offset of the farthest merged tile
+    Merge(isize, isize),
+    Spawn,
+}
+
+fn offset(from: (usize, usize), to: (usize, usize)) -> (isize, isize) {
+    (from.0 as isize - to.0 as isize, from.1 as isize - to.1 as isize)
+}
+
+pub fn tile_animation<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, idx: (usize, usize)) -> TileAnimation {
+    if g.game_variant_data.array[idx.0][idx.1].is_none() {
+        return TileAnimation::Still;
+    }
+    match g.game_variant_data.mergers_infos[idx.0][idx.1] {
+        None | Some(RetainerMergerInfo::None) => TileAnimation::Spawn,
+        Some(RetainerMergerInfo::NotMerged(old_idx, _)) if old_idx != idx => {
+            let (rows, columns) = offset(old_idx, idx);
+            TileAnimation::Slide(rows, columns)
+        }
+        Some(RetainerMergerInfo::Merged((idx_a, _), (idx_b, _))) => {
+            let (a, b) = (offset(idx_a, idx), offset(idx_b, idx));
+            let (rows, columns) = if a.0.abs() + a.1.abs() >= b.0.abs() + b.1.abs() { a } else { b };
+            TileAnimation::Merge(rows, columns)
+        }
+        _ => TileAnimation::Still,
+    }
+}
+
+// the css animations started on the board, a merge slides then pops
+pub fn animations_count<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>) -> usize {
+    (0..g.board_size.0)
+        .flat_map(|id_x| (0..g.board_size.1).map(move |id_y| (id_x, id_y)))
+        .map(|idx| match tile_animation(g, idx) {
+            TileAnimation::Still => 0,
+            TileAnimation::Slide(_, _) | TileAnimation::Spawn => 1,
+            TileAnimation::Merge(_, _) => 2,
+        })
+        .sum()
+}
+
+pub fn animation_class(animation: TileAnimation) -> &'static str {
+    match animation {
+        TileAnimation::Still => "",
+        TileAnimation::Slide(_, _) => "tile-slide z-10",
+        TileAnimation::Merge(_, _) => "tile-merge z-10",
+        TileAnimation::Spawn => "tile-spawn",
+    }
+}
+
+// the slides start from the old cell, one cell is the tile size plus the grid gap
+pub fn animation_style(animation: TileAnimation) -> String {
+    match animation {
+        TileAnimation::Slide(rows, columns) | TileAnimation::Merge(rows, columns) => format!("--slide-x: {columns}; --slide-y: {rows};"),
+        _ => String::new(),
+    }
+}
+
+// the other animations of the board (the flash of the cleared cells) are not waited for
+pub fn is_tile_animation(name: &str) -> bool {
+    name.starts_with("tile-")
+}
@@ -0,0 +1,143 @@
+// Puzzle campaign: the level select page and the play page of a level of the bundled pack
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::rectangular_board;
+use crate::game::game_variants::game_variant_1::{GameStatus, Goal, SpecificGame};
+use crate::game::levels::{Level, CAMPAIGN, LEVEL_CAPACITY};
+use crate::game::merge_rules::ClassicMergeRule;
+use crate::game::rand_manager::RandManager;
+use crate::storage::campaign::{CampaignProgress, MAX_STARS};
+use crate::storage::default_storage;
+use crate::Route;
+
+// the bundled pack is checked by the tests of the levels, a broken pack shows no level
+fn campaign_levels() -> Vec<Level> {
+    Level::parse_pack(CAMPAIGN).unwrap_or_default()
+}
+
+pub fn goal_description(goal: Goal) -> String {
+    match goal {
+        Goal::ReachTile(a) => format!("Reach the {a} tile"),
+        Goal::ClearBlocks => "Break all the blocks".to_string(),
+        Goal::Score(a) => format!("Score {a} points"),
+    }
+}
+
+fn stars_label(stars: u8) -> String {
+    (1..=MAX_STARS).map(|a| if a <= stars { '★' } else { '☆' }).collect()
+}
+
+#[component]
+pub fn campaign_select() -> Element {
+    let levels = use_hook(campaign_levels);
+    let progress = use_context::<Signal<CampaignProgress>>();
+    let completed = (0..levels.len()).filter(|idx| progress.read().stars(*idx).is_some()).count();
+    let total_stars: u32 = (0..levels.len()).filter_map(|idx| progress.read().stars(idx)).map(u32::from).sum();
+
+    rsx! {
+        div { class: "pt-20 max-w-6xl mx-auto px-6 min-h-[100dvh] text-[#776e65]",
+            section { class: "text-center mb-8",
+                h1 { class: "text-4xl font-bold mb-4", "Puzzle campaign" }
+                p { "{completed} / {levels.len()} levels completed, {total_stars} / {3 * levels.len()} stars" }
+            }
+            section { class: "grid gap-6 sm:grid-cols-2 lg:grid-cols-4",
+                for (idx, level) in levels.iter().enumerate() {
+                    Link {
+                        to: Route::CampaignLevel { level: idx },
+                        class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
+
+                        h2 { class: "text-xl font-semibold", "{idx + 1}. {level.name}" }
+                        p { class: "text-sm mt-2", "{goal_description(level.goal)}" }
+                        if let Some(move_limit) = level.move_limit {
+                            p { class: "text-sm", "in {move_limit} moves" }
+                        }
+                        match progress.read().stars(idx) {
+                            Some(stars) => rsx! {
+                                p { class: "text-2xl text-[#edc22e] mt-2", "{stars_label(stars)}" }
+                            },
+                            None => rsx! {
+                                p { class: "text-sm mt-2 italic", "not completed" }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn campaign_level(level: usize) -> Element {
+    let levels = use_hook(campaign_levels);
+    let mut progress = use_context::<Signal<CampaignProgress>>();
+    // (level, stars) of the last win
+    let mut result = use_signal(|| None::<(usize, u8)>);
+    let size_signal = use_signal(|| (LEVEL_CAPACITY, LEVEL_CAPACITY));
+
+    let Some(current) = levels.get(level).cloned() else {
+        return rsx! {
+            div { class: "pt-20 text-center text-[#776e65] min-h-[100dvh]",
+                p { "This level does not exist." }
+                Link { to: Route::Campaign {}, class: "underline", "Back to the levels" }
+            }
+        };
+    };
+    // the level is built once to show why it cannot be played
+    let first_game = match current.build::<LEVEL_CAPACITY, LEVEL_CAPACITY, ClassicMergeRule>(current.seed.unwrap_or_else(RandManager::entropy_seed)) {
+        Ok(g) => g,
+        Err(e) => {
+            return rsx! {
+                div { class: "pt-20 text-center text-[#776e65] min-h-[100dvh]",
+                    p { "The level {level + 1} cannot be played: {e}" }
+                    Link { to: Route::Campaign {}, class: "underline", "Back to the levels" }
+                }
+            };
+        }
+    };
+    let level_init = current.clone();
+    let level_end = current.clone();
+    let has_next = level + 1 < levels.len();
+
+    rsx! {
+        div { class: "pt-20 flex flex-col items-center text-[#776e65]",
+            // the key starts a new board when going to the next level
+            key: "{level}",
+            p { class: "text-xl font-semibold", "{level + 1}. {current.name}" }
+            p {
+                "{goal_description(current.goal)}"
+                if let Some(move_limit) = current.move_limit {
+                    " in {move_limit} moves"
+                }
+            }
+            if let Some((_, stars)) = result().filter(|(won, _)| *won == level) {
+                p { class: "text-2xl text-[#edc22e]", "{stars_label(stars)}" }
+            }
+            div { class: "flex gap-4 mt-2",
+                Link { to: Route::Campaign {}, class: "underline", "Levels" }
+                if has_next {
+                    Link { to: Route::CampaignLevel { level: level + 1 }, class: "underline", "Next level" }
+                }
+            }
+            rectangular_board {
+                // the scores and the history of every level are kept apart
+                id: format!("campaign_{level}"),
+                title: "Campaign",
+                game_init: move |_size| {
+                    let seed = level_init.seed.unwrap_or_else(RandManager::entropy_seed);
+                    level_init.build::<LEVEL_CAPACITY, LEVEL_CAPACITY, ClassicMergeRule>(seed).unwrap_or_else(|_| first_game.clone())
+                },
+                size_signal,
+                on_game_end: move |g: SpecificGame<LEVEL_CAPACITY, LEVEL_CAPACITY, ClassicMergeRule>| {
+                    if g.game_status == GameStatus::END_SUCCESS {
+                        let stars = level_end.stars(g.game_variant_data.moves);
+                        progress.write().record(level, stars);
+                        let _ = progress.read().save(&mut *default_storage());
+                        result.set(Some((level, stars)));
+                    }
+                },
+                // the levels are short, a reload starts the level over
+                autosave: false,
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// Daily challenge page: one scored run on the game of the day, then its shareable summary
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::rectangular_board;
+use crate::frontendcomponents::share::copy_to_clipboard;
+use crate::game::daily::{date_text, DailyChallenge, DAILY_CAPACITY};
+use crate::game::game_variants::game_variant_1::{GameStatus, GameVariant, SpecificGame};
+use crate::storage::daily::{load_daily_result, save_daily_result};
+use crate::storage::default_storage;
+
+#[component]
+pub fn daily_challenge() -> Element {
+    let challenge = use_hook(DailyChallenge::today);
+    // the attempt of the day saved before the page was opened, or the run just finished
+    let day = challenge.day;
+    let mut daily_result = use_signal(move || load_daily_result(&*default_storage(), day));
+    let size_signal = use_signal(|| (DAILY_CAPACITY, DAILY_CAPACITY));
+
+    let challenge_init = challenge.clone();
+    let challenge_move = challenge.clone();
+    let challenge_end = challenge.clone();
+
+    rsx! {
+        div { class: "pt-20 flex flex-col items-center text-[#776e65] min-h-[100dvh]",
+            p { class: "text-xl font-semibold", "Daily challenge {date_text(challenge.day)}" }
+            p { "{challenge.variant.title} on a {challenge.size.0}x{challenge.size.1} board, one scored run per day" }
+
+            match daily_result() {
+                Some(Ok(result)) => {
+                    let summary = challenge.summary(&result);
+                    rsx! {
+                        pre { class: "mt-6 p-4 bg-white rounded-lg shadow font-mono text-sm", "{summary}" }
+                        button {
+                            class: "mt-4 px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                            onclick: move |_| copy_to_clipboard(summary.clone()),
+                            "Copy the result"
+                        }
+                        p { class: "mt-4 text-sm", "Come back tomorrow for a new challenge." }
+                    }
+                }
+                // the attempt was made even if its result is lost
+                Some(Err(_)) => rsx! {
+                    p { class: "mt-6", "Today's run was already played, its result cannot be read anymore." }
+                    p { class: "mt-4 text-sm", "Come back tomorrow for a new challenge." }
+                },
+                None => rsx! {
+                    rectangular_board {
+                        id: "daily_challenge",
+                        title: "Daily challenge",
+                        game_init: move |_size| match challenge_init.build::<DAILY_CAPACITY, DAILY_CAPACITY>() {
+                            Ok(g) => g,
+                            Err(_) => GameVariant::new_game(),
+                        },
+                        size_signal,
+                        // the first move uses the attempt of the day, a reload shows its result
+                        on_move: move |g: SpecificGame<DAILY_CAPACITY, DAILY_CAPACITY>| {
+                            let _ = save_daily_result(&mut *default_storage(), &challenge_move.result(&g));
+                        },
+                        on_game_end: move |g: SpecificGame<DAILY_CAPACITY, DAILY_CAPACITY>| {
+                            // a won run ends the attempt too, the score is the one reached at 2048
+                            if g.game_status != GameStatus::INPROGRESS {
+                                let result = challenge_end.result(&g);
+                                let _ = save_daily_result(&mut *default_storage(), &result);
+                                daily_result.set(Some(Ok(result)));
+                            }
+                        },
+                        // a restart would be a second attempt
+                        restartable: false,
+                        // a reload shall not bring back an attempt of an older day
+                        autosave: false,
+                    }
+                },
+            }
+        }
+    }
+}
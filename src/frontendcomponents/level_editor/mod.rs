@@ -0,0 +1,318 @@
+// Editor of the level files: the designer paints the cells, tunes the level and test-plays it in place
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::rectangular_board;
+use crate::theme::{theme, Theme};
+use crate::storage::settings::Settings;
+use crate::game::game_variants::game_variant_1::{Goal, SpecificElementType, SpecificGame};
+use crate::game::levels::{Level, DEFAULT_BLOCK_HP, LEVEL_CAPACITY};
+use crate::game::merge_rules::ClassicMergeRule;
+use crate::game::rand_manager::RandManager;
+
+const BRUSH_VALUES: [i32; 10] = [2, 4, 8, 16, 32, 64, 128, 256, 512, 1024];
+const GOAL_VALUES: [i32; 8] = [32, 64, 128, 256, 512, 1024, 2048, 4096];
+const DEFAULT_GOAL_SCORE: i32 = 1000;
+
+fn goal_kind(goal: Goal) -> &'static str {
+    match goal {
+        Goal::ReachTile(_) => "tile",
+        Goal::ClearBlocks => "clear_blocks",
+        Goal::Score(_) => "score",
+    }
+}
+
+// a click cycles empty -> number -> block -> fixed block -> empty
+fn next_cell(cell: Option<SpecificElementType>, brush: i32) -> Option<SpecificElementType> {
+    match cell {
+        None => Some(SpecificElementType::Some(brush)),
+        Some(SpecificElementType::Some(_)) => Some(SpecificElementType::Block(DEFAULT_BLOCK_HP)),
+        Some(SpecificElementType::Block(_)) => Some(SpecificElementType::BlockFixed),
+        _ => None,
+    }
+}
+
+fn cell_label(cell: Option<SpecificElementType>) -> String {
+    match cell {
+        Some(SpecificElementType::Some(a)) => a.to_string(),
+        Some(SpecificElementType::Block(hp)) => format!("B{hp}"),
+        Some(SpecificElementType::BlockFixed) => "#".to_string(),
+        Some(SpecificElementType::Joker) => "★".to_string(),
+        Some(SpecificElementType::Bomb(countdown)) => format!("💣{countdown}"),
+        _ => String::new(),
+    }
+}
+
+// the blocks are drawn flat in the first colour of their texture with their label
+fn cell_style(theme: &Theme, cell: Option<SpecificElementType>) -> String {
+    match theme.texture(cell) {
+        Some(texture) => format!("background-color: {}; color: #ffffff;", texture.palette[0]),
+        None => theme.tile_style::<ClassicMergeRule>(cell),
+    }
+}
+
+fn download_level(text: String) {
+    let eval = document::eval(
+        r#"
+        const text = await dioxus.recv();
+        const link = document.createElement("a");
+        link.href = "data:text/plain;charset=utf-8," + encodeURIComponent(text);
+        link.download = "level.txt";
+        link.click();
+        "#,
+    );
+    let _ = eval.send(text);
+}
+
+#[component]
+pub fn level_editor() -> Element {
+    let mut level = use_signal(|| Level::new((4, 4)));
+    let mut brush = use_signal(|| 2);
+    // the board of the test run, built when it starts
+    let mut test_game = use_signal(|| None::<SpecificGame<LEVEL_CAPACITY, LEVEL_CAPACITY, ClassicMergeRule>>);
+    let mut play_error = use_signal(|| None::<String>);
+    let mut level_text = use_signal(String::new);
+    let mut import_error = use_signal(|| None::<String>);
+    let size_signal = use_signal(|| (LEVEL_CAPACITY, LEVEL_CAPACITY));
+    let board_theme = theme(&use_context::<Signal<Settings>>().read().theme);
+
+    let validation = level.read().validate::<ClassicMergeRule>();
+    let (rows, cols) = level.read().size;
+    let goal = level.read().goal;
+
+    let mut import_text = move |text: String| match Level::parse(&text) {
+        Ok(parsed) => {
+            level.set(parsed);
+            import_error.set(None);
+        }
+        Err(e) => import_error.set(Some(e)),
+    };
+
+    // a level that does not build keeps the editor open with the error
+    let mut start_test = move || match level.read().build::<LEVEL_CAPACITY, LEVEL_CAPACITY, ClassicMergeRule>(RandManager::entropy_seed()) {
+        Ok(g) => {
+            play_error.set(None);
+            test_game.set(Some(g));
+        }
+        Err(e) => play_error.set(Some(e)),
+    };
+
+    if let Some(g) = test_game() {
+        return rsx! {
+            div { class: "flex flex-col items-center pt-20",
+                button {
+                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                    onclick: move |_| test_game.set(None),
+                    "Back to the editor"
+                }
+                rectangular_board {
+                    id: "level_test",
+                    title: "Level test",
+                    // a restart draws other starting tiles, the level built once already
+                    game_init: move |_size| level.peek().build::<LEVEL_CAPACITY, LEVEL_CAPACITY, ClassicMergeRule>(RandManager::entropy_seed()).unwrap_or_else(|_| g.clone()),
+                    size_signal,
+                    // the test run always starts from the edited level and stays out of the stats
+                    autosave: false,
+                    recorded: false,
+                }
+            }
+        };
+    }
+
+    rsx! {
+        div { class: "pt-20 max-w-3xl mx-auto px-6 min-h-[100dvh] text-[#776e65]",
+            h1 { class: "text-4xl font-bold mb-6", "Level editor" }
+
+            div { class: "flex flex-wrap gap-4 mb-4",
+                label { "Rows "
+                    select {
+                        value: "{rows}",
+                        onchange: move |evt| {
+                            if let Ok(new_rows) = evt.value().parse() {
+                                level.write().resize((new_rows, cols));
+                            }
+                        },
+                        for side in 2..=LEVEL_CAPACITY {
+                            option { value: "{side}", selected: side == rows, "{side}" }
+                        }
+                    }
+                }
+                label { "Columns "
+                    select {
+                        value: "{cols}",
+                        onchange: move |evt| {
+                            if let Ok(new_cols) = evt.value().parse() {
+                                level.write().resize((rows, new_cols));
+                            }
+                        },
+                        for side in 2..=LEVEL_CAPACITY {
+                            option { value: "{side}", selected: side == cols, "{side}" }
+                        }
+                    }
+                }
+                label { "Starting tiles "
+                    input {
+                        r#type: "number",
+                        class: "w-16",
+                        min: "0",
+                        max: "{rows * cols}",
+                        value: "{level.read().start_tiles}",
+                        oninput: move |evt| {
+                            if let Ok(start_tiles) = evt.value().parse() {
+                                level.write().start_tiles = start_tiles;
+                            }
+                        },
+                    }
+                }
+                label { "Goal "
+                    select {
+                        value: "{goal_kind(goal)}",
+                        onchange: move |evt| {
+                            level.write().goal = match evt.value().as_str() {
+                                "clear_blocks" => Goal::ClearBlocks,
+                                "score" => Goal::Score(DEFAULT_GOAL_SCORE),
+                                _ => Goal::ReachTile(2048),
+                            };
+                        },
+                        option { value: "tile", selected: goal_kind(goal) == "tile", "Reach a tile" }
+                        option { value: "clear_blocks", selected: goal_kind(goal) == "clear_blocks", "Break all the blocks" }
+                        option { value: "score", selected: goal_kind(goal) == "score", "Score" }
+                    }
+                    match goal {
+                        Goal::ReachTile(target) => rsx! {
+                            select {
+                                value: "{target}",
+                                onchange: move |evt| {
+                                    if let Ok(new_goal) = evt.value().parse() {
+                                        level.write().goal = Goal::ReachTile(new_goal);
+                                    }
+                                },
+                                for value in GOAL_VALUES {
+                                    option { value: "{value}", selected: value == target, "{value}" }
+                                }
+                            }
+                        },
+                        Goal::Score(target) => rsx! {
+                            input {
+                                r#type: "number",
+                                class: "w-20",
+                                min: "1",
+                                value: "{target}",
+                                oninput: move |evt| {
+                                    if let Ok(new_goal) = evt.value().parse() {
+                                        level.write().goal = Goal::Score(new_goal);
+                                    }
+                                },
+                            }
+                        },
+                        Goal::ClearBlocks => rsx! {},
+                    }
+                }
+                // an empty move limit lets the player move until the board is full
+                label { "Move limit "
+                    input {
+                        r#type: "number",
+                        class: "w-16",
+                        min: "1",
+                        value: "{level.read().move_limit.map(|a| a.to_string()).unwrap_or_default()}",
+                        oninput: move |evt| level.write().move_limit = evt.value().parse().ok(),
+                    }
+                }
+                label { "Tile value "
+                    select {
+                        value: "{brush}",
+                        onchange: move |evt| {
+                            if let Ok(value) = evt.value().parse() {
+                                brush.set(value);
+                            }
+                        },
+                        for value in BRUSH_VALUES {
+                            option { value: "{value}", selected: value == brush(), "{value}" }
+                        }
+                    }
+                }
+            }
+
+            p { class: "text-sm mb-2", "Click a cell to cycle empty / tile / block / fixed block." }
+
+            div { class: "p-4 rounded-lg shadow-lg inline-block", style: "background-color: {board_theme.board}",
+                div { class: "grid grid-cols-{cols} gap-3",
+                    for i in 0..rows {
+                        for j in 0..cols {
+                            div {
+                                class: "aspect-square rounded flex items-center justify-center text-2xl w-[5ch] font-bold cursor-pointer",
+                                style: "{cell_style(&board_theme, level.read().board[i][j])}",
+                                onclick: move |_| {
+                                    let cell = level.read().board[i][j];
+                                    level.write().board[i][j] = next_cell(cell, brush());
+                                },
+                                "{cell_label(level.read().board[i][j])}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            match &validation {
+                Ok(()) => rsx! {
+                    p { class: "mt-4 text-green-700", "The level is valid." }
+                },
+                Err(e) => rsx! {
+                    p { class: "mt-4 text-red-700", "{e}" }
+                },
+            }
+
+            if let Some(e) = play_error() {
+                p { class: "mt-2 text-red-700", "The test run cannot start: {e}" }
+            }
+
+            div { class: "flex flex-wrap gap-3 mt-4",
+                button {
+                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] disabled:opacity-50",
+                    disabled: validation.is_err(),
+                    onclick: move |_| start_test(),
+                    "Test play"
+                }
+                button {
+                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                    onclick: move |_| {
+                        let text = level.read().to_text();
+                        level_text.set(text.clone());
+                        download_level(text);
+                    },
+                    "Export"
+                }
+                label { class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] cursor-pointer",
+                    "Import"
+                    input {
+                        r#type: "file",
+                        accept: ".txt",
+                        class: "hidden",
+                        onchange: move |evt| async move {
+                            if let Some(file) = evt.files().into_iter().next() {
+                                match file.read_string().await {
+                                    Ok(text) => import_text(text),
+                                    Err(e) => import_error.set(Some(e.to_string())),
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+
+            h2 { class: "text-xl font-semibold mt-6 mb-2", "Level file" }
+            textarea {
+                class: "w-full h-48 font-mono text-sm p-2 rounded border",
+                value: "{level_text}",
+                oninput: move |evt| level_text.set(evt.value()),
+            }
+            button {
+                class: "mt-2 px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                onclick: move |_| import_text(level_text()),
+                "Load the text"
+            }
+            if let Some(e) = import_error() {
+                p { class: "mt-2 text-red-700", "{e}" }
+            }
+        }
+    }
+}
@@ -1,211 +1,472 @@
-
-
+pub mod accessibility;
+pub mod adversary;
+pub mod animation;
+pub mod campaign;
+pub mod daily;
+pub mod level_editor;
+pub mod online;
+pub mod settings;
+pub mod share;
+pub mod stats;
+pub mod versus;
 
 use dioxus::prelude::*;
 
-use crate::game::game_variants::game_variant_1::{SpecificGame, GameVariant, AllowedMoves, GameStatus, SpecificElementType, get_rand_idx, set_nth_none_element, get_rand_value };
-
-const SCRIPT_JS: Asset = asset!("/assets/script.js");
-
-use std::f64::consts::{PI, FRAC_PI_2,FRAC_PI_8, TAU};
-
-
-
-#[derive(Debug, Clone,Copy)]
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down
-}
-
-struct DirectionInfoSwapType {
-    asociated_direction : Direction,
-    angle_ranges : [Option<(f64,f64)>;2],
-}
-
-const DIRECTION_INFOS : [DirectionInfoSwapType;4]= [
-    DirectionInfoSwapType{
-        asociated_direction : Direction::Right,
-        angle_ranges : [Some((0.0,0.0 + FRAC_PI_8)), Some((TAU-FRAC_PI_8,TAU))],
-    },
-    DirectionInfoSwapType{
-        asociated_direction : Direction::Up,
-        angle_ranges : [Some((FRAC_PI_2 - FRAC_PI_8,FRAC_PI_2 + FRAC_PI_8)),None],
-
-    },
-    DirectionInfoSwapType{
-        asociated_direction : Direction::Left,
-        angle_ranges : [Some((PI - FRAC_PI_8,PI + FRAC_PI_8)),None],
-
-    },
-    DirectionInfoSwapType{
-        asociated_direction : Direction::Down,
-        angle_ranges : [Some((3.0*FRAC_PI_2 - FRAC_PI_8,3.0*FRAC_PI_2 + FRAC_PI_8)),None],
-    },
-
-];
-
-fn is_part_of_this_angle_range(angle:f64, angle_ref_info:&DirectionInfoSwapType)-> bool{
-    for angle_range in angle_ref_info.angle_ranges {
-        if let Some((min, max)) = angle_range{
-            if angle >= min && angle <= max {
-                return true
-            }
-        }
+use crate::game::game_variants::game_variant_1::{SpecificGame, GameVariant, AllowedMoves, GameStatus, SpecificElementType, VariantRules, MergerInfo, RetainerMergerInfo, max_tile};
+use crate::game::clock::now_millis;
+use crate::game::merge_rules::{ClassicMergeRule, FibonacciMergeRule, TriplingMergeRule, ThreesMergeRule};
+use crate::storage::default_storage;
+use crate::game::undo::UndoHistory;
+use std::collections::VecDeque;
+use crate::theme::{theme, tile_class};
+use self::accessibility::{cell_label, move_announcement, use_game_focus};
+use self::animation::{animation_class, animation_style, animations_count, is_tile_animation, tile_animation, TileAnimation, MAX_QUEUED_MOVES};
+use crate::storage::saves::{load_game, remove_game, save_game};
+use crate::storage::scores::{FinishedGame, ScoreBook};
+use crate::storage::settings::{Settings, VariantSettings, SETTINGS_CAPACITY};
+use crate::variants::{variant, Rules, VariantDef};
+use crate::input::{min_swipe_length, GameAction, InputEvent};
+use crate::game::rand_manager::RandManager;
+use crate::game::strategies::Strategy;
+use crate::replay::{share_text, Replay};
+use self::share::{copy_to_clipboard, download_file, replay_url};
+use crate::export::{board_svg, replay_gif, DEFAULT_GIF_DELAY};
+
+
+// the blocks crack when they lose their hit points
+pub fn get_class_damage(el: Option<SpecificElementType>) -> &'static str {
+    match el {
+        Some(SpecificElementType::Block(1)) => "opacity-40 border-2 border-dashed border-[#776e65]",
+        Some(SpecificElementType::Block(2)) => "opacity-70 border-2 border-dashed border-[#776e65]",
+        _ => "",
     }
-    false
 }
 
-const MIN_LENGTH_SWAP : f64 = 80.0;
-
-fn get_swap_directions(diff_x : f64, diff_y:f64) -> Vec<Direction> {
-
-    let mut directions: Vec<Direction> = Vec::new();;
-    let swap_length_p_2 = diff_x.powi(2) + diff_y.powi(2);
-    if swap_length_p_2 < MIN_LENGTH_SWAP {
-        return directions
+// flash on the cells cleared during the last move by a bomb or a broken block
+pub fn get_class_cleared(info: Option<MergerInfo>) -> &'static str {
+    match info {
+        Some(RetainerMergerInfo::Detonated(_, _)) => "ring-4 ring-[#f65e3b] animate-pulse",
+        Some(RetainerMergerInfo::Destroyed(_)) => "ring-4 ring-[#776e65] animate-pulse",
+        _ => "",
     }
-    
-    let  tan_phi = diff_y / diff_x;
-
-    let mut phi = tan_phi.atan();
-    if phi < 0.0 {
-        phi = TAU + phi;
-        if diff_x < 0.0 {
-            phi = phi - PI;
-        }
-    }
-    else if phi > 0.0 && diff_x < 0.0 {
-        phi = phi + PI
-
-    }
-
+}
 
+pub fn get_class_candidate(candidate: bool) -> &'static str {
+    if candidate { "ring-4 ring-[#edc22e] cursor-pointer" } else { "" }
+}
 
-    for direction_info in DIRECTION_INFOS {
-        if is_part_of_this_angle_range(phi, &direction_info) {
-            directions.push(direction_info.asociated_direction)
+// grid of a game, shared by the single player board and the versus mode, the candidate cells
+// are highlighted and clickable. The animated board tells the end of every tile animation.
+#[component]
+pub fn board_view<const C_W:usize, const C_H:usize, R: VariantRules>(g_signal : ReadSignal<SpecificGame<C_W,C_H,R>>, #[props(default)] candidates : Vec<(usize,usize)>, on_cell_click : Option<EventHandler<(usize,usize)>>, #[props(default)] animated : bool, on_animation_end : Option<EventHandler<()>>) -> Element {
+    let tile_animation_of = move |idx: (usize, usize)| if animated { tile_animation(&g_signal.read(), idx) } else { TileAnimation::Still };
+    let theme = theme(&use_context::<Signal<Settings>>().read().theme);
+    rsx! {
+        div { class: "p-4 rounded-lg shadow-lg", style: "background-color: {theme.board}",
+            div {
+                class: "grid grid-cols-{g_signal.read().board_size.1} gap-3",
+                role: "grid",
+                aria_label: "Game board, {g_signal.read().board_size.0} rows by {g_signal.read().board_size.1} columns",
+                onanimationend: move |evt| {
+                    if let (true, Some(on_animation_end)) = (is_tile_animation(&evt.animation_name()), on_animation_end) {
+                        on_animation_end.call(());
+                    }
+                },
+
+                for i in 0..g_signal.read().board_size.0 {
+                    // the rows only exist for the screen readers, their cells are laid out by the grid
+                    div { key: "{i}", role: "row", class: "contents",
+                        for j in 0..g_signal.read().board_size.1 {
+                            div {
+                                // new cells after every move so their animations start again
+                                key: "{g_signal.read().game_variant_data.moves}-{i}-{j}",
+                                role: "gridcell",
+                                aria_label: "{cell_label(&g_signal.read(), (i, j))}",
+                                tabindex: if candidates.contains(&(i, j)) { "0" } else { "-1" },
+                                class: "relative {tile_class(g_signal.read().game_variant_data.array[i][j])} {get_class_damage(g_signal.read().game_variant_data.array[i][j])} {get_class_cleared(g_signal.read().game_variant_data.mergers_infos[i][j])} {get_class_candidate(candidates.contains(&(i, j)))} {animation_class(tile_animation_of((i, j)))}",
+                                style: "{theme.tile_style::<R>(g_signal.read().game_variant_data.array[i][j])} {animation_style(tile_animation_of((i, j)))}",
+                                onclick: move |_| {
+                                    if let Some(on_cell_click) = on_cell_click {
+                                        on_cell_click.call((i, j));
+                                    }
+                                },
+                                // the candidate cells are picked with the keyboard too
+                                onkeydown: move |evt| {
+                                    if let (Key::Enter, Some(on_cell_click)) = (evt.key(), on_cell_click) {
+                                        on_cell_click.call((i, j));
+                                    }
+                                },
+                                if let Some(SpecificElementType::Timed(num, age)) = g_signal.read().game_variant_data.array[i][j] {
+                                    {num.to_string()}
+                                    // moves left before the tile expires
+                                    if let Some(moves_left) = g_signal.read().game_variant_data.spawn_config.timed.moves_left(age) {
+                                        span { class: "absolute top-0 right-1 text-xs font-normal", "{moves_left}" }
+                                    }
+                                } else if let Some(SpecificElementType::Some(num)) = g_signal
+                                    .read()
+                                    .game_variant_data
+                                    .array[i][j]
+                                {
+                                    {num.to_string()}
+                                } else if let Some(texture) = theme.texture(g_signal.read().game_variant_data.array[i][j]) {
+                                    for colour in texture.cells() {
+                                        div { aria_hidden: "true", style: "background-color: {colour}" }
+                                    }
+                                } else if let Some(SpecificElementType::Joker) = g_signal.read().game_variant_data.array[i][j] {
+                                    "★"
+                                } else if let Some(SpecificElementType::Bomb(countdown)) = g_signal.read().game_variant_data.array[i][j] {
+                                    "💣{countdown}"
+                                } else {
+                                    div {}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            // Dummy div to make tailwind include these classes
+            div { class: "hidden grid-cols-1 grid-cols-2 grid-cols-3 grid-cols-4 grid-cols-5 grid-cols-6 grid-cols-7 grid-cols-8 grid-cols-9 grid-cols-10 grid-cols-11 grid-cols-12" }
+                        //div { class: "hidden w-[1ch] w-[2ch] w-[3ch] w-[4ch] w-[5ch] w-[6ch] w-[7ch] w-[8ch] w-[9ch] w-[10ch] w-[11ch] w-[12ch]" }
         }
     }
-    directions
-
 }
 
-
+// the id keys the saves, the scores and the replays of the board, every move that changes the
+// board is told to `on_move`
 #[component]
-pub fn rectangular_board<const C_W:usize, const C_H:usize>( game_init : fn((usize,usize)) -> SpecificGame<C_W,C_H>, size_signal : Signal<(usize,usize)>)-> Element {
+pub fn rectangular_board<const C_W:usize, const C_H:usize, R: VariantRules>( #[props(into)] id : String, title : &'static str, game_init : Callback<(usize,usize), SpecificGame<C_W,C_H,R>>, size_signal : Signal<(usize,usize)>, on_move : Option<EventHandler<SpecificGame<C_W,C_H,R>>>, on_game_end : Option<EventHandler<SpecificGame<C_W,C_H,R>>>, #[props(default = true)] restartable : bool, #[props(default = true)] autosave : bool, #[props(default = true)] recorded : bool, #[props(default)] undos : usize, replay_settings : Option<VariantSettings>)-> Element {
 
-    let mut touch_start_event: Signal<Option<TouchEvent>> = use_signal(|| None);
+    // (pointer, x, y) where the drag started
+    let mut drag_start = use_signal(|| None::<(i32, f64, f64)>);
+    // width of the board in pixels, the swipes scale with it
+    let mut board_width = use_signal(|| 0.0);
     
     let mut logs = use_signal::<Vec<String>>(Vec::new);
 
-    let mut g = game_init((size_signal.read().0,size_signal.read().1));
+    let g = game_init.call((size_signal.read().0,size_signal.read().1));
 
     let mut g_signal = use_signal(move || g);
 
-    fn get_class_x(el: Option<SpecificElementType>) -> &'static str {
-        match el {
-            None => "aspect-square flex items-center justify-center w-[5ch] font-bold bg-[#cdc1b4] rounded text-2xl",
-
-            Some(SpecificElementType::Some(2)) => "aspect-square bg-[#cdc1b4] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-[#776e65]",
-            Some(SpecificElementType::Some(4)) => "aspect-square bg-[#eee4da] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-[#776e65]",
-            Some(SpecificElementType::Some(8)) => "aspect-square bg-[#ede0c8] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-[#776e65]",
-            Some(SpecificElementType::Some(16)) => "aspect-square bg-[#f2b179] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(32)) => "aspect-square bg-[#f59563] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(64)) => "aspect-square bg-[#f67c5f] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(128)) => "aspect-square bg-[#f65e3b] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(256)) => "aspect-square bg-[#edcf72] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(512))=> "aspect-square bg-[#edcc61] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(1024)) => "aspect-square bg-[#edc850] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::Some(2048)) => "aspect-square bg-[#edc22e] rounded flex items-center justify-center text-2xl w-[5ch] font-bold text-white",
-            Some(SpecificElementType::BlockFixed) => "aspect-square rounded grid grid-cols-8 grid-rows-8",
-            Some(SpecificElementType::Block) => "aspect-square rounded grid grid-cols-8 grid-rows-8",
-
-            _ => "aspect-square bg-red rounded",
+    let mut score_book = use_context::<Signal<ScoreBook>>();
+    let mut started_at = use_signal(now_millis);
+    let mut undo_history = use_signal(move || UndoHistory::new(undos));
+    let settings = use_context::<Signal<Settings>>();
+    let animated = !settings.read().reduced_motion;
+    // tile animations still running and the moves made meanwhile
+    let mut running_animations = use_signal(|| 0usize);
+    let mut move_queue = use_signal(VecDeque::<AllowedMoves>::new);
+    let mut game_focus = use_game_focus();
+    // read by the screen readers after every move
+    let mut announcement = use_signal(String::new);
+    // move suggested by the hint, until the next move
+    let mut hint = use_signal(|| None::<AllowedMoves>);
+    // moves of the game, None once unknown (resumed from an older save), with the seed of the
+    // game and the settings it was built from they replay it
+    let mut move_log = use_signal(|| Some(Vec::<AllowedMoves>::new()));
+    let mut shared_text = use_signal(|| None::<String>);
+    let replay_settings = use_signal(move || replay_settings);
+    let id = use_signal(move || id);
+
+    // game left in progress on the last visit, a save that cannot be read anymore is dropped
+    let mut saved_game = use_signal(move || {
+        if !autosave {
+            return None;
         }
-    }
+        match load_game::<C_W, C_H, R>(&*default_storage(), &id.read()) {
+            Some(Ok(saved)) if saved.0.game_status == GameStatus::INPROGRESS => Some(saved),
+            Some(_) => {
+                let _ = remove_game(&mut *default_storage(), &id.read());
+                None
+            }
+            None => None,
+        }
+    });
+
+    let mut restart = move || {
+        *g_signal.write() = game_init.call((size_signal.read().0, size_signal.read().1));
+        started_at.set(now_millis());
+        undo_history.set(UndoHistory::new(undos));
+        running_animations.set(0);
+        move_queue.write().clear();
+        hint.set(None);
+        move_log.set(Some(Vec::new()));
+        shared_text.set(None);
+        saved_game.set(None);
+        if autosave {
+            let _ = remove_game(&mut *default_storage(), &id.read());
+        }
+        announcement.set("New game.".to_string());
+        game_focus.focus();
+    };
+
+    // the finished games are kept in the history of the variant, unless the board is a test run
+    let mut record_game = move || {
+        if !recorded {
+            return;
+        }
+        let g = g_signal.read();
+        score_book.write().record(FinishedGame {
+            variant: id(),
+            size: g.board_size,
+            date: now_millis(),
+            score: g.game_variant_data.score,
+            max_tile: max_tile(&g),
+            moves: g.game_variant_data.moves,
+            duration: now_millis().saturating_sub(started_at()),
+            won: g.game_status == GameStatus::END_SUCCESS,
+        });
+        let _ = score_book.read().save(&mut *default_storage());
+    };
+
+    let autosave_game = move || {
+        if autosave {
+            let mut storage = default_storage();
+            let _ = if g_signal.read().game_status == GameStatus::INPROGRESS {
+                save_game(&mut *storage, &id.read(), &*g_signal.read(), &*undo_history.read(), move_log.read().as_deref())
+            } else {
+                remove_game(&mut *storage, &id.read())
+            };
+        }
+    };
+
+    let mut undo = move || {
+        let previous = undo_history.write().undo();
+        if let Some(previous) = previous {
+            g_signal.set(previous);
+            running_animations.set(0);
+            move_queue.write().clear();
+            hint.set(None);
+            if let Some(log) = move_log.write().as_mut() {
+                log.pop();
+            }
+            autosave_game();
+            announcement.set(format!("Move undone, {} undos left.", undo_history.read().undos_left));
+        }
+        game_focus.focus();
+    };
+
+    let mut play = move |move_type: AllowedMoves| {
+        let before = g_signal.read().clone();
+        g_signal.write().move_generic(move_type);
+        let status = g_signal.read().game_status;
+        let moved = g_signal.read().game_variant_data.moves != before.game_variant_data.moves;
+        announcement.set(move_announcement(&g_signal.read(), move_type, moved));
+        if moved {
+            hint.set(None);
+            if let Some(log) = move_log.write().as_mut() {
+                log.push(move_type);
+            }
+            undo_history.write().record(before.clone());
+            if animated {
+                running_animations.set(animations_count(&g_signal.read()));
+            }
+            if let Some(on_move) = on_move {
+                on_move.call(g_signal.read().clone());
+            }
+        }
+        // the first move starts a new game instead of resuming the saved one
+        if saved_game.read().is_some() {
+            saved_game.set(None);
+        }
+        autosave_game();
+        //check game status if end to show game over
+        if status == GameStatus::END_FAIL {
+            logs.write().push("Game Over".to_string());
+
+        } else if status == GameStatus::END_SUCCESS {
+            logs.write().push("You Win!".to_string());
+        }
+        if status != before.game_status {
+            record_game();
+            if let Some(on_game_end) = on_game_end {
+                on_game_end.call(g_signal.read().clone());
+            }
+        }
+    };
+
+    // a move made during the animations of the last one waits for their end
+    let mut request_move = move |move_type: AllowedMoves| {
+        if running_animations() == 0 {
+            play(move_type);
+        } else if move_queue.read().len() < MAX_QUEUED_MOVES {
+            move_queue.write().push_back(move_type);
+        }
+    };
+
+    let on_animation_end = move |_| {
+        let running = running_animations().saturating_sub(1);
+        running_animations.set(running);
+        if running == 0 {
+            let next_move = move_queue.write().pop_front();
+            if let Some(next_move) = next_move {
+                play(next_move);
+            }
+        }
+    };
+
+    // the move the AI would play, said and shown until the next move
+    let mut show_hint = move || {
+        let suggested = Strategy::Ai.choose_move(&g_signal.read(), &mut RandManager::new());
+        hint.set(suggested);
+        announcement.set(match suggested {
+            Some(move_type) => format!("Hint: {}.", GameAction::Move(move_type).label()),
+            None => "No move changes the board.".to_string(),
+        });
+    };
+
+    // every input ends here, the actions the board does not offer are ignored
+    let mut dispatch = move |action: GameAction| {
+        match action {
+            GameAction::Move(move_type) => request_move(move_type),
+            GameAction::Undo if undos > 0 => undo(),
+            GameAction::Restart if restartable => restart(),
+            GameAction::Hint => show_hint(),
+            _ => {}
+        }
+    };
+
+    // true when the input was bound to an action
+    let mut handle_input = move |event: InputEvent| {
+        let action = settings.read().bindings.resolve(&event);
+        if let Some(action) = action {
+            dispatch(action);
+        }
+        action.is_some()
+    };
+
+    // the replay of the game when its seed, its settings and all its moves are known
+    let replay = move || {
+        let g = g_signal.read();
+        let log = move_log.read();
+        match (replay_settings.read().clone(), g.game_variant_data.seed, log.as_ref()) {
+            (Some(settings), Some(seed), Some(moves)) if moves.len() == g.game_variant_data.moves => {
+                Some(Replay { variant: id(), settings, seed, moves: moves.clone() })
+            }
+            _ => None,
+        }
+    };
+
+    let share = move |_| {
+        if let Some(replay) = replay() {
+            spawn(async move {
+                let text = share_text(title, &g_signal.read(), &replay_url(&replay).await);
+                copy_to_clipboard(text.clone());
+                shared_text.set(Some(text));
+            });
+        }
+    };
+
+    // the board as it is, then the whole game so far
+    let download_svg = move |_| {
+        let svg = board_svg(&g_signal.read(), &theme(&settings.read().theme));
+        download_file(format!("{}.svg", id.read()), "image/svg+xml", svg.into_bytes());
+    };
+    let download_gif = move |_| {
+        if let Some(replay) = replay() {
+            let gif = variant(&replay.variant)
+                .ok_or(format!("unknown variant: {}", replay.variant))
+                .and_then(|def| replay.states::<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>(def.setup))
+                .and_then(|states| replay_gif(&states, &theme(&settings.read().theme), DEFAULT_GIF_DELAY));
+            match gif {
+                Ok(gif) => download_file(format!("{}.gif", id.read()), "image/gif", gif),
+                Err(e) => logs.write().push(format!("gif export: {e}")),
+            }
+        }
+    };
+
+    let move_keys = settings.read().bindings.move_keys();
 
     rsx! {
-        document::Script { src: SCRIPT_JS, defer: true }
         div {
             tabindex: "0",
-            class: "flex items-center min-h-[100dvh] justify-center items-center",
+            class: "flex items-center min-h-[100dvh] justify-center items-center select-none",
             id: "game-container",
             style: "touch-action: none;",
+            aria_label: "{title}, move with {move_keys}, or swipe or drag on the board",
+            onmounted: move |evt| game_focus.mounted(evt),
 
-            // keyboard
+            // keyboard, the bound keys do not scroll the page
             onkeydown: move |evt| {
-                match evt.key() {
-                    Key::ArrowLeft => g_signal.write().move_generic(AllowedMoves::LEFT),
-                    Key::ArrowRight => g_signal.write().move_generic(AllowedMoves::RIGHT),
-                    Key::ArrowUp => g_signal.write().move_generic(AllowedMoves::UP),
-                    Key::ArrowDown => g_signal.write().move_generic(AllowedMoves::DOWN),
-
-                    _ => {}
-                }
-                let status = g_signal.read().game_status;
-                //check game status if end to show game over
-                if status == GameStatus::END_FAIL {
-                    logs.write().push("Game Over".to_string());
-
-                } else if status == GameStatus::END_SUCCESS {
-                    logs.write().push("You Win!".to_string());
+                if handle_input(InputEvent::Key(evt.key().to_string())) {
+                    evt.prevent_default();
                 }
             },
 
-            ontouchstart: move |evt| {
-                let mut t = touch_start_event.write();
-                *t = Some(evt);
+            // the touch swipes and the mouse or trackpad drags, one pointer at a time
+            onpointerdown: move |evt| {
+                if evt.is_primary() {
+                    let start = evt.client_coordinates();
+                    drag_start.set(Some((evt.pointer_id(), start.x, start.y)));
+                }
             },
 
-            ontouchend: move |evt| {
-                let t = touch_start_event.read();
-                let mut directions: Vec<Direction> = Vec::new();
-                if let Some(var) = t.as_ref() {
-                    if let Some(touch_start) = var.touches().first() {
-                        if let Some(touch_end) = evt.touches_changed().first() {
-
-                            let diff_x = touch_end.client_coordinates().x
-                                - touch_start.client_coordinates().x;
-                            let diff_y = touch_end.client_coordinates().y
-                                - touch_start.client_coordinates().y;
-                            // because the direction axis of y  is down
-                            directions = get_swap_directions(diff_x, -diff_y);
-
-                            logs.write().push(format!("directions : {directions:#?}"));
-                        }
-                    }
+            onpointerup: move |evt| {
+                let Some((pointer_id, start_x, start_y)) = drag_start.take() else {
+                    return;
+                };
+                if pointer_id == evt.pointer_id() {
+                    let end = evt.client_coordinates();
+                    let (diff_x, diff_y) = (end.x - start_x, end.y - start_y);
+                    logs.write().push(format!("swipe : {diff_x} {diff_y}"));
+                    let min_length = min_swipe_length(board_width(), settings.read().swipe_percent);
+                    handle_input(InputEvent::Swipe { diff_x, diff_y, min_length });
                 }
-                if directions.len() == 1 {
-                    match directions[0] {
-                        Direction::Left => g_signal.write().move_generic(AllowedMoves::LEFT),
-                        Direction::Right => g_signal.write().move_generic(AllowedMoves::RIGHT),
-                        Direction::Down => g_signal.write().move_generic(AllowedMoves::DOWN),
-                        Direction::Up => g_signal.write().move_generic(AllowedMoves::UP),
-                    }
+            },
 
-                    let status = g_signal.read().game_status;
-                    //check game status if end to show game over
-                    if status == GameStatus::END_FAIL {
-                        logs.write().push("Game Over".to_string());
+            onpointercancel: move |_| drag_start.set(None),
 
-                    } else if status == GameStatus::END_SUCCESS {
-                        logs.write().push("You Win!".to_string());
+            h1 { class: "absolute top-20 text-4xl font-bold text-[#776e65]", "{title}" }
+            div { class: "wrapper",
+                if let Some(saved) = saved_game() {
+                    div { class: "mb-4 p-3 bg-white rounded-lg shadow flex items-center justify-between gap-3 text-[#776e65]",
+                        span { "Resume your game? (score {saved.0.game_variant_data.score}, {saved.0.game_variant_data.moves} moves)" }
+                        div { class: "flex gap-2",
+                            button {
+                                class: "px-3 py-1 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                                onclick: move |_| {
+                                    if let Some((saved, history, log)) = saved_game.take() {
+                                        *g_signal.write() = saved;
+                                        undo_history.set(history);
+                                        move_log.set(log);
+                                        started_at.set(now_millis());
+                                        announcement.set("Game resumed.".to_string());
+                                    }
+                                    game_focus.focus();
+                                },
+                                "Resume"
+                            }
+                            button {
+                                class: "px-3 py-1 bg-[#bbada0] text-white rounded hover:bg-[#a39485]",
+                                onclick: move |_| {
+                                    saved_game.set(None);
+                                    let _ = remove_game(&mut *default_storage(), &id.read());
+                                    game_focus.focus();
+                                },
+                                "New game"
+                            }
+                        }
                     }
                 }
-
-            },
-            h1 { class: "absolute top-20 text-4xl font-bold text-[#776e65]", "Classic 2048 Variant 1" }
-            div { class: "wrapper",
                 div { class: "mb-4 flex justify-between",
 
-                    button {
-                        class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
-                        onclick: move |_| {
-                            *g_signal.write() = game_init((size_signal.read().0, size_signal.read().1));
-                        },
-                        "Restart"
+                    if restartable {
+                        button {
+                            class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                            onclick: move |_| {
+                                handle_input(InputEvent::Button(GameAction::Restart));
+                            },
+                            "Restart"
+                        }
+                    }
+                    if undos > 0 {
+                        button {
+                            class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] disabled:opacity-50",
+                            disabled: !undo_history.read().can_undo(),
+                            onclick: move |_| {
+                                handle_input(InputEvent::Button(GameAction::Undo));
+                            },
+                            "Undo ({undo_history.read().undos_left})"
+                        }
                     }
                     div {
                         "Score : "
@@ -215,197 +476,91 @@ pub fn rectangular_board<const C_W:usize, const C_H:usize>( game_init : fn((usiz
                         {size_signal.read().1.to_string()}
                     
                     }
-                
-                }
-
-                div { class: "bg-[#bbada0] p-4 rounded-lg shadow-lg",
                     div {
-                        id: "board",
-                        class: "grid grid-cols-{g_signal.read().board_size.0} gap-3",
-
-                        for i in 0..g_signal.read().board_size.1 {
-                            for j in 0..g_signal.read().board_size.0 {
-                                div { class: get_class_x(g_signal.read().game_variant_data.array[i][j]),
-                                    if let Some(SpecificElementType::Some(num)) = g_signal
-                                        .read()
-                                        .game_variant_data
-                                        .array[i][j]
-                                    {
-                                        {num.to_string()}
-                                    } else if let Some(SpecificElementType::BlockFixed) = g_signal
-                                        .read()
-                                        .game_variant_data
-                                        .array[i][j]
-                                    {
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                    } else if let Some(SpecificElementType::Block) = g_signal.read().game_variant_data.array[i][j] {
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#c05a42]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#a44734]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                        div { class: "bg-[#b5523b]" }
-                                    } else {
-                                        div {}
-                                    }
-                                }
-                            }
+                        "Best : "
+                        {score_book.read().best(&id.read(), g_signal.read().board_size).unwrap_or(0).max(g_signal.read().game_variant_data.score).to_string()}
+                    }
+                    // moves left in the levels with a move limit
+                    if let Some(move_limit) = g_signal.read().game_variant_data.move_limit {
+                        div {
+                            "Moves : "
+                            {g_signal.read().game_variant_data.moves.to_string()}
+                            "/"
+                            {move_limit.to_string()}
+                        }
+                    }
+                    // preview of the next tile for the rules that show it
+                    if let Some(next) = g_signal.read().game_variant_data.next_value.and_then(|e| e.number()) {
+                        div {
+                            "Next : "
+                            {next.to_string()}
                         }
                     }
-                    // for debug
-                    div { class: "hidden",
-                        div { class: "mt-4 p-2 border h-32 overflow-auto text-sm bg-black text-green-400",
+                
+                }
 
-                            for log in logs.read().iter().rev() {
-                                div { "{log}" }
-                            }
+                div {
+                    onresize: move |evt| {
+                        if let Ok(size) = evt.data().get_border_box_size() {
+                            board_width.set(size.width);
                         }
+                    },
+                    board_view { g_signal, animated, on_animation_end }
+                }
+                // on-screen buttons, for the players without keyboard nor swipes
+                div { class: "mt-4 flex justify-center items-center gap-2",
+                    for (action, arrow) in [(GameAction::Move(AllowedMoves::LEFT), "←"), (GameAction::Move(AllowedMoves::UP), "↑"), (GameAction::Move(AllowedMoves::DOWN), "↓"), (GameAction::Move(AllowedMoves::RIGHT), "→")] {
                         button {
-                            class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                            class: "w-10 h-10 bg-[#bbada0] text-white text-xl rounded hover:bg-[#a39485]",
+                            aria_label: action.label(),
                             onclick: move |_| {
-                                *g_signal.write() = game_init((size_signal.read().0, size_signal.read().1));
+                                handle_input(InputEvent::Button(action));
                             },
-                            "Restart"
+                            "{arrow}"
                         }
                     }
-                    // Dummy div to make tailwind include these classes
-                    div { class: "hidden grid-cols-1 grid-cols-2 grid-cols-3 grid-cols-4 grid-cols-5 grid-cols-6 grid-cols-7 grid-cols-8 grid-cols-9 grid-cols-10 grid-cols-11 grid-cols-12" }
-                                //div { class: "hidden w-[1ch] w-[2ch] w-[3ch] w-[4ch] w-[5ch] w-[6ch] w-[7ch] w-[8ch] w-[9ch] w-[10ch] w-[11ch] w-[12ch]" }
+                    button {
+                        class: "ml-2 px-3 h-10 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                        onclick: move |_| {
+                            handle_input(InputEvent::Button(GameAction::Hint));
+                            game_focus.focus();
+                        },
+                        "Hint"
+                    }
+                    if let Some(move_type) = hint() {
+                        span { class: "text-[#776e65]", "{GameAction::Move(move_type).label()}" }
+                    }
+                }
+                div { class: "mt-2 flex justify-center gap-2 text-sm",
+                    button {
+                        class: "px-3 py-1 bg-[#bbada0] text-white rounded hover:bg-[#a39485]",
+                        onclick: download_svg,
+                        "Download SVG"
+                    }
+                    if replay().is_some() {
+                        button {
+                            class: "px-3 py-1 bg-[#bbada0] text-white rounded hover:bg-[#a39485]",
+                            onclick: download_gif,
+                            "Download GIF"
+                        }
+                    }
+                }
+                div { class: "sr-only", role: "status", aria_live: "polite", "{announcement}" }
+                // for debug
+                div { class: "hidden",
+                    div { class: "mt-4 p-2 border h-32 overflow-auto text-sm bg-black text-green-400",
+
+                        for log in logs.read().iter().rev() {
+                            div { "{log}" }
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                        onclick: move |_| {
+                            restart();
+                        },
+                        "Restart"
+                    }
                 }
             }
 
@@ -422,13 +577,26 @@ pub fn rectangular_board<const C_W:usize, const C_H:usize>( game_init : fn((usiz
                             h2 { class: "text-3xl font-bold text-[#776e65] mb-4", "You Win!" }
                         }
                         div { class: "flex gap-3 justify-center",
-                            button {
-                                class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
-                                onclick: move |_| {
-                                    *g_signal.write() = game_init((size_signal.read().0, size_signal.read().1));
-                                },
-                                "Restart"
+                            if restartable {
+                                button {
+                                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                                    onclick: move |_| {
+                                        restart();
+                                    },
+                                    "Restart"
+                                }
                             }
+                            if replay().is_some() {
+                                button {
+                                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                                    onclick: share,
+                                    "Share"
+                                }
+                            }
+                        }
+                        if let Some(text) = shared_text() {
+                            pre { class: "mt-4 p-2 bg-white rounded text-left text-xs whitespace-pre-wrap break-all", "{text}" }
+                            p { class: "mt-2 text-sm text-[#776e65]", "Copied to the clipboard." }
                         }
                     }
                 }
@@ -440,90 +608,60 @@ pub fn rectangular_board<const C_W:usize, const C_H:usize>( game_init : fn((usiz
 
     
 
-#[component]
-pub fn classic_2048() -> Element {
-    fn game_init<const C_W:usize,const C_H:usize>(size : (usize, usize)) -> SpecificGame<C_W,C_H> {
-        let mut g = GameVariant::<C_W,C_H>::new_game();
-
-        let mut insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,get_rand_value());
-        g
-    }
-    let size_signal: Signal<(usize, usize)> = use_signal(||(4 as usize,4 as usize));
-    rsx!{
-        rectangular_board { game_init: game_init::<4, 4>, size_signal }
+// the settings of a variant before the player changes them
+pub fn default_settings(def: &VariantDef) -> VariantSettings {
+    match def.rules {
+        Rules::Classic => VariantSettings::defaults::<ClassicMergeRule>(def.size, def.setup),
+        Rules::Fibonacci => VariantSettings::defaults::<FibonacciMergeRule>(def.size, def.setup),
+        Rules::Tripling => VariantSettings::defaults::<TriplingMergeRule>(def.size, def.setup),
+        Rules::Threes => VariantSettings::defaults::<ThreesMergeRule>(def.size, def.setup),
     }
 }
 
-#[component]
-pub fn variant_1_2048() -> Element {
-    fn game_init<const C_W:usize,const C_H:usize>(size : (usize, usize)) -> SpecificGame<C_W,C_H> {
-        let mut g = GameVariant::<C_W,C_H>::new_game();
-
-        let mut insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,get_rand_value());
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::Block));
-        g
-    }
-    
-    let size_signal: Signal<(usize, usize)> = use_signal(||(4 as usize,4 as usize));
+// board of a variant built from its settings, the settings that do not fit the variant anymore
+// are ignored
+fn settings_board<R: VariantRules>(def: &'static VariantDef) -> Element {
+    let settings = use_context::<Signal<Settings>>();
+    let setup = def.setup;
+    let variant_settings = use_hook(|| {
+        settings
+            .read()
+            .variant(def.id)
+            .filter(|s| s.validate(setup).is_ok())
+            .cloned()
+            .unwrap_or_else(|| default_settings(def))
+    });
+    let size_signal = use_signal(|| variant_settings.size);
+    let undos = variant_settings.undos;
+    let replay_settings = Some(variant_settings.clone());
     rsx!{
-        rectangular_board { game_init: game_init::<4, 4>, size_signal }
+        rectangular_board {
+            id: def.id,
+            title: def.title,
+            game_init: move |_size| match variant_settings.build::<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>(setup) {
+                Ok(g) => g,
+                Err(_) => GameVariant::new_game(),
+            },
+            size_signal,
+            undos,
+            replay_settings,
+        }
     }
 }
 
+// the page of a variant of the registry, the rules pick the merge rule. The route keys it by the
+// id so another variant starts over.
 #[component]
-pub fn variant_2_2048() -> Element {
-    fn game_init<const C_W:usize,const C_H:usize>(size : (usize, usize)) -> SpecificGame<C_W,C_H> {
-        let mut g = GameVariant::<C_W,C_H>::new_game();
-
-        let mut insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,get_rand_value());
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::BlockFixed));
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::Block));
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::Block));
-        g
-    }
-    
-    let size_signal = use_signal(||(4 as usize,4 as usize));
-    rsx!{
-
-        rectangular_board { game_init: game_init::<4, 4>, size_signal }
+pub fn variant_board(variant_id: String) -> Element {
+    let Some(def) = variant(&variant_id) else {
+        return rsx! {
+            p { class: "pt-20 text-center text-[#776e65]", "Unknown variant {variant_id}." }
+        };
+    };
+    match def.rules {
+        Rules::Classic => settings_board::<ClassicMergeRule>(def),
+        Rules::Fibonacci => settings_board::<FibonacciMergeRule>(def),
+        Rules::Tripling => settings_board::<TriplingMergeRule>(def),
+        Rules::Threes => settings_board::<ThreesMergeRule>(def),
     }
 }
-
-
-#[component]
-pub fn variant_3_2048() -> Element {
-    fn game_init<const C_W:usize,const C_H:usize>(size : (usize, usize)) -> SpecificGame<C_W,C_H> {
-        let mut g = GameVariant::<C_W,C_H>::new_game_specific_dim(size).unwrap();
-
-        let mut insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,get_rand_value());
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::BlockFixed));
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::Block));
-
-        insert_idx = get_rand_idx(0, g.game_variant_data.nones_number);
-        set_nth_none_element(&mut g, insert_idx,Some(SpecificElementType::Block));
-        g
-    }
-    
-    let mut size_signal = use_signal(||(4 as usize,4 as usize));
-    rsx!{
-        //input { oninput: move |el| { (*size_signal.write()).0 = el.value().parse::<usize>().unwrap() } }
-        //input { oninput: move |el| { (*size_signal.write()).1 = el.value().parse::<usize>().unwrap() } }
-        rectangular_board { game_init: game_init::<5, 5>, size_signal }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,214 @@
+// Online race page: the player joins a room of the relay server and races the other players on
+// the same seeded board. After a reconnection the moves played offline are sent again, or the
+// board is rebuilt from the server's moves log when the local one is behind. The token given on the
+// first join keeps the seat of the player across the reconnections.
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::accessibility::use_game_focus;
+use crate::frontendcomponents::board_view;
+use crate::input::{GameAction, InputEvent};
+use crate::storage::settings::Settings;
+use crate::game::game_variants::game_variant_1::{AllowedMoves, GameStatus};
+use crate::net::protocol::{is_valid_name, ClientMessage, ServerMessage};
+use crate::net::relay::{race_board, DEFAULT_RACE_GOAL};
+
+const DEFAULT_SERVER: &str = "ws://127.0.0.1:9048";
+
+// the socket reconnects by itself, "open" and "closed" report its state to the page
+const SOCKET_JS: &str = r#"
+    const url = await dioxus.recv();
+    let socket = null;
+    function connect() {
+        socket = new WebSocket(url);
+        socket.onopen = () => dioxus.send("open");
+        socket.onmessage = (event) => dioxus.send(event.data);
+        socket.onclose = () => {
+            dioxus.send("closed");
+            setTimeout(connect, 1000);
+        };
+    }
+    connect();
+    while (true) {
+        const line = await dioxus.recv();
+        if (socket.readyState === WebSocket.OPEN) {
+            socket.send(line);
+        }
+    }
+"#;
+
+#[derive(Clone, Debug, PartialEq)]
+struct PlayerScore {
+    name: String,
+    score: i32,
+    moves: usize,
+    status: GameStatus,
+}
+
+fn status_label(status: GameStatus) -> &'static str {
+    match status {
+        GameStatus::INPROGRESS => "playing",
+        GameStatus::END_FAIL => "game over",
+        GameStatus::END_SUCCESS => "reached the goal",
+    }
+}
+
+#[component]
+pub fn online_2048() -> Element {
+    let mut server = use_signal(|| DEFAULT_SERVER.to_string());
+    let mut room = use_signal(|| "lobby".to_string());
+    let mut name = use_signal(String::new);
+    let mut socket = use_signal(|| None::<document::Eval>);
+    let mut connected = use_signal(|| false);
+    // (seed, goal) of the race
+    let mut race = use_signal(|| None::<(u64, i32)>);
+    let mut token = use_signal(|| None::<String>);
+    let mut log = use_signal(Vec::<AllowedMoves>::new);
+    let mut g_signal = use_signal(|| race_board(0, DEFAULT_RACE_GOAL, &[]));
+    let mut scores = use_signal(Vec::<PlayerScore>::new);
+    let mut winner = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+    let mut game_focus = use_game_focus();
+
+    let send = move |message: ClientMessage| {
+        if let Some(socket) = socket.read().as_ref() {
+            let _ = socket.send(message.to_line());
+        }
+    };
+
+    let mut receive = move |line: String| match line.as_str() {
+        "open" => {
+            connected.set(true);
+            send(ClientMessage::Join { room: room(), name: name(), token: token() });
+        }
+        "closed" => connected.set(false),
+        _ => match ServerMessage::parse(&line) {
+            Ok(ServerMessage::Welcome { seed, goal, token: seat_token, log: server_log }) => {
+                token.set(Some(seat_token));
+                if race() != Some((seed, goal)) || log.read().len() < server_log.len() {
+                    race.set(Some((seed, goal)));
+                    g_signal.set(race_board(seed, goal, &server_log));
+                    log.set(server_log);
+                } else {
+                    let offline_moves = log.read()[server_log.len()..].to_vec();
+                    for move_type in offline_moves {
+                        send(ClientMessage::Move(move_type));
+                    }
+                }
+            }
+            Ok(ServerMessage::Score { name, score, moves, status }) => {
+                let player = PlayerScore { name, score, moves, status };
+                let mut scores = scores.write();
+                match scores.iter_mut().find(|p| p.name == player.name) {
+                    Some(known) => *known = player,
+                    None => scores.push(player),
+                }
+            }
+            Ok(ServerMessage::Winner { name }) => winner.set(Some(name)),
+            Ok(ServerMessage::Moved { .. }) => {}
+            Ok(ServerMessage::Error(e)) | Err(e) => error.set(Some(e)),
+        },
+    };
+
+    let connect = move |_| {
+        if !is_valid_name(&room()) || !is_valid_name(&name()) {
+            error.set(Some("the room and the name shall be one word".to_string()));
+            return;
+        }
+        error.set(None);
+        let mut eval = document::eval(SOCKET_JS);
+        let _ = eval.send(server());
+        socket.set(Some(eval));
+        spawn(async move {
+            while let Ok(line) = eval.recv::<String>().await {
+                receive(line);
+            }
+        });
+        game_focus.focus();
+    };
+
+    let mut play = move |move_type: AllowedMoves| {
+        if race().is_none() || winner().is_some() {
+            return;
+        }
+        let moves_before = g_signal.read().game_variant_data.moves;
+        g_signal.write().move_generic(move_type);
+        // the moves that do not change the board are not sent
+        if g_signal.read().game_variant_data.moves != moves_before {
+            log.write().push(move_type);
+            send(ClientMessage::Move(move_type));
+        }
+    };
+
+    let settings = use_context::<Signal<Settings>>();
+
+    rsx! {
+        div {
+            tabindex: "0",
+            class: "flex flex-col items-center min-h-[100dvh] pt-20 text-[#776e65]",
+            id: "game-container",
+            onmounted: move |evt| game_focus.mounted(evt),
+
+            // only the moves of the player's bindings, the other actions are not offered here
+            onkeydown: move |evt| {
+                if let Some(GameAction::Move(move_type)) = settings.read().bindings.resolve(&InputEvent::Key(evt.key().to_string())) {
+                    evt.prevent_default();
+                    play(move_type);
+                }
+            },
+
+            h1 { class: "text-4xl font-bold mb-4", "Online race" }
+
+            if socket.read().is_none() {
+                div { class: "flex flex-wrap gap-4 mb-4 items-center",
+                    label { "Server "
+                        input { class: "w-56", value: "{server}", oninput: move |evt| server.set(evt.value()) }
+                    }
+                    label { "Room "
+                        input { class: "w-28", value: "{room}", oninput: move |evt| room.set(evt.value()) }
+                    }
+                    label { "Name "
+                        input { class: "w-28", value: "{name}", oninput: move |evt| name.set(evt.value()) }
+                    }
+                    button {
+                        class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                        onclick: connect,
+                        "Join"
+                    }
+                }
+                p { class: "text-sm", "Start the server with `cargo run --bin relay_server`." }
+            } else {
+                p { class: "mb-2",
+                    if connected() { "Connected to the room {room}" } else { "Reconnecting..." }
+                }
+            }
+            if let Some(e) = error() {
+                p { class: "mb-2 text-red-700", "{e}" }
+            }
+            if let Some(name) = winner() {
+                p { class: "mb-2 text-2xl font-bold", "{name} wins the race!" }
+            }
+
+            if race().is_some() {
+                div { class: "flex flex-wrap gap-8 justify-center items-start",
+                    board_view { g_signal }
+                    table { class: "text-left",
+                        tr {
+                            th { class: "pr-4", "Player" }
+                            th { class: "pr-4", "Score" }
+                            th { class: "pr-4", "Moves" }
+                            th { "" }
+                        }
+                        for player in scores() {
+                            tr {
+                                td { class: "pr-4", "{player.name}" }
+                                td { class: "pr-4", "{player.score}" }
+                                td { class: "pr-4", "{player.moves}" }
+                                td { "{status_label(player.status)}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
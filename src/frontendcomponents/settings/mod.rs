@@ -0,0 +1,295 @@
+// Settings page: the player picks the board, the spawns, the goal, the obstacles and the undos of
+// every variant page, the new games of the page use them, and binds the keys of the actions
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::default_settings;
+use crate::theme::{theme, THEMES};
+use crate::variants::{variant, VariantDef, VARIANTS};
+use crate::input::{Bindings, ACTIONS, MAX_SWIPE_PERCENT, PRESETS};
+use crate::storage::default_storage;
+use crate::storage::settings::{Settings, VariantSettings, MAX_SPAWN_WEIGHT, MAX_UNDOS, MIN_SIDE, SETTINGS_CAPACITY};
+
+fn current_settings(settings: &Settings, def: &VariantDef) -> VariantSettings {
+    settings.variant(def.id).cloned().unwrap_or_else(|| default_settings(def))
+}
+
+#[component]
+pub fn settings_page() -> Element {
+    let mut settings = use_context::<Signal<Settings>>();
+    let mut def = use_signal(|| &VARIANTS[0]);
+    let mut draft = use_signal(|| current_settings(&settings.read(), &VARIANTS[0]));
+    let mut message = use_signal(|| None::<String>);
+
+    let (rows, cols) = draft.read().size;
+
+    let mut persist = move |new_settings: Settings| {
+        let saved = new_settings.save(&mut *default_storage());
+        settings.set(new_settings);
+        message.set(Some(match saved {
+            Ok(()) => "Saved, the next games use these settings.".to_string(),
+            Err(e) => format!("The settings could not be saved: {e}"),
+        }));
+    };
+
+    let save = move |_| {
+        let variant_settings = draft.read().clone();
+        if let Err(e) = variant_settings.validate(def().setup) {
+            message.set(Some(e));
+            return;
+        }
+        let mut new_settings = settings.read().clone();
+        new_settings.variants.insert(def().id.to_string(), variant_settings);
+        persist(new_settings);
+    };
+
+    let reset = move |_| {
+        let mut new_settings = settings.read().clone();
+        new_settings.variants.remove(def().id);
+        draft.set(default_settings(def()));
+        persist(new_settings);
+    };
+
+    rsx! {
+        div { class: "pt-20 max-w-3xl mx-auto px-6 min-h-[100dvh] text-[#776e65]",
+            h1 { class: "text-4xl font-bold mb-6", "Settings" }
+
+            // shared by all the variants, saved at once
+            label { class: "block mb-6",
+                input {
+                    r#type: "checkbox",
+                    class: "mr-2",
+                    checked: settings.read().reduced_motion,
+                    onchange: move |evt| {
+                        let mut new_settings = settings.read().clone();
+                        new_settings.reduced_motion = evt.checked();
+                        persist(new_settings);
+                    },
+                }
+                "Reduced motion (no tile animations)"
+            }
+            label { class: "block mb-6", "Theme "
+                select {
+                    value: "{theme(&settings.read().theme).name}",
+                    onchange: move |evt| {
+                        let mut new_settings = settings.read().clone();
+                        new_settings.theme = evt.value();
+                        persist(new_settings);
+                    },
+                    for t in THEMES {
+                        option { value: "{t.name}", selected: t.name == theme(&settings.read().theme).name, "{t.label}" }
+                    }
+                }
+            }
+
+            // the swipes and the drags shorter than this share of the board are not moves
+            label { class: "block mb-6", "Swipe length "
+                input {
+                    r#type: "range",
+                    class: "align-middle",
+                    min: "1",
+                    max: "{MAX_SWIPE_PERCENT}",
+                    value: "{settings.read().swipe_percent}",
+                    onchange: move |evt| {
+                        if let Ok(percent) = evt.value().parse() {
+                            let mut new_settings = settings.read().clone();
+                            new_settings.swipe_percent = percent;
+                            persist(new_settings);
+                        }
+                    },
+                }
+                " {settings.read().swipe_percent}% of the board width"
+            }
+
+            // the keys of the actions, from a preset or bound one by one
+            h2 { class: "text-xl font-semibold mb-2", "Controls" }
+            label { class: "block mb-2", "Keys "
+                select {
+                    onchange: move |evt| {
+                        if let Some(bindings) = Bindings::preset(&evt.value()) {
+                            let mut new_settings = settings.read().clone();
+                            new_settings.bindings = bindings;
+                            persist(new_settings);
+                        }
+                    },
+                    for (name, label, _) in PRESETS {
+                        option { value: "{name}", selected: settings.read().bindings.preset_name() == Some(name), "{label}" }
+                    }
+                    if settings.read().bindings.preset_name().is_none() {
+                        option { value: "custom", selected: true, "Custom" }
+                    }
+                }
+            }
+            div { class: "grid gap-2 mb-6",
+                for action in ACTIONS {
+                    div { class: "flex flex-wrap items-center gap-2",
+                        span { class: "w-28", "{action.label()}" }
+                        for key in settings.read().bindings.keys_of(action).into_iter().map(str::to_string).collect::<Vec<String>>() {
+                            button {
+                                class: "px-2 py-1 bg-[#eee4da] rounded hover:bg-[#ede0c8]",
+                                aria_label: "Unbind {key}",
+                                onclick: move |_| {
+                                    let mut new_settings = settings.read().clone();
+                                    new_settings.bindings.unbind(&key);
+                                    persist(new_settings);
+                                },
+                                "{key} ×"
+                            }
+                        }
+                        // the next key pressed in the field is bound, the tab still leaves it and the
+                        // modifiers wait for the key they modify
+                        input {
+                            class: "w-28 px-2 py-1 border rounded",
+                            readonly: true,
+                            placeholder: "press a key",
+                            aria_label: "New key for {action.label()}",
+                            onkeydown: move |evt| {
+                                if matches!(evt.key(), Key::Tab | Key::Shift | Key::Control | Key::Alt | Key::Meta) {
+                                    return;
+                                }
+                                evt.prevent_default();
+                                let mut new_settings = settings.read().clone();
+                                new_settings.bindings.bind(&evt.key().to_string(), action);
+                                persist(new_settings);
+                            },
+                        }
+                    }
+                }
+            }
+
+            label { class: "block mb-4", "Variant "
+                select {
+                    value: "{def().id}",
+                    onchange: move |evt| {
+                        if let Some(new_def) = variant(&evt.value()) {
+                            def.set(new_def);
+                            draft.set(current_settings(&settings.read(), new_def));
+                            message.set(None);
+                        }
+                    },
+                    for variant_def in VARIANTS.iter() {
+                        option { value: "{variant_def.id}", selected: variant_def.id == def().id, "{variant_def.title}" }
+                    }
+                }
+            }
+
+            div { class: "grid gap-4 sm:grid-cols-2",
+                label { "Rows "
+                    select {
+                        value: "{rows}",
+                        onchange: move |evt| {
+                            if let Ok(new_rows) = evt.value().parse() {
+                                draft.write().size.0 = new_rows;
+                            }
+                        },
+                        for side in MIN_SIDE..=SETTINGS_CAPACITY {
+                            option { value: "{side}", selected: side == rows, "{side}" }
+                        }
+                    }
+                }
+                label { "Columns "
+                    select {
+                        value: "{cols}",
+                        onchange: move |evt| {
+                            if let Ok(new_cols) = evt.value().parse() {
+                                draft.write().size.1 = new_cols;
+                            }
+                        },
+                        for side in MIN_SIDE..=SETTINGS_CAPACITY {
+                            option { value: "{side}", selected: side == cols, "{side}" }
+                        }
+                    }
+                }
+                label { "Target tile "
+                    input {
+                        r#type: "number",
+                        class: "w-24",
+                        min: "1",
+                        value: "{draft.read().target}",
+                        oninput: move |evt| {
+                            if let Ok(target) = evt.value().parse() {
+                                draft.write().target = target;
+                            }
+                        },
+                    }
+                }
+                label { "Undos per game "
+                    input {
+                        r#type: "number",
+                        class: "w-16",
+                        min: "0",
+                        max: "{MAX_UNDOS}",
+                        value: "{draft.read().undos}",
+                        oninput: move |evt| {
+                            if let Ok(undos) = evt.value().parse() {
+                                draft.write().undos = undos;
+                            }
+                        },
+                    }
+                }
+                label { "Moving blocks "
+                    input {
+                        r#type: "number",
+                        class: "w-16",
+                        min: "0",
+                        value: "{draft.read().blocks}",
+                        oninput: move |evt| {
+                            if let Ok(blocks) = evt.value().parse() {
+                                draft.write().blocks = blocks;
+                            }
+                        },
+                    }
+                }
+                label { "Fixed blocks "
+                    input {
+                        r#type: "number",
+                        class: "w-16",
+                        min: "0",
+                        value: "{draft.read().blocks_fixed}",
+                        oninput: move |evt| {
+                            if let Ok(blocks_fixed) = evt.value().parse() {
+                                draft.write().blocks_fixed = blocks_fixed;
+                            }
+                        },
+                    }
+                }
+            }
+
+            // the spawned values are the ones of the merge rules, only their weights change
+            h2 { class: "text-xl font-semibold mt-6 mb-2", "Spawn weights" }
+            div { class: "flex flex-wrap gap-4",
+                for (idx, (value, weight)) in draft.read().spawn_weights.iter().copied().enumerate() {
+                    label { "{value} "
+                        input {
+                            r#type: "number",
+                            class: "w-16",
+                            min: "0",
+                            max: "{MAX_SPAWN_WEIGHT}",
+                            value: "{weight}",
+                            oninput: move |evt| {
+                                if let Ok(weight) = evt.value().parse() {
+                                    draft.write().spawn_weights[idx].1 = weight;
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex gap-3 mt-6",
+                button {
+                    class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                    onclick: save,
+                    "Save"
+                }
+                button {
+                    class: "px-4 py-2 bg-[#bbada0] text-white rounded hover:bg-[#a39485]",
+                    onclick: reset,
+                    "Reset to the defaults"
+                }
+            }
+            if let Some(message) = message() {
+                p { class: "mt-4", "{message}" }
+            }
+        }
+    }
+}
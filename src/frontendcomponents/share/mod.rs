@@ -0,0 +1,147 @@
+// Shared games: the text copied at the end of a game and the page of its link, which plays the
+// game again from its seed and its moves and steps through them
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::board_view;
+use crate::game::game_variants::game_variant_1::{max_tile, GameVariant, VariantRules};
+use crate::game::merge_rules::{ClassicMergeRule, FibonacciMergeRule, ThreesMergeRule, TriplingMergeRule};
+use crate::replay::Replay;
+use crate::storage::settings::{Settings, SETTINGS_CAPACITY};
+use crate::variants::{variant, Rules, VariantDef};
+
+pub fn copy_to_clipboard(text: String) {
+    let eval = document::eval(
+        r#"
+        const text = await dioxus.recv();
+        await navigator.clipboard.writeText(text);
+        "#,
+    );
+    let _ = eval.send(text);
+}
+
+// a file made in the app, the browser saves it
+pub fn download_file(name: String, mime: &str, bytes: Vec<u8>) {
+    let eval = document::eval(
+        r#"
+        const [name, mime, bytes] = await dioxus.recv();
+        const url = URL.createObjectURL(new Blob([new Uint8Array(bytes)], { type: mime }));
+        const link = document.createElement("a");
+        link.href = url;
+        link.download = name;
+        link.click();
+        setTimeout(() => URL.revokeObjectURL(url), 1000);
+        "#,
+    );
+    let _ = eval.send((name, mime.to_string(), bytes));
+}
+
+// the link of a replay on the current site
+pub async fn replay_url(replay: &Replay) -> String {
+    let origin = document::eval("return window.location.origin;").join::<String>().await.unwrap_or_default();
+    format!("{origin}{}", replay.path())
+}
+
+fn replay_board<R: VariantRules>(def: &'static VariantDef, replay: Replay) -> Element {
+    let states = use_signal(|| replay.states::<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>(def.setup));
+    let last = states.read().as_ref().map(|states| states.len() - 1).unwrap_or(0);
+    // the final board first, as it was shared
+    let mut step = use_signal(|| last);
+    let mut shown = use_signal(|| match &*states.read() {
+        Ok(states) => states[last].clone(),
+        Err(_) => GameVariant::new_game(),
+    });
+    let animated = !use_context::<Signal<Settings>>().read().reduced_motion;
+
+    let mut go_to = move |new_step: usize| {
+        let new_step = new_step.min(last);
+        if let Ok(states) = &*states.read() {
+            shown.set(states[new_step].clone());
+        }
+        step.set(new_step);
+    };
+    let error = states.read().as_ref().err().cloned();
+
+    rsx! {
+        div { class: "pt-20 flex flex-col items-center min-h-[100dvh] text-[#776e65]",
+            h1 { class: "text-4xl font-bold mb-2", "{def.title}" }
+            match error {
+                Some(e) => rsx! {
+                    p { class: "mt-4", "This replay cannot be played: {e}." }
+                },
+                None => rsx! {
+                    p { class: "mb-4",
+                        "Move {step} / {last}, score {shown.read().game_variant_data.score}, max tile {max_tile(&shown.read())}"
+                    }
+                    board_view { g_signal: shown, animated }
+                    div { class: "mt-4 flex gap-2 items-center",
+                        button {
+                            class: "px-3 py-1 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] disabled:opacity-50",
+                            aria_label: "First move",
+                            disabled: step() == 0,
+                            onclick: move |_| go_to(0),
+                            "⏮"
+                        }
+                        button {
+                            class: "px-3 py-1 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] disabled:opacity-50",
+                            aria_label: "Previous move",
+                            disabled: step() == 0,
+                            onclick: move |_| go_to(step().saturating_sub(1)),
+                            "◀"
+                        }
+                        input {
+                            r#type: "range",
+                            aria_label: "Move",
+                            min: "0",
+                            max: "{last}",
+                            value: "{step}",
+                            oninput: move |evt| {
+                                if let Ok(new_step) = evt.value().parse() {
+                                    go_to(new_step);
+                                }
+                            },
+                        }
+                        button {
+                            class: "px-3 py-1 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] disabled:opacity-50",
+                            aria_label: "Next move",
+                            disabled: step() == last,
+                            onclick: move |_| go_to(step() + 1),
+                            "▶"
+                        }
+                        button {
+                            class: "px-3 py-1 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957] disabled:opacity-50",
+                            aria_label: "Last move",
+                            disabled: step() == last,
+                            onclick: move |_| go_to(last),
+                            "⏭"
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+// the page of a shared link, the variant picks the merge rules and the setup of the game. The
+// route keys it by its link so another link starts over.
+#[component]
+pub fn shared_game(variant_id: String, code: String) -> Element {
+    let Some(def) = variant(&variant_id) else {
+        return rsx! {
+            p { class: "pt-20 text-center text-[#776e65]", "Unknown variant {variant_id}." }
+        };
+    };
+    let replay = match Replay::from_code(&variant_id, &code) {
+        Ok(replay) => replay,
+        Err(e) => {
+            return rsx! {
+                p { class: "pt-20 text-center text-[#776e65]", "This link is damaged: {e}." }
+            };
+        }
+    };
+    match def.rules {
+        Rules::Classic => replay_board::<ClassicMergeRule>(def, replay),
+        Rules::Fibonacci => replay_board::<FibonacciMergeRule>(def, replay),
+        Rules::Tripling => replay_board::<TriplingMergeRule>(def, replay),
+        Rules::Threes => replay_board::<ThreesMergeRule>(def, replay),
+    }
+}
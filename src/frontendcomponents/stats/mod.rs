@@ -0,0 +1,153 @@
+// Statistics page: a table comparing the variants of the registry played in the game history, then for one variant the
+// histogram of the max tiles reached and the score of every game over time, drawn as inline svg
+use dioxus::prelude::*;
+
+use crate::storage::scores::ScoreBook;
+use crate::storage::stats::{all_stats, VariantStats};
+use crate::variants::{variant, variants_ids};
+
+const CHART_WIDTH: f64 = 480.0;
+const CHART_HEIGHT: f64 = 160.0;
+// room for the labels under the bars and left of the score axis
+const CHART_MARGIN: f64 = 24.0;
+
+fn variant_title(key: &str) -> String {
+    variant(key).map(|def| def.title.to_string()).unwrap_or_else(|| key.to_string())
+}
+
+// one bar per max tile, the count above it and the tile under it
+#[component]
+fn max_tile_histogram(stats: VariantStats) -> Element {
+    let most = stats.max_tiles.values().copied().max().unwrap_or(1) as f64;
+    let slot = (CHART_WIDTH - CHART_MARGIN) / stats.max_tiles.len().max(1) as f64;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    rsx! {
+        svg {
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            class: "w-full bg-white rounded-lg",
+            role: "img",
+            "aria-label": "Games by max tile reached",
+            for (idx, (tile, games)) in stats.max_tiles.iter().map(|(t, g)| (*t, *g)).enumerate() {
+                {
+                    let height = games as f64 / most * plot_height;
+                    let x = CHART_MARGIN / 2.0 + idx as f64 * slot;
+                    let y = CHART_HEIGHT - CHART_MARGIN - height;
+                    rsx! {
+                        rect { x: "{x + slot * 0.1}", y: "{y}", width: "{slot * 0.8}", height: "{height}", fill: "#edc22e", rx: "3" }
+                        text { x: "{x + slot / 2.0}", y: "{y - 4.0}", text_anchor: "middle", font_size: "11", fill: "#776e65", "{games}" }
+                        text { x: "{x + slot / 2.0}", y: "{CHART_HEIGHT - CHART_MARGIN / 2.0 + 4.0}", text_anchor: "middle", font_size: "11", fill: "#776e65", "{tile}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// the final score of the games in their order, the best score on the axis
+#[component]
+fn score_chart(stats: VariantStats) -> Element {
+    let best = stats.best_score.max(1) as f64;
+    let plot_width = CHART_WIDTH - 2.0 * CHART_MARGIN;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    let step = plot_width / (stats.scores.len().max(2) - 1) as f64;
+    let point = move |idx: usize, score: i32| (CHART_MARGIN + idx as f64 * step, CHART_HEIGHT - CHART_MARGIN - score as f64 / best * plot_height);
+    let points = stats
+        .scores
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, score))| {
+            let (x, y) = point(idx, *score);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    rsx! {
+        svg {
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            class: "w-full bg-white rounded-lg",
+            role: "img",
+            "aria-label": "Score of every game, oldest first",
+            line { x1: "{CHART_MARGIN}", y1: "{CHART_HEIGHT - CHART_MARGIN}", x2: "{CHART_WIDTH - CHART_MARGIN}", y2: "{CHART_HEIGHT - CHART_MARGIN}", stroke: "#bbada0" }
+            line { x1: "{CHART_MARGIN}", y1: "{CHART_MARGIN}", x2: "{CHART_MARGIN}", y2: "{CHART_HEIGHT - CHART_MARGIN}", stroke: "#bbada0" }
+            text { x: "{CHART_MARGIN - 4.0}", y: "{CHART_MARGIN + 4.0}", text_anchor: "end", font_size: "10", fill: "#776e65", "{stats.best_score}" }
+            polyline { points: "{points}", fill: "none", stroke: "#f67c5f", stroke_width: "2" }
+            for (idx, (_, score)) in stats.scores.iter().copied().enumerate() {
+                {
+                    let (x, y) = point(idx, score);
+                    rsx! { circle { cx: "{x}", cy: "{y}", r: "3", fill: "#f65e3b" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn stats_page() -> Element {
+    let score_book = use_context::<Signal<ScoreBook>>();
+    let stats = all_stats(&score_book.read().history, &variants_ids());
+    let mut selected = use_signal(|| stats.first().map(|s| s.variant.clone()).unwrap_or_default());
+    let details = stats.iter().find(|s| s.variant == selected()).cloned();
+
+    rsx! {
+        div { class: "pt-20 max-w-4xl mx-auto px-6 min-h-[100dvh] text-[#776e65]",
+            h1 { class: "text-4xl font-bold mb-6", "Statistics" }
+
+            if stats.is_empty() {
+                p { "No finished game yet, the statistics start with the first one." }
+            } else {
+                div { class: "overflow-x-auto mb-8",
+                    table { class: "w-full text-left bg-white rounded-lg",
+                        thead {
+                            tr {
+                                th { class: "p-2", "Variant" }
+                                th { class: "p-2", "Games" }
+                                th { class: "p-2", "Win rate" }
+                                th { class: "p-2", "Average score" }
+                                th { class: "p-2", "Best score" }
+                                th { class: "p-2", "Longest streak" }
+                            }
+                        }
+                        tbody {
+                            for s in stats.iter().cloned() {
+                                tr {
+                                    key: "{s.variant}",
+                                    class: if s.variant == selected() { "bg-[#eee4da] cursor-pointer" } else { "cursor-pointer hover:bg-[#faf8ef]" },
+                                    onclick: {
+                                        let variant = s.variant.clone();
+                                        move |_| selected.set(variant.clone())
+                                    },
+                                    td { class: "p-2", "{variant_title(&s.variant)}" }
+                                    td { class: "p-2", "{s.games}" }
+                                    // the bar compares the win rates at a glance
+                                    td { class: "p-2",
+                                        svg { view_box: "0 0 100 8", class: "inline-block w-20 h-2 mr-2 bg-[#eee4da] rounded",
+                                            rect { width: "{s.win_rate()}", height: "8", fill: "#8f7a66" }
+                                        }
+                                        "{s.win_rate():.0}%"
+                                    }
+                                    td { class: "p-2", "{s.average_score():.0}" }
+                                    td { class: "p-2", "{s.best_score}" }
+                                    td { class: "p-2", "{s.longest_streak}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(details) = details {
+                    h2 { class: "text-2xl font-semibold mb-4", "{variant_title(&details.variant)}" }
+                    div { class: "grid gap-6 md:grid-cols-2",
+                        div {
+                            h3 { class: "font-semibold mb-2", "Max tile reached" }
+                            max_tile_histogram { stats: details.clone() }
+                        }
+                        div {
+                            h3 { class: "font-semibold mb-2", "Score over time" }
+                            score_chart { stats: details }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
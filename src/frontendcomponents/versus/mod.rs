@@ -0,0 +1,114 @@
+// Local two players mode: player 1 plays with WASD on the left board, player 2 with the arrows
+// on the right board
+use dioxus::prelude::*;
+
+use crate::frontendcomponents::accessibility::use_game_focus;
+use crate::frontendcomponents::board_view;
+use crate::game::game_variants::game_variant_1::{AllowedMoves, SpecificGame};
+use crate::game::rand_manager::RandManager;
+use crate::game::versus::{big_merges, drop_garbage, new_boards, winner, GARBAGE_THRESHOLD};
+use crate::input::{Bindings, GameAction};
+
+const VERSUS_SIZE: (usize, usize) = (4, 4);
+const VERSUS_PRESETS: [&str; 2] = ["wasd", "arrows"];
+
+// (player, move) of a key, the players keep the WASD and the arrows presets whatever the settings
+fn key_move(key: &str) -> Option<(usize, AllowedMoves)> {
+    VERSUS_PRESETS.into_iter().enumerate().find_map(|(player, preset)| match Bindings::preset(preset)?.action(key) {
+        Some(GameAction::Move(move_type)) => Some((player, move_type)),
+        _ => None,
+    })
+}
+
+fn match_boards() -> Result<([SpecificGame<4, 4>; 2], RandManager), String> {
+    let seed = RandManager::entropy_seed();
+    new_boards::<4, 4>(VERSUS_SIZE, seed).map(|boards| (boards, RandManager::new_seeded(!seed)))
+}
+
+#[component]
+pub fn versus_2048() -> Element {
+    let (boards, rand_manager) = match use_hook(match_boards) {
+        Ok(start) => start,
+        Err(e) => {
+            return rsx! {
+                p { class: "pt-20 text-center text-[#776e65]", "The match cannot start: {e}" }
+            };
+        }
+    };
+    let mut match_error = use_signal(|| None::<String>);
+    let mut players = [use_signal(|| boards[0].clone()), use_signal(|| boards[1].clone())];
+    let mut garbage_rand_manager = use_signal(|| rand_manager);
+    let mut game_focus = use_game_focus();
+
+    let current_winner = winner(&[players[0](), players[1]()]);
+
+    let mut rematch = move || {
+        match match_boards() {
+            Ok((boards, rand_manager)) => {
+                for (player, board) in boards.into_iter().enumerate() {
+                    players[player].set(board);
+                }
+                garbage_rand_manager.set(rand_manager);
+                match_error.set(None);
+            }
+            Err(e) => match_error.set(Some(e)),
+        }
+        game_focus.focus();
+    };
+
+    rsx! {
+        div {
+            tabindex: "0",
+            class: "flex flex-col items-center min-h-[100dvh] pt-20 text-[#776e65]",
+            id: "game-container",
+            onmounted: move |evt| game_focus.mounted(evt),
+
+            // the keys of both players do not scroll the page
+            onkeydown: move |evt| {
+                let Some((player, move_type)) = key_move(&evt.key().to_string()) else {
+                    return;
+                };
+                evt.prevent_default();
+                if current_winner.is_some() {
+                    return;
+                }
+                let moves_before = players[player].read().game_variant_data.moves;
+                players[player].write().move_generic(move_type);
+                // a move that does not change the board keeps the merges of the previous one
+                if players[player].read().game_variant_data.moves != moves_before {
+                    let blocks = big_merges(&players[player].read());
+                    if blocks > 0 {
+                        drop_garbage(&mut players[1 - player].write(), blocks, &mut garbage_rand_manager.write());
+                    }
+                }
+            },
+
+            h1 { class: "text-4xl font-bold mb-2", "Versus" }
+            p { class: "mb-6 text-sm", "Merging into a tile of {GARBAGE_THRESHOLD} or more drops a block on the opponent's board." }
+
+            div { class: "flex flex-wrap gap-8 justify-center",
+                for (player, keys) in [(0, "W A S D"), (1, "arrows")] {
+                    div { class: "flex flex-col items-center gap-2",
+                        p { class: "font-semibold", "Player {player + 1} ({keys})" }
+                        p { "Score : {players[player].read().game_variant_data.score}" }
+                        board_view { g_signal: players[player] }
+                    }
+                }
+            }
+
+            if let Some(player) = current_winner {
+                div { class: "mt-6 flex flex-col items-center gap-3",
+                    h2 { class: "text-3xl font-bold", "Player {player + 1} wins!" }
+                    button {
+                        class: "px-4 py-2 bg-[#8f7a66] text-white rounded hover:bg-[#7c6957]",
+                        onclick: move |_| rematch(),
+                        "Rematch"
+                    }
+                    if let Some(e) = match_error() {
+                        p { class: "text-red-700", "The rematch cannot start: {e}" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Adversarial mode: the new tile of each move is not random, a second player (a human or the evil
+// ai) chooses its cell and its value, or drops one of its few blocks instead
+use crate::game::game_kernel::Swap2DGameConfig;
+use crate::game::game_variants::game_variant_1::{AllowedMoves, GameVariant, GameSetup, GameStatus, SpawnConfig, SpawnSource, SpecificElementType, SpecificGame, populate_game};
+use crate::game::strategies::{empty_cells, player_value};
+
+pub const SPAWN_VALUES: [i32; 2] = [2, 4];
+// blocks the spawner can drop in a game
+pub const ADVERSARY_BLOCKS: usize = 3;
+pub const ADVERSARY_BLOCK_HP: usize = 2;
+
+const ADVERSARY_SETUP: GameSetup = GameSetup { start_tiles: 2, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig { block_hp: ADVERSARY_BLOCK_HP, ..SpawnConfig::NUMBERS_ONLY } };
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Turn {
+    Player,
+    Spawner,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpawnChoice {
+    pub idx: (usize, usize),
+    pub element: SpecificElementType,
+}
+
+#[derive(Clone)]
+pub struct AdversarialGame<const C_W: usize, const C_H: usize> {
+    pub game: SpecificGame<C_W, C_H>,
+    pub turn: Turn,
+    pub blocks_left: usize,
+}
+
+impl<const C_W: usize, const C_H: usize> AdversarialGame<C_W, C_H> {
+
+    // the starting tiles are random, the spawner plays after the first move
+    pub fn new(size: (usize, usize), seed: u64) -> Result<AdversarialGame<C_W, C_H>, String> {
+        let mut game = GameVariant::<C_W, C_H>::new_game_specific_dim_seeded(size, seed)?;
+        populate_game(&mut game, ADVERSARY_SETUP);
+        game.game_variant_data.spawn_source = SpawnSource::External;
+        Ok(AdversarialGame { game, turn: Turn::Player, blocks_left: ADVERSARY_BLOCKS })
+    }
+
+    // false when it is not the player's turn or the move does not change the board
+    pub fn player_move(&mut self, move_type: AllowedMoves) -> bool {
+        if self.turn != Turn::Player || self.game.game_status != GameStatus::INPROGRESS {
+            return false;
+        }
+        let moves_before = self.game.game_variant_data.moves;
+        self.game.move_generic(move_type);
+        if self.game.game_variant_data.moves == moves_before {
+            return false;
+        }
+        self.turn = Turn::Spawner;
+        true
+    }
+
+    // the empty cells the spawner can choose
+    pub fn candidate_cells(&self) -> Vec<(usize, usize)> {
+        match (self.turn, self.game.game_status) {
+            (Turn::Spawner, GameStatus::INPROGRESS) => empty_cells(&self.game),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn candidate_elements(&self) -> Vec<SpecificElementType> {
+        let mut elements: Vec<SpecificElementType> = SPAWN_VALUES.iter().map(|a| SpecificElementType::Some(*a)).collect();
+        if self.blocks_left > 0 {
+            elements.push(SpecificElementType::Block(ADVERSARY_BLOCK_HP));
+        }
+        elements
+    }
+
+    pub fn spawn(&mut self, choice: SpawnChoice) -> Result<(), String> {
+        if !self.candidate_cells().contains(&choice.idx) {
+            return Err("the spawn shall be on an empty cell during the spawner's turn".to_string());
+        }
+        if !self.candidate_elements().contains(&choice.element) {
+            return Err("this tile cannot be spawned".to_string());
+        }
+        if let SpecificElementType::Block(_) = choice.element {
+            self.blocks_left -= 1;
+        }
+        self.game.board_set_element(choice.idx, Some(choice.element));
+        self.game.game_status = self.game.board_game_status_fn();
+        self.turn = Turn::Player;
+        Ok(())
+    }
+
+    // the spawn leaving the player the worst position
+    pub fn evil_spawn(&self) -> Option<SpawnChoice> {
+        let mut worst: Option<(SpawnChoice, f64)> = None;
+        for idx in self.candidate_cells() {
+            for element in self.candidate_elements() {
+                let mut spawned = self.game.clone();
+                spawned.board_set_element(idx, Some(element));
+                let value = player_value(&spawned);
+                if worst.is_none_or(|(_, worst_value)| value < worst_value) {
+                    worst = Some((SpawnChoice { idx, element }, value));
+                }
+            }
+        }
+        worst.map(|(choice, _)| choice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::levels::cell_from_text;
+
+    fn adversarial_game(rows: &[&str]) -> AdversarialGame<4, 4> {
+        let mut g = AdversarialGame::<4, 4>::new((4, 4), 11).unwrap();
+        for (id_x, row) in rows.iter().enumerate() {
+            for (id_y, cell) in row.split_whitespace().enumerate() {
+                g.game.board_set_element((id_x, id_y), cell_from_text(cell).unwrap());
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn player_and_spawner_take_turns() {
+        let mut g = adversarial_game(&[". . . .", ". . . .", ". . . .", "2 . . ."]);
+        assert_eq!(g.turn, Turn::Player);
+        assert!(g.candidate_cells().is_empty());
+        assert!(g.spawn(SpawnChoice { idx: (0, 0), element: SpecificElementType::Some(2) }).is_err());
+        // a move that changes nothing keeps the player's turn
+        assert!(!g.player_move(AllowedMoves::LEFT));
+        assert_eq!(g.turn, Turn::Player);
+        assert!(g.player_move(AllowedMoves::RIGHT));
+        assert_eq!(g.turn, Turn::Spawner);
+        // no tile spawned by the move itself
+        assert_eq!(g.candidate_cells().len(), 15);
+        assert!(!g.player_move(AllowedMoves::LEFT));
+        assert!(g.spawn(SpawnChoice { idx: (3, 3), element: SpecificElementType::Some(2) }).is_err());
+        assert!(g.spawn(SpawnChoice { idx: (0, 0), element: SpecificElementType::Some(8) }).is_err());
+        assert_eq!(g.spawn(SpawnChoice { idx: (0, 0), element: SpecificElementType::Some(4) }), Ok(()));
+        assert_eq!(g.turn, Turn::Player);
+        assert_eq!(g.game.board_get_element((0, 0)), Some(SpecificElementType::Some(4)));
+    }
+
+    #[test]
+    fn blocks_run_out() {
+        let mut g = adversarial_game(&[". . . .", ". . . .", ". . . .", "2 . . ."]);
+        let block = SpecificElementType::Block(ADVERSARY_BLOCK_HP);
+        for blocks_left in (0..ADVERSARY_BLOCKS).rev() {
+            // the 2 crosses its row at every move, the blocks slide along the top row
+            let move_type = if g.game.board_get_element((3, 0)).is_some() { AllowedMoves::RIGHT } else { AllowedMoves::LEFT };
+            assert!(g.player_move(move_type));
+            assert!(g.candidate_elements().contains(&block));
+            let idx = g.candidate_cells().into_iter().find(|idx| idx.0 == 0).unwrap();
+            assert_eq!(g.spawn(SpawnChoice { idx, element: block }), Ok(()));
+            assert_eq!(g.blocks_left, blocks_left);
+        }
+        assert!(g.player_move(AllowedMoves::DOWN));
+        assert!(!g.candidate_elements().contains(&block));
+        assert!(g.spawn(SpawnChoice { idx: (1, 1), element: block }).is_err());
+        assert_eq!(g.blocks_left, 0);
+    }
+
+    #[test]
+    fn evil_spawn_minimises_the_best_reply() {
+        let mut g = adversarial_game(&["2 4 8 16", "4 8 16 32", ". . 4 2", ". . . ."]);
+        assert!(g.player_move(AllowedMoves::DOWN));
+        let choice = g.evil_spawn().unwrap();
+        let value_of = |choice: SpawnChoice| {
+            let mut spawned = g.game.clone();
+            spawned.board_set_element(choice.idx, Some(choice.element));
+            player_value(&spawned)
+        };
+        let chosen = value_of(choice);
+        for idx in g.candidate_cells() {
+            for element in g.candidate_elements() {
+                assert!(chosen <= value_of(SpawnChoice { idx, element }));
+            }
+        }
+        assert_eq!(g.spawn(choice), Ok(()));
+    }
+
+    #[test]
+    fn no_evil_spawn_on_the_player_turn() {
+        assert_eq!(adversarial_game(&[". . . .", ". . . .", ". . . .", "2 . . ."]).evil_spawn(), None);
+    }
+}
@@ -0,0 +1,15 @@
+// Wall clock of the platform: the browser's clock on the web, the system clock elsewhere
+pub const MILLIS_PER_DAY: u64 = 86_400_000;
+
+// milliseconds since 1970-01-01 UTC
+#[cfg(target_arch = "wasm32")]
+pub fn now_millis() -> u64 {
+    js_sys::Date::now() as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
@@ -0,0 +1,157 @@
+// Daily challenge: the variant, the board size, the obstacles and the spawns of the game of the
+// day only depend on the date, so every player gets the same game on the same (UTC) day
+use crate::game::clock::{now_millis, MILLIS_PER_DAY};
+use crate::game::game_variants::game_variant_1::{GameVariant, GameStatus, SpecificGame, max_tile, populate_game, GameSetup};
+use crate::game::rand_manager::RandManager;
+use crate::variants::{Rules, VariantDef, VARIANTS};
+
+const MIN_SIDE: usize = 4;
+const MAX_SIDE: usize = 6;
+// fixed blocks added to the obstacles of the variant
+const MAX_EXTRA_BLOCKS_FIXED: usize = 2;
+// capacity of the daily boards
+pub const DAILY_CAPACITY: usize = MAX_SIDE;
+// mixed with the day so the daily seeds differ from the small seeds used elsewhere
+const DAILY_SALT: u64 = 0x2048_da11;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailyChallenge {
+    // days since 1970-01-01
+    pub day: u64,
+    pub variant: &'static VariantDef,
+    // (rows, columns)
+    pub size: (usize, usize),
+    pub setup: GameSetup,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailyResult {
+    pub day: u64,
+    pub score: i32,
+    pub max_tile: i32,
+    pub moves: usize,
+    pub status: GameStatus,
+}
+
+// the current day in UTC
+pub fn today() -> u64 {
+    now_millis() / MILLIS_PER_DAY
+}
+
+// YYYY-MM-DD of a day since 1970-01-01 (proleptic gregorian calendar)
+pub fn date_text(day: u64) -> String {
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day_of_month:02}")
+}
+
+impl DailyChallenge {
+
+    pub fn for_day(day: u64) -> DailyChallenge {
+        let mut rand_manager = RandManager::new_seeded(day ^ DAILY_SALT);
+        // the daily boards follow the classic rules and every run shall end
+        let variants: Vec<&'static VariantDef> = VARIANTS.iter().filter(|def| def.rules == Rules::Classic && !def.endless).collect();
+        let variant = variants[rand_manager.gen_range(0, variants.len())];
+        let mut setup = variant.setup;
+        let size = (rand_manager.gen_range(MIN_SIDE, MAX_SIDE + 1), rand_manager.gen_range(MIN_SIDE, MAX_SIDE + 1));
+        setup.blocks_fixed += rand_manager.gen_range(0, MAX_EXTRA_BLOCKS_FIXED + 1);
+        DailyChallenge { day, variant, size, setup, seed: rand_manager.next_u64() }
+    }
+
+    pub fn today() -> DailyChallenge {
+        Self::for_day(today())
+    }
+
+    // the seeded game places the same obstacles and, for the same moves, spawns the same tiles
+    pub fn build<const C_W: usize, const C_H: usize>(&self) -> Result<SpecificGame<C_W, C_H>, String> {
+        let mut g = GameVariant::<C_W, C_H>::new_game_specific_dim_seeded(self.size, self.seed)?;
+        populate_game(&mut g, self.setup);
+        Ok(g)
+    }
+
+    pub fn result<const C_W: usize, const C_H: usize>(&self, g: &SpecificGame<C_W, C_H>) -> DailyResult {
+        DailyResult {
+            day: self.day,
+            score: g.game_variant_data.score,
+            max_tile: max_tile(g),
+            moves: g.game_variant_data.moves,
+            status: g.game_status,
+        }
+    }
+
+    // text shared by the players to compare their runs
+    pub fn summary(&self, result: &DailyResult) -> String {
+        let outcome = match result.status {
+            GameStatus::END_SUCCESS => "won",
+            GameStatus::END_FAIL => "lost",
+            GameStatus::INPROGRESS => "unfinished",
+        };
+        format!(
+            "rusty-2048 daily {}\n{} {}x{}\nscore {} | max tile {} | {} moves | {}",
+            date_text(self.day), self.variant.title, self.size.0, self.size.1, result.score, result.max_tile, result.moves, outcome
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_day_same_challenge() {
+        assert_eq!(DailyChallenge::for_day(20_089), DailyChallenge::for_day(20_089));
+        let g = DailyChallenge::for_day(20_089).build::<DAILY_CAPACITY, DAILY_CAPACITY>().unwrap();
+        assert_eq!(g.game_variant_data.array, DailyChallenge::for_day(20_089).build::<DAILY_CAPACITY, DAILY_CAPACITY>().unwrap().game_variant_data.array);
+    }
+
+    #[test]
+    fn consecutive_days_differ() {
+        let challenges: Vec<DailyChallenge> = (20_000..20_060).map(DailyChallenge::for_day).collect();
+        for pair in challenges.windows(2) {
+            assert_ne!(pair[0].seed, pair[1].seed);
+        }
+        let mut variants: Vec<&str> = challenges.iter().map(|c| c.variant.id).collect();
+        variants.sort_unstable();
+        variants.dedup();
+        assert!(variants.len() > 1);
+    }
+
+    #[test]
+    fn daily_variants_end_and_fit() {
+        for day in 20_000..20_100 {
+            let challenge = DailyChallenge::for_day(day);
+            assert!(!challenge.variant.endless && challenge.variant.rules == Rules::Classic, "{}", challenge.variant.id);
+            assert!((MIN_SIDE..=MAX_SIDE).contains(&challenge.size.0) && (MIN_SIDE..=MAX_SIDE).contains(&challenge.size.1));
+            assert!(challenge.build::<DAILY_CAPACITY, DAILY_CAPACITY>().is_ok());
+        }
+    }
+
+    #[test]
+    fn dates_of_the_utc_days() {
+        assert_eq!(date_text(0), "1970-01-01");
+        assert_eq!(date_text(11_016), "2000-02-29");
+        assert_eq!(date_text(11_017), "2000-03-01");
+        assert_eq!(date_text(47_541), "2100-03-01");
+        // the last and the first millisecond of a year
+        assert_eq!(date_text(1_735_689_599_999 / MILLIS_PER_DAY), "2024-12-31");
+        assert_eq!(date_text(1_735_689_600_000 / MILLIS_PER_DAY), "2025-01-01");
+    }
+
+    #[test]
+    fn summary_of_a_run() {
+        let challenge = DailyChallenge::for_day(20_089);
+        let result = DailyResult { day: 20_089, score: 1234, max_tile: 128, moves: 210, status: GameStatus::END_FAIL };
+        assert_eq!(
+            challenge.summary(&result),
+            format!("rusty-2048 daily 2025-01-01\n{} {}x{}\nscore 1234 | max tile 128 | 210 moves | lost", challenge.variant.title, challenge.size.0, challenge.size.1)
+        );
+    }
+}
@@ -1,4 +1,5 @@
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum AllowedMoves {
     UP,
     DOWN,
@@ -6,12 +7,21 @@ pub enum AllowedMoves {
     RIGHT,
 }
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum GameStatus {
     INPROGRESS,
     END_FAIL,
     END_SUCCESS,
 }
 
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SlideMode {
+    // the tiles slide as far as possible
+    Full,
+    // the tiles move at most one cell, a line stops at its first blocked tile (Threes)
+    OneStep,
+}
+
 pub type IdxType = usize;
 
 #[derive(Copy, Clone, Debug)]
@@ -22,11 +32,13 @@ pub enum BoardIndex {
 
 pub type CtxElementType = (IdxType, IdxType);
 
+#[derive(Clone)]
 pub struct Swap2DGame<GameVariant> {
     board_top_left_corner: (IdxType, IdxType),
     pub board_size: (IdxType, IdxType),
     board_capacity: (IdxType, IdxType),
     pub game_status: GameStatus,
+    pub slide_mode: SlideMode,
     
     // Here the variant is used to own the data used by the custom implementation
     // to simplify the implementation of the callbacks and have a clean code and 
@@ -49,6 +61,11 @@ pub trait RetainerManager<ElementType>{
     fn new() -> Self;
     fn push_and_pop_if_filled(&mut self, ctx_element: CtxElementType, element: Option<ElementType>) -> (RetainerManagerElementType<ElementType>,Self::RetainerMergerInfoType);
     fn pop(&mut self) -> (RetainerManagerElementType<ElementType>,Self::RetainerMergerInfoType);
+
+    // one step slide: the element moving into the neighbour cell `target`, None if it is blocked
+    fn step_into(target: Option<(CtxElementType, ElementType)>, moving: (CtxElementType, ElementType)) -> Option<(ElementType, Self::RetainerMergerInfoType)>;
+    // info of an element that did not move
+    fn still(ctx_element: CtxElementType, element: ElementType) -> Self::RetainerMergerInfoType;
 }
 
 pub trait Swap2DGameConfig {
@@ -75,10 +92,15 @@ impl<GameVariant> Swap2DGame<GameVariant>
             board_size,
             board_capacity,
             game_status: GameStatus::INPROGRESS,
+            slide_mode: SlideMode::Full,
             game_variant_data,
         }
     }
 
+    pub fn board_capacity(&self) -> (IdxType, IdxType) {
+        self.board_capacity
+    }
+
     fn game_end(&mut self) {
         // to be implemented if needed
     }
@@ -98,7 +120,6 @@ impl<GameVariant> Swap2DGame<GameVariant>
             GameStatus::END_FAIL | GameStatus::END_SUCCESS => {
                 return;
             }
-            _ => {}
         }
 
         self.merge(move_type);
@@ -116,9 +137,9 @@ impl<GameVariant> Swap2DGame<GameVariant>
 
     pub fn merge(&mut self, move_direction: AllowedMoves) {
 
-        let mut start_corner: BoardIndex;
-        let mut inner_move : AllowedMoves;
-        let mut outer_move : AllowedMoves;
+        let start_corner: BoardIndex;
+        let inner_move : AllowedMoves;
+        let outer_move : AllowedMoves;
         let mut board_index_outer: BoardIndex;
         let mut board_index_inner: BoardIndex;
         let mut board_index_to_be_filled: BoardIndex;
@@ -146,40 +167,45 @@ impl<GameVariant> Swap2DGame<GameVariant>
         }
 
         board_index_outer = start_corner;
-        board_index_inner = start_corner;
 
-        while let BoardIndex::CorrectIndex(IdxOuter) = board_index_outer {
+        while let BoardIndex::CorrectIndex(idx_outer) = board_index_outer {
+
+            if self.slide_mode == SlideMode::OneStep {
+                self.slide_line_one_step(idx_outer, inner_move);
+                board_index_outer = self.step_2d(idx_outer, outer_move);
+                continue;
+            }
 
             let mut retainer = <Swap2DGame<GameVariant> as Swap2DGameConfig>::RetainerManager::new();
             board_index_to_be_filled = board_index_outer;
             board_index_inner = board_index_outer;
 
-            while let BoardIndex::CorrectIndex(IdxInner) = board_index_inner{
-                let t= self.board_get_element(IdxInner);
-                if let BoardIndex::CorrectIndex(IdxToBeFilled) = board_index_to_be_filled {
-                    match retainer.push_and_pop_if_filled(IdxInner, t) {
+            while let BoardIndex::CorrectIndex(idx_inner) = board_index_inner{
+                let t= self.board_get_element(idx_inner);
+                if let BoardIndex::CorrectIndex(idx_to_be_filled) = board_index_to_be_filled {
+                    match retainer.push_and_pop_if_filled(idx_inner, t) {
                         (RetainerManagerElementType::GameElement(a),info) =>{
-                            self.board_set_element(IdxToBeFilled, Some(a));
-                            self.board_elementary_move_details(IdxToBeFilled, Some(info));
+                            self.board_set_element(idx_to_be_filled, Some(a));
+                            self.board_elementary_move_details(idx_to_be_filled, Some(info));
                             
-                            board_index_to_be_filled = self.step_2d(IdxToBeFilled, inner_move);
+                            board_index_to_be_filled = self.step_2d(idx_to_be_filled, inner_move);
                         }
                         (RetainerManagerElementType::GameElementWithIncrementalIgnore(steps,a),info) => {
 
                             for _ in 0..steps {
-                                if let BoardIndex::CorrectIndex(IdxToBeFilled) = board_index_to_be_filled  {
-                                    self.board_set_element(IdxToBeFilled, None);
-                                    self.board_elementary_move_details(IdxToBeFilled, Some(info));
-                                    board_index_to_be_filled = self.step_2d(IdxToBeFilled, inner_move);
+                                if let BoardIndex::CorrectIndex(idx_to_be_filled) = board_index_to_be_filled  {
+                                    self.board_set_element(idx_to_be_filled, None);
+                                    self.board_elementary_move_details(idx_to_be_filled, Some(info));
+                                    board_index_to_be_filled = self.step_2d(idx_to_be_filled, inner_move);
                                 }
                                 else{
                                     break;
                                 }
                             }
-                            if let BoardIndex::CorrectIndex(IdxToBeFilled) = board_index_to_be_filled  {
-                                self.board_set_element(IdxToBeFilled, Some(a));
-                                self.board_elementary_move_details(IdxToBeFilled, Some(info));
-                                board_index_to_be_filled = self.step_2d(IdxToBeFilled, inner_move);
+                            if let BoardIndex::CorrectIndex(idx_to_be_filled) = board_index_to_be_filled  {
+                                self.board_set_element(idx_to_be_filled, Some(a));
+                                self.board_elementary_move_details(idx_to_be_filled, Some(info));
+                                board_index_to_be_filled = self.step_2d(idx_to_be_filled, inner_move);
                             }
                             
                         }
@@ -187,49 +213,76 @@ impl<GameVariant> Swap2DGame<GameVariant>
                         }
                     }
                 }
-                board_index_inner = self.step_2d(IdxInner, inner_move);
+                board_index_inner = self.step_2d(idx_inner, inner_move);
             }
 
             //flush remaining retainer buffer
-            while let BoardIndex::CorrectIndex(IdxInner) = board_index_to_be_filled{
+            while let BoardIndex::CorrectIndex(idx_inner) = board_index_to_be_filled{
                 match retainer.pop() {
                     (RetainerManagerElementType::GameElement(a),info)  => {
-                        self.board_set_element(IdxInner, Some(a));
-                        self.board_elementary_move_details(IdxInner, Some(info));
+                        self.board_set_element(idx_inner, Some(a));
+                        self.board_elementary_move_details(idx_inner, Some(info));
                     }
                     (RetainerManagerElementType::GameElementWithIncrementalIgnore(steps, a),info) => {
 
 
                         for _ in 0..steps {
-                            if let BoardIndex::CorrectIndex(IdxInner) = board_index_to_be_filled  {
-                                self.board_set_element(IdxInner, None);
-                                self.board_elementary_move_details(IdxInner, Some(info));
-                                board_index_to_be_filled = self.step_2d(IdxInner, inner_move);
+                            if let BoardIndex::CorrectIndex(idx_inner) = board_index_to_be_filled  {
+                                self.board_set_element(idx_inner, None);
+                                self.board_elementary_move_details(idx_inner, Some(info));
+                                board_index_to_be_filled = self.step_2d(idx_inner, inner_move);
 
                             }
                             else{
                                 break;
                             }
                         }
-                        if let BoardIndex::CorrectIndex(IdxInner) = board_index_to_be_filled  {
-                            self.board_set_element(IdxInner, Some(a));
-                            self.board_elementary_move_details(IdxInner, Some(info));
+                        if let BoardIndex::CorrectIndex(idx_inner) = board_index_to_be_filled  {
+                            self.board_set_element(idx_inner, Some(a));
+                            self.board_elementary_move_details(idx_inner, Some(info));
                             
                         }
 
                     }
                     (RetainerManagerElementType::NeutralIgnore,_) => {
-                        self.board_set_element(IdxInner, None);
-                        self.board_elementary_move_details(IdxInner, None);
+                        self.board_set_element(idx_inner, None);
+                        self.board_elementary_move_details(idx_inner, None);
                     }                    
                 }
-                if let BoardIndex::CorrectIndex(IdxInner) = board_index_to_be_filled{
-                    board_index_to_be_filled = self.step_2d(IdxInner, inner_move)
+                if let BoardIndex::CorrectIndex(idx_inner) = board_index_to_be_filled{
+                    board_index_to_be_filled = self.step_2d(idx_inner, inner_move)
                 }
             }
-            board_index_outer =self.step_2d(IdxOuter, outer_move);
+            board_index_outer =self.step_2d(idx_outer, outer_move);
         }
-    }        
+    }
 
+    // walks the line from the side of the move, each element steps into the previous cell
+    // if it is free or if they merge, the cells behind then follow into the freed cells
+    fn slide_line_one_step(&mut self, line_start: (IdxType, IdxType), inner_move: AllowedMoves) {
+        let mut board_index = BoardIndex::CorrectIndex(line_start);
+        while let BoardIndex::CorrectIndex(idx) = board_index {
+            let info = self.board_get_element(idx).map(|e| <Swap2DGame<GameVariant> as Swap2DGameConfig>::RetainerManager::still(idx, e));
+            self.board_elementary_move_details(idx, info);
+            board_index = self.step_2d(idx, inner_move);
+        }
 
-}
+        let mut idx_previous = line_start;
+        board_index = self.step_2d(line_start, inner_move);
+        while let BoardIndex::CorrectIndex(idx) = board_index {
+            if let Some(moving) = self.board_get_element(idx) {
+                // a cell filled during this move always frees the next one, so an element never
+                // steps into an element that already moved
+                let target = self.board_get_element(idx_previous).map(|t| (idx_previous, t));
+                if let Some((element, info)) = <Swap2DGame<GameVariant> as Swap2DGameConfig>::RetainerManager::step_into(target, (idx, moving)) {
+                    self.board_set_element(idx_previous, Some(element));
+                    self.board_elementary_move_details(idx_previous, Some(info));
+                    self.board_set_element(idx, None);
+                    self.board_elementary_move_details(idx, None);
+                }
+            }
+            idx_previous = idx;
+            board_index = self.step_2d(idx, inner_move);
+        }
+    }
+}
\ No newline at end of file
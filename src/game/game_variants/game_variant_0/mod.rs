@@ -8,9 +8,10 @@ pub use game_kernel::AllowedMoves;
 pub use game_kernel::GameStatus;
 
 type SpecificElementType = i32;
+pub type MergerInfo = <RetainerMerger<SpecificElementType> as RetainerManager<SpecificElementType>>::RetainerMergerInfoType;
 pub struct GameVariant<const C_W:usize, const C_H:usize> {
     pub array : [[Option<SpecificElementType>; C_W]; C_H],
-    pub mergers_infos : [[Option<<RetainerMerger<SpecificElementType> as RetainerManager<SpecificElementType>>::RetainerMergerInfoType>; C_W]; C_H],
+    pub mergers_infos : [[Option<MergerInfo>; C_W]; C_H],
     pub nones_number : usize,
     pub score:i32
 }
@@ -19,28 +20,28 @@ pub type SpecificGame<const C_W: usize, const C_H: usize> = Swap2DGame<GameVaria
 fn can_move<const C_W: usize, const C_H: usize>(g:&SpecificGame<C_W, C_H>, idx :(usize, usize)) -> bool {
     let mut temp_element : Option<SpecificElementType> ;
     let ele = g.board_get_element((idx.0, idx.1));
-    if ele == None {
+    if ele.is_none() {
         return true;
     }
-    if let BoardIndex::CorrectIndex(Idx) = g.step_2d((idx.0, idx.1), AllowedMoves::DOWN) {
-        temp_element = g.board_get_element( (Idx.0, Idx.1));
-        if temp_element == None || temp_element == ele {
+    if let BoardIndex::CorrectIndex(neighbour_idx) = g.step_2d((idx.0, idx.1), AllowedMoves::DOWN) {
+        temp_element = g.board_get_element((neighbour_idx.0, neighbour_idx.1));
+        if temp_element.is_none() || temp_element == ele {
             return true;
         }
     }
-    if let BoardIndex::CorrectIndex(Idx) =  g.step_2d((idx.0, idx.1), AllowedMoves::UP)  {
-        temp_element = g.board_get_element((Idx.0, Idx.1));
-        if temp_element == None || temp_element == ele {
+    if let BoardIndex::CorrectIndex(neighbour_idx) =  g.step_2d((idx.0, idx.1), AllowedMoves::UP)  {
+        temp_element = g.board_get_element((neighbour_idx.0, neighbour_idx.1));
+        if temp_element.is_none() || temp_element == ele {
             return true;
         }    }
-    if let BoardIndex::CorrectIndex(Idx) =   g.step_2d((idx.0, idx.1), AllowedMoves::RIGHT)  {
-        temp_element = g.board_get_element((Idx.0, Idx.1));
-        if temp_element == None || temp_element == ele {
+    if let BoardIndex::CorrectIndex(neighbour_idx) =   g.step_2d((idx.0, idx.1), AllowedMoves::RIGHT)  {
+        temp_element = g.board_get_element((neighbour_idx.0, neighbour_idx.1));
+        if temp_element.is_none() || temp_element == ele {
             return true;
         }    }
-    if let BoardIndex::CorrectIndex(Idx) =   g.step_2d((idx.0, idx.1), AllowedMoves::LEFT)  {
-        temp_element = g.board_get_element((Idx.0, Idx.1));
-        if temp_element == None || temp_element == ele {
+    if let BoardIndex::CorrectIndex(neighbour_idx) =   g.step_2d((idx.0, idx.1), AllowedMoves::LEFT)  {
+        temp_element = g.board_get_element((neighbour_idx.0, neighbour_idx.1));
+        if temp_element.is_none() || temp_element == ele {
             return true;
         }
     }
@@ -55,8 +56,8 @@ fn is_status_changed<const C_W: usize, const C_H: usize>(g:&SpecificGame<C_W, C_
                 if let  RetainerMergerInfo::Merged((_, _), (_, _)) = merger_info {
                     return true;
                 }
-                else if let RetainerMergerInfo::NotMerged(OldIdx, _) = merger_info {
-                    if OldIdx != (id_x, id_y) {
+                else if let RetainerMergerInfo::NotMerged(old_idx, _) = merger_info {
+                    if old_idx != (id_x, id_y) {
                         return true;
                     }
                 }
@@ -69,7 +70,7 @@ fn is_status_changed<const C_W: usize, const C_H: usize>(g:&SpecificGame<C_W, C_
 fn get_rand_idx(start_idx: usize, end_idx: usize) -> usize {
     //let insert_idx = thread_rng().gen_range(0..g.game_custom_info.nones_number);
     let mut random_value: [u8;1] = [0];
-    getrandom::getrandom(&mut random_value);
+    let _ = getrandom::getrandom(&mut random_value);
     (random_value[0] as usize) % (end_idx - start_idx) + start_idx
 }
 
@@ -77,7 +78,7 @@ fn get_rand_value() -> Option<SpecificElementType>{
 
     let mut random_value: [u8;1] = [0];
 
-    getrandom::getrandom(&mut random_value);
+    let _ = getrandom::getrandom(&mut random_value);
 
     // we divide the 256 values [0->255] into 9 (0->8) of 25 ranges + last range 25+6 so
     // Some(4) will have around 12,10 % of proba
@@ -102,7 +103,7 @@ impl<const W: usize, const H: usize> Swap2DGameConfig for Swap2DGame<GameVariant
 
     fn board_set_element(&mut self, idx: (usize, usize), element: Option<Self::ElementType>) {
         match (self.game_variant_data.array[idx.0][idx.1], element) {
-            (Some(a), None) => self.game_variant_data.nones_number+=1,
+            (Some(_a), None) => self.game_variant_data.nones_number+=1,
             (None, Some(_a)) => self.game_variant_data.nones_number-=1,
             _ => {}
         }
@@ -122,15 +123,15 @@ impl<const W: usize, const H: usize> Swap2DGameConfig for Swap2DGame<GameVariant
                     self.board_set_element((id_x, id_y), get_rand_value());
                     //println!("hhhhh {:?}", (id_x, id_y));
                 }
-                if ele == None {
+                if ele.is_none() {
                     curr_idx += 1;
                 }
             }
         }
     }
 
-    fn board_elementary_move_details(&mut self, Idx: (usize, usize), retainer_merger_info: Option<<<Swap2DGame<GameVariant<W, H>> as Swap2DGameConfig>::RetainerManager as RetainerManager<Self::ElementType>>::RetainerMergerInfoType>) {
-        self.game_variant_data.mergers_infos[Idx.0][Idx.1] = retainer_merger_info;
+    fn board_elementary_move_details(&mut self, idx: (usize, usize), retainer_merger_info: Option<MergerInfo>) {
+        self.game_variant_data.mergers_infos[idx.0][idx.1] = retainer_merger_info;
         if let  Some(RetainerMergerInfo::Merged((_, element_1), (_, element_2))) = retainer_merger_info {
             self.game_variant_data.score += element_1 + element_2;
         }
@@ -159,7 +160,7 @@ impl<const C_W: usize, const C_H: usize> GameVariant<C_W, C_H> {
 
     // the size shall be under the capacity
     pub fn new_game_specific_dim(dim : (usize, usize)) -> Result<SpecificGame<C_W, C_H>, String>{
-        if (dim.0 <= C_W && dim.1 <= C_H) {
+        if dim.0 <= C_W && dim.1 <= C_H {
             let rand_idx_0_start = get_rand_idx(0, C_H);
             let rand_idx_1_start = get_rand_idx(0, C_W);
             let mut game_variant = GameVariant {
@@ -173,7 +174,7 @@ impl<const C_W: usize, const C_H: usize> GameVariant<C_W, C_H> {
             game_variant.nones_number -= 1;
 
             let g = SpecificGame::game_init((C_W, C_H), (C_W, C_H), game_variant);
-            return Ok(g);
+            Ok(g)
         }
         else {
             Err("The dimension wanted is above the capacity".to_string())
@@ -1,25 +1,171 @@
-use dioxus::html::u::is;
-use getrandom;
 use crate::game::game_kernel as game_kernel;
 use game_kernel::{Swap2DGame, Swap2DGameConfig, RetainerManager, BoardIndex};
 use crate::game::retainer_merger_variants::retainer_merger_variant_1::RetainerMerger as RetainerMerger;
-use crate::game::retainer_merger_variants::retainer_merger_variant_1::{RetainerMergerInfo, SpecificElementType as SpecificElementTypeVariant1 };
-use std::ops::Add;
+use crate::game::retainer_merger_variants::retainer_merger_variant_1::SpecificElementType as SpecificElementTypeVariant1;
+pub use crate::game::retainer_merger_variants::retainer_merger_variant_1::RetainerMergerInfo;
+use crate::game::rand_manager::RandManager;
+use crate::game::merge_rules::{MergeRule, ClassicMergeRule, FibonacciMergeRule, TriplingMergeRule, ThreesMergeRule};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
 
 pub use game_kernel::AllowedMoves;
 pub use game_kernel::GameStatus;
+pub use game_kernel::SlideMode;
 
 type T = i32;
 pub type SpecificElementType = SpecificElementTypeVariant1<i32>;
-pub struct GameVariant<const C_W:usize, const C_H:usize> {
+pub type MergerInfo = <RetainerMerger<T> as RetainerManager<SpecificElementTypeVariant1<T>>>::RetainerMergerInfoType;
+
+// where the new tile enters the board after a move
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpawnPlace {
+    RandomEmptyCell,
+    // a random empty cell of the trailing edge among the moved lines, the value is known one
+    // move in advance (Threes)
+    TrailingEdge,
+}
+
+// who places the new tile after a move
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpawnSource {
+    Random,
+    // an adversary places it between the moves, see the adversary module
+    External,
+}
+
+// what happens to the numbers that are not merged for a number of moves
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TimedTiles {
+    Off,
+    // the number vanishes
+    Decaying(usize),
+    // the number turns into a fixed block
+    Petrifying(usize),
+}
+
+impl TimedTiles {
+    // moves left before a number of this age expires
+    pub fn moves_left(&self, age: usize) -> Option<usize> {
+        match self {
+            TimedTiles::Off => None,
+            TimedTiles::Decaying(limit) | TimedTiles::Petrifying(limit) => Some(limit.saturating_sub(age)),
+        }
+    }
+}
+
+// special tiles spawned instead of a number, the chances are per thousand spawns
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpawnConfig {
+    pub joker_per_mille : u32,
+    pub bomb_per_mille : u32,
+    // moves before a spawned bomb detonates by itself
+    pub bomb_countdown : usize,
+    pub block_per_mille : u32,
+    // hit points of the blocks, the initial ones included
+    pub block_hp : usize,
+    // applies to the spawned numbers, the initial ones included
+    pub timed : TimedTiles,
+}
+
+impl SpawnConfig {
+    pub const NUMBERS_ONLY: SpawnConfig = SpawnConfig { joker_per_mille: 0, bomb_per_mille: 0, bomb_countdown: 0, block_per_mille: 0, block_hp: 1, timed: TimedTiles::Off };
+
+    // the numbers are timed when the timed tiles are on
+    pub fn with_timer(&self, element: Option<SpecificElementType>) -> Option<SpecificElementType> {
+        match (self.timed, element) {
+            (TimedTiles::Off, _) => element,
+            (_, Some(SpecificElementType::Some(a))) => Some(SpecificElementType::Timed(a, 0)),
+            _ => element,
+        }
+    }
+}
+
+// what the player has to achieve to win
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Goal {
+    ReachTile(T),
+    // no movable block left on the board
+    ClearBlocks,
+    Score(T),
+}
+
+// Rules of a variant on top of its merge rule: the spawned values with their weights, the
+// tile to reach and the rank of a value in the merge sequence (0 for the smallest spawned value)
+pub trait VariantRules: MergeRule<T> + Clone + PartialEq + 'static {
+    const SPAWN_WEIGHTS: &'static [(T, u32)];
+    const TARGET_VALUE: T;
+    const SLIDE_MODE: SlideMode = SlideMode::Full;
+    const SPAWN_PLACE: SpawnPlace = SpawnPlace::RandomEmptyCell;
+    fn tile_rank(value: T) -> usize;
+}
+
+impl VariantRules for ClassicMergeRule {
+    // 2 with 90 % of proba and 4 with 10 % of proba
+    const SPAWN_WEIGHTS: &'static [(T, u32)] = &[(2, 9), (4, 1)];
+    const TARGET_VALUE: T = 2048;
+    fn tile_rank(value: T) -> usize {
+        value.max(2).ilog2() as usize - 1
+    }
+}
+
+impl VariantRules for FibonacciMergeRule {
+    const SPAWN_WEIGHTS: &'static [(T, u32)] = &[(1, 3), (2, 1)];
+    const TARGET_VALUE: T = 610;
+    fn tile_rank(value: T) -> usize {
+        let (mut f_0, mut f_1, mut rank) = (1, 2, 0);
+        while f_0 < value {
+            (f_0, f_1, rank) = (f_1, f_0 + f_1, rank + 1);
+        }
+        rank
+    }
+}
+
+impl VariantRules for TriplingMergeRule {
+    const SPAWN_WEIGHTS: &'static [(T, u32)] = &[(3, 9), (9, 1)];
+    const TARGET_VALUE: T = 177147;
+    fn tile_rank(value: T) -> usize {
+        value.max(3).ilog(3) as usize - 1
+    }
+}
+
+impl VariantRules for ThreesMergeRule {
+    const SPAWN_WEIGHTS: &'static [(T, u32)] = &[(1, 1), (2, 1), (3, 1)];
+    const TARGET_VALUE: T = 384;
+    const SLIDE_MODE: SlideMode = SlideMode::OneStep;
+    const SPAWN_PLACE: SpawnPlace = SpawnPlace::TrailingEdge;
+    // 1 and 2 share the first rank
+    fn tile_rank(value: T) -> usize {
+        if value < 3 { 0 } else { (value / 3).ilog2() as usize + 1 }
+    }
+}
+
+#[derive(Clone)]
+pub struct GameVariant<const C_W:usize, const C_H:usize, R = ClassicMergeRule> {
     pub array : [[Option<SpecificElementType>; C_W]; C_H],
-    pub mergers_infos : [[Option<<RetainerMerger<T> as RetainerManager<SpecificElementTypeVariant1<T>>>::RetainerMergerInfoType>; C_W]; C_H],
+    pub mergers_infos : [[Option<MergerInfo>; C_W]; C_H],
     pub nones_number : usize,
-    pub score:i32
+    pub score:i32,
+    pub rand_manager: RandManager,
+    // seed the game was created with, None when it is not known anymore (older saves)
+    pub seed: Option<u64>,
+    // preview of the next spawned value, only drawn in advance for the trailing edge spawn
+    pub next_value: Option<SpecificElementType>,
+    pub spawn_config: SpawnConfig,
+    // spawned values with their weights, the ones of the rules unless the settings change them
+    pub spawn_weights: Vec<(T, u32)>,
+    pub goal: Goal,
+    // the game is lost when the goal is not achieved within this number of moves
+    pub move_limit: Option<usize>,
+    // moves that changed the board
+    pub moves: usize,
+    // values of the next spawns in order, once used up no tile spawns anymore
+    pub spawn_sequence: Option<VecDeque<T>>,
+    pub spawn_source: SpawnSource,
+    pub rules: PhantomData<R>,
 }
-pub type SpecificGame<const C_W: usize, const C_H: usize> = Swap2DGame<GameVariant<C_W, C_H>>;
+pub type SpecificGame<const C_W: usize, const C_H: usize, R = ClassicMergeRule> = Swap2DGame<GameVariant<C_W, C_H, R>>;
 
-impl<const C_W: usize, const C_H: usize> PartialEq for GameVariant<C_W, C_H>{
+impl<const C_W: usize, const C_H: usize, R> PartialEq for GameVariant<C_W, C_H, R>{
     fn eq(&self, other :&Self) -> bool {
             for id_x in 0..C_H {
                 for id_y in 0..C_W {
@@ -32,57 +178,36 @@ impl<const C_W: usize, const C_H: usize> PartialEq for GameVariant<C_W, C_H>{
     }
 }
 
-fn can_move<const C_W: usize, const C_H: usize>(g:&SpecificGame<C_W, C_H>, idx :(usize, usize)) -> bool {
-    let mut temp_element : Option<SpecificElementType> ;
+fn can_move<const C_W: usize, const C_H: usize, R: VariantRules>(g:&SpecificGame<C_W, C_H, R>, idx :(usize, usize)) -> bool {
     let ele = g.board_get_element((idx.0, idx.1));
-    if ele == None {
+    if ele.is_none() {
         return true;
     }
-    if let BoardIndex::CorrectIndex(Idx) = g.step_2d((idx.0, idx.1), AllowedMoves::DOWN) {
-        temp_element = g.board_get_element( (Idx.0, Idx.1));
-        if temp_element == None || ((temp_element == ele) && ((temp_element != Some(SpecificElementType::BlockFixed)) && (temp_element != Some(SpecificElementType::Block)))) {
-            return true;
-        }
-    }
-    if let BoardIndex::CorrectIndex(Idx) =  g.step_2d((idx.0, idx.1), AllowedMoves::UP)  {
-        temp_element = g.board_get_element((Idx.0, Idx.1));
-        if temp_element == None || ((temp_element == ele) &&  ((temp_element != Some(SpecificElementType::BlockFixed)) && (temp_element != Some(SpecificElementType::Block))))  {
-            return true;
-        }    }
-    if let BoardIndex::CorrectIndex(Idx) =   g.step_2d((idx.0, idx.1), AllowedMoves::RIGHT)  {
-        temp_element = g.board_get_element((Idx.0, Idx.1));
-        if temp_element == None ||  ((temp_element == ele) &&  ((temp_element != Some(SpecificElementType::BlockFixed)) && (temp_element != Some(SpecificElementType::Block)))) {
-            return true;
-        }    }
-    if let BoardIndex::CorrectIndex(Idx) =   g.step_2d((idx.0, idx.1), AllowedMoves::LEFT)  {
-        temp_element = g.board_get_element((Idx.0, Idx.1));
-        if temp_element == None ||  ((temp_element == ele) &&  ((temp_element != Some(SpecificElementType::BlockFixed)) && (temp_element != Some(SpecificElementType::Block))))  {
-            return true;
+    for move_type in [AllowedMoves::DOWN, AllowedMoves::UP, AllowedMoves::RIGHT, AllowedMoves::LEFT] {
+        if let BoardIndex::CorrectIndex(neighbour_idx) = g.step_2d((idx.0, idx.1), move_type) {
+            match (ele, g.board_get_element((neighbour_idx.0, neighbour_idx.1))) {
+                (_, None) => return true,
+                (Some(a), Some(b)) if a.merge_with::<R>(b).is_some() => return true,
+                _ => {}
+            }
         }
     }
     false
 }
 
 // can be optimized later
-fn is_status_changed<const C_W: usize, const C_H: usize>(g:&SpecificGame<C_W, C_H>) -> bool {
+pub fn is_status_changed<const C_W: usize, const C_H: usize, R: VariantRules>(g:&SpecificGame<C_W, C_H, R>) -> bool {
     for id_x in 0..g.board_size.0 {
         for id_y in 0..g.board_size.1 {
-            if let Some(merger_info) = g.game_variant_data.mergers_infos[id_x][id_y] {
-                if let  RetainerMergerInfo::Merged((_, _), (_, _)) = merger_info {
-                    return true;
-                }
-                else if let RetainerMergerInfo::NotMerged(OldIdx, _) = merger_info {
-                    if OldIdx != (id_x, id_y) {
-                        return true;
-                    }
-                }
+            if has_moved(g.game_variant_data.mergers_infos[id_x][id_y], (id_x, id_y)) {
+                return true;
             }
         }
     }
     false
 }
 
-pub fn set_nth_none_element<const C_W: usize, const C_H: usize>(g:&mut SpecificGame<C_W, C_H>, insert_idx : usize, ele_set : Option<<SpecificGame<C_W, C_H> as Swap2DGameConfig>::ElementType>){
+pub fn set_nth_none_element<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>, insert_idx : usize, ele_set : Option<SpecificElementType>){
     let mut curr_idx = 0;
     for id_x in 0..g.board_size.0 {
         for id_y in 0..g.board_size.1 {
@@ -90,42 +215,214 @@ pub fn set_nth_none_element<const C_W: usize, const C_H: usize>(g:&mut SpecificG
             if (ele, curr_idx) == (None, insert_idx) {
                 g.board_set_element((id_x, id_y), ele_set);
             }
-            if ele == None {
+            if ele.is_none() {
                 curr_idx += 1;
             }
         }
     }
 }
 
-pub fn get_rand_idx(start_idx: usize, end_idx: usize) -> usize {
-    //let insert_idx = thread_rng().gen_range(0..g.game_custom_info.nones_number);
-    let mut random_value: [u8;1] = [0];
-    getrandom::getrandom(&mut random_value);
-    (random_value[0] as usize) % (end_idx - start_idx) + start_idx
+// puts the element in a random empty cell of the board, nothing is done if the board is full
+pub fn set_rand_none_element<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>, ele_set : Option<SpecificElementType>){
+    if g.game_variant_data.nones_number == 0 {
+        return;
+    }
+    let insert_idx = get_rand_idx(&mut g.game_variant_data.rand_manager, 0, g.game_variant_data.nones_number);
+    set_nth_none_element(g, insert_idx, ele_set);
 }
 
-pub fn get_rand_value() -> Option<SpecificElementType>{
+pub fn get_rand_idx(rand_manager: &mut RandManager, start_idx: usize, end_idx: usize) -> usize {
+    rand_manager.gen_range(start_idx, end_idx)
+}
+
+// value drawn following the spawn weights, None without any weight
+pub fn get_rand_value(rand_manager: &mut RandManager, spawn_weights: &[(T, u32)]) -> Option<SpecificElementType>{
 
-    let mut random_value: [u8;1] = [0];
+    // summed in u64 so the weights of a save or a link cannot overflow, the draw is the one of
+    // `gen_range`
+    let total_weight: u64 = spawn_weights.iter().map(|(_, weight)| u64::from(*weight)).sum();
+    if total_weight == 0 {
+        return None;
+    }
+    let mut random_value = rand_manager.next_u64() % total_weight;
 
-    getrandom::getrandom(&mut random_value);
+    for (value, weight) in spawn_weights {
+        if random_value < u64::from(*weight) {
+            return Some(SpecificElementType::Some(*value));
+        }
+        random_value -= u64::from(*weight);
+    }
+    None
+}
+
+// a special tile following the spawn config or else a value following the spawn weights
+fn get_rand_spawn(rand_manager: &mut RandManager, spawn_weights: &[(T, u32)], spawn_config: SpawnConfig) -> Option<SpecificElementType> {
+    if spawn_config.joker_per_mille > 0 && (rand_manager.gen_range(0, 1000) as u32) < spawn_config.joker_per_mille {
+        return Some(SpecificElementType::Joker);
+    }
+    if spawn_config.bomb_per_mille > 0 && (rand_manager.gen_range(0, 1000) as u32) < spawn_config.bomb_per_mille {
+        return Some(SpecificElementType::Bomb(spawn_config.bomb_countdown));
+    }
+    if spawn_config.block_per_mille > 0 && (rand_manager.gen_range(0, 1000) as u32) < spawn_config.block_per_mille {
+        return Some(SpecificElementType::Block(spawn_config.block_hp));
+    }
+    spawn_config.with_timer(get_rand_value(rand_manager, spawn_weights))
+}
+
+// the value of the next spawn, taken from the preview when the rules show one
+fn draw_spawn_value<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>) -> Option<SpecificElementType> {
+    if let Some(spawn_sequence) = &mut g.game_variant_data.spawn_sequence {
+        return spawn_sequence.pop_front().map(SpecificElementType::Some);
+    }
+    let data = &mut g.game_variant_data;
+    let value = get_rand_spawn(&mut data.rand_manager, &data.spawn_weights, data.spawn_config);
+    match R::SPAWN_PLACE {
+        SpawnPlace::RandomEmptyCell => value,
+        SpawnPlace::TrailingEdge => std::mem::replace(&mut g.game_variant_data.next_value, value),
+    }
+}
+
+fn has_moved(info: Option<MergerInfo>, idx: (usize, usize)) -> bool {
+    match info {
+        Some(RetainerMergerInfo::Merged(_, _)) => true,
+        Some(RetainerMergerInfo::NotMerged(old_idx, _)) => old_idx != idx,
+        Some(RetainerMergerInfo::Detonated(_, _)) | Some(RetainerMergerInfo::Destroyed(_)) => true,
+        _ => false,
+    }
+}
+
+// the bombs armed by a merge or at the end of their countdown clear their 3x3 neighbourhood
+// except the fixed blocks, a bomb in the neighbourhood detonates too
+fn detonate_bombs<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>) {
+    let (rows, cols) = g.board_size;
+    let mut detonating = Vec::new();
+    for id_x in 0..rows {
+        for id_y in 0..cols {
+            match g.board_get_element((id_x, id_y)) {
+                Some(SpecificElementType::Bomb(countdown)) if countdown <= 1 => detonating.push((id_x, id_y)),
+                Some(SpecificElementType::Bomb(countdown)) => g.board_set_element((id_x, id_y), Some(SpecificElementType::Bomb(countdown - 1))),
+                _ => {}
+            }
+        }
+    }
+
+    while let Some(bomb_idx) = detonating.pop() {
+        for id_x in bomb_idx.0.saturating_sub(1)..(bomb_idx.0 + 2).min(rows) {
+            for id_y in bomb_idx.1.saturating_sub(1)..(bomb_idx.1 + 2).min(cols) {
+                let cleared = match g.board_get_element((id_x, id_y)) {
+                    None | Some(SpecificElementType::BlockFixed) => continue,
+                    Some(SpecificElementType::Bomb(_)) if (id_x, id_y) != bomb_idx => {
+                        detonating.push((id_x, id_y));
+                        continue;
+                    }
+                    Some(cleared) => cleared,
+                };
+                if let Some(a) = cleared.number() {
+                    g.game_variant_data.score += a;
+                }
+                g.board_set_element((id_x, id_y), None);
+                g.game_variant_data.mergers_infos[id_x][id_y] = Some(RetainerMergerInfo::Detonated(bomb_idx, cleared));
+            }
+        }
+    }
+}
+
+// every merge damages the blocks orthogonally next to it, a block without hit points is removed
+fn damage_blocks<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>) {
+    let mut merged_cells = Vec::new();
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            if let Some(RetainerMergerInfo::Merged(_, _)) = g.game_variant_data.mergers_infos[id_x][id_y] {
+                merged_cells.push((id_x, id_y));
+            }
+        }
+    }
+
+    for idx in merged_cells {
+        for move_type in [AllowedMoves::DOWN, AllowedMoves::UP, AllowedMoves::RIGHT, AllowedMoves::LEFT] {
+            if let BoardIndex::CorrectIndex(neighbour_idx) = g.step_2d(idx, move_type) {
+                match g.board_get_element(neighbour_idx) {
+                    Some(SpecificElementType::Block(hp)) if hp <= 1 => {
+                        g.board_set_element(neighbour_idx, None);
+                        g.game_variant_data.mergers_infos[neighbour_idx.0][neighbour_idx.1] = Some(RetainerMergerInfo::Destroyed(SpecificElementType::Block(hp)));
+                    }
+                    Some(SpecificElementType::Block(hp)) => g.board_set_element(neighbour_idx, Some(SpecificElementType::Block(hp - 1))),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
 
-    // we divide the 256 values [0->255] into 9 (0->8) of 25 ranges + last range 25+6 so
-    // Some(4) will have around 12,10 % of proba
-    // Some(2) will have around 87,89 % of proba
+// the timed numbers that did not merge during the move get older, at the limit they vanish or
+// turn into fixed blocks
+fn age_timed_tiles<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>) {
+    let (limit, expired) = match g.game_variant_data.spawn_config.timed {
+        TimedTiles::Off => return,
+        TimedTiles::Decaying(limit) => (limit, None),
+        TimedTiles::Petrifying(limit) => (limit, Some(SpecificElementType::BlockFixed)),
+    };
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            let merged = matches!(g.game_variant_data.mergers_infos[id_x][id_y], Some(RetainerMergerInfo::Merged(_, _)));
+            match g.board_get_element((id_x, id_y)) {
+                Some(SpecificElementType::Timed(_, _)) if merged => {}
+                Some(timed @ SpecificElementType::Timed(_, age)) if age + 1 >= limit => {
+                    g.board_set_element((id_x, id_y), expired);
+                    if expired.is_none() {
+                        g.game_variant_data.mergers_infos[id_x][id_y] = Some(RetainerMergerInfo::Destroyed(timed));
+                    }
+                }
+                Some(SpecificElementType::Timed(a, age)) => g.board_set_element((id_x, id_y), Some(SpecificElementType::Timed(a, age + 1))),
+                _ => {}
+            }
+        }
+    }
+}
 
-    if (random_value[0] / 25)  < 9 {
-        Some(SpecificElementType::Some(2))
-    } else {
-        Some(SpecificElementType::Some(2))
+// empty cells of the edge opposite to the move whose line moved
+pub fn trailing_edge_cells<const C_W: usize, const C_H: usize, R: VariantRules>(g:&SpecificGame<C_W, C_H, R>, move_type: AllowedMoves) -> Vec<(usize, usize)> {
+    let (rows, cols) = g.board_size;
+    let lines: Vec<Vec<(usize, usize)>> = match move_type {
+        AllowedMoves::LEFT | AllowedMoves::RIGHT => (0..rows).map(|id_x| (0..cols).map(|id_y| (id_x, id_y)).collect()).collect(),
+        AllowedMoves::UP | AllowedMoves::DOWN => (0..cols).map(|id_y| (0..rows).map(|id_x| (id_x, id_y)).collect()).collect(),
+    };
+    let mut cells = Vec::new();
+    for line in lines {
+        let trailing = match move_type {
+            AllowedMoves::LEFT | AllowedMoves::UP => line[line.len() - 1],
+            AllowedMoves::RIGHT | AllowedMoves::DOWN => line[0],
+        };
+        let moved = line.iter().any(|idx| has_moved(g.game_variant_data.mergers_infos[idx.0][idx.1], *idx));
+        if moved && g.board_get_element(trailing).is_none() {
+            cells.push(trailing);
+        }
     }
+    cells
+}
 
+// biggest number on the board, 0 without numbers
+pub fn max_tile<const C_W: usize, const C_H: usize, R: VariantRules>(g:&SpecificGame<C_W, C_H, R>) -> T {
+    (0..g.board_size.0)
+        .flat_map(|id_x| (0..g.board_size.1).map(move |id_y| (id_x, id_y)))
+        .filter_map(|idx| g.board_get_element(idx).and_then(|e| e.number()))
+        .max()
+        .unwrap_or(0)
 }
 
-impl<const W: usize, const H: usize> Swap2DGameConfig for Swap2DGame<GameVariant<W, H>> {
+pub fn is_goal_achieved<const C_W: usize, const C_H: usize, R: VariantRules>(g:&SpecificGame<C_W, C_H, R>) -> bool {
+    let mut cells = (0..g.board_size.0).flat_map(|id_x| (0..g.board_size.1).map(move |id_y| (id_x, id_y)));
+    match g.game_variant_data.goal {
+        Goal::ReachTile(target) => cells.any(|idx| g.board_get_element(idx).and_then(|e| e.number()).is_some_and(|a| a >= target)),
+        Goal::ClearBlocks => !cells.any(|idx| matches!(g.board_get_element(idx), Some(SpecificElementType::Block(_)))),
+        Goal::Score(target) => g.game_variant_data.score >= target,
+    }
+}
+
+impl<const W: usize, const H: usize, R: VariantRules> Swap2DGameConfig for Swap2DGame<GameVariant<W, H, R>> {
 
     type ElementType = SpecificElementType;
-    type RetainerManager = RetainerMerger<T>;
+    type RetainerManager = RetainerMerger<T, R>;
 
     fn board_get_element(&self, idx: (usize, usize)) -> Option<Self::ElementType> {
         self.game_variant_data.array[idx.0][idx.1]
@@ -133,35 +430,57 @@ impl<const W: usize, const H: usize> Swap2DGameConfig for Swap2DGame<GameVariant
 
     fn board_set_element(&mut self, idx: (usize, usize), element: Option<Self::ElementType>) {
         match (self.game_variant_data.array[idx.0][idx.1], element) {
-            (Some(a), None) => self.game_variant_data.nones_number+=1,
+            (Some(_a), None) => self.game_variant_data.nones_number+=1,
             (None, Some(_a)) => self.game_variant_data.nones_number-=1,
             _ => {}
         }
         self.game_variant_data.array[idx.0][idx.1] = element;
     }
 
-    fn board_update_after_move(&mut self, _: AllowedMoves) {
-        if self.game_variant_data.nones_number == 0 || !is_status_changed(self) { return;}
+    fn board_update_after_move(&mut self, move_type: AllowedMoves) {
+        if !is_status_changed(self) { return;}
 
-        let insert_idx = get_rand_idx(0, self.game_variant_data.nones_number);
+        self.game_variant_data.moves += 1;
+        damage_blocks(self);
+        detonate_bombs(self);
+        age_timed_tiles(self);
+        if self.game_variant_data.nones_number == 0 || self.game_variant_data.spawn_source == SpawnSource::External { return;}
 
-        set_nth_none_element(self, insert_idx,get_rand_value());
+        match R::SPAWN_PLACE {
+            SpawnPlace::RandomEmptyCell => {
+                if let Some(ele_set) = draw_spawn_value(self) {
+                    set_rand_none_element(self, Some(ele_set));
+                }
+            }
+            SpawnPlace::TrailingEdge => {
+                let cells = trailing_edge_cells(self, move_type);
+                if !cells.is_empty() {
+                    let idx = cells[get_rand_idx(&mut self.game_variant_data.rand_manager, 0, cells.len())];
+                    let ele_set = draw_spawn_value(self);
+                    self.board_set_element(idx, ele_set);
+                }
+            }
+        }
     }
 
-    fn board_elementary_move_details(&mut self, Idx: (usize, usize), retainer_merger_info: Option<<<Swap2DGame<GameVariant<W, H>> as Swap2DGameConfig>::RetainerManager as RetainerManager<Self::ElementType>>::RetainerMergerInfoType>) {
-        self.game_variant_data.mergers_infos[Idx.0][Idx.1] = retainer_merger_info;
-        if let  Some(RetainerMergerInfo::Merged((_, SpecificElementType::Some(a)), (_,  SpecificElementType::Some(b)))) = retainer_merger_info {
-            self.game_variant_data.score += a + b;
+    fn board_elementary_move_details(&mut self, idx: (usize, usize), retainer_merger_info: Option<MergerInfo>) {
+        self.game_variant_data.mergers_infos[idx.0][idx.1] = retainer_merger_info;
+        // the merged element is already set on the board
+        if let (Some(RetainerMergerInfo::Merged(_, _)), Some(a)) = (retainer_merger_info, self.board_get_element(idx).and_then(|e| e.number())) {
+            self.game_variant_data.score += a;
         }
     }
 
     fn board_game_status_fn(&self) -> GameStatus {
+        if is_goal_achieved(self) {
+            return GameStatus::END_SUCCESS;
+        }
+        if self.game_variant_data.move_limit.is_some_and(|limit| self.game_variant_data.moves >= limit) {
+            return GameStatus::END_FAIL;
+        }
         let mut able_to_move = false;
         for id_x in 0..self.board_size.0 {
             for id_y in 0..self.board_size.1 {
-                if self.board_get_element((id_x, id_y)) == Some(SpecificElementType::Some(2048)) {
-                    return GameStatus::END_SUCCESS;
-                }
                 if can_move(self, (id_x, id_y)){
                    able_to_move = true;
                 }
@@ -174,21 +493,72 @@ impl<const W: usize, const H: usize> Swap2DGameConfig for Swap2DGame<GameVariant
     }
 }
 
-impl<const C_W: usize, const C_H: usize> GameVariant<C_W, C_H> {
+// Initial content of a board, the tiles and obstacles are dropped at random empty cells
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GameSetup {
+    pub start_tiles : usize,
+    pub blocks_fixed : usize,
+    pub blocks : usize,
+    pub spawn : SpawnConfig,
+}
+
+pub fn populate_game<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>, setup : GameSetup) {
+    g.game_variant_data.spawn_config = setup.spawn;
+    for _ in 0..setup.start_tiles {
+        let ele_set = setup.spawn.with_timer(get_rand_value(&mut g.game_variant_data.rand_manager, &g.game_variant_data.spawn_weights));
+        set_rand_none_element(g, ele_set);
+    }
+    for _ in 0..setup.blocks_fixed {
+        set_rand_none_element(g, Some(SpecificElementType::BlockFixed));
+    }
+    for _ in 0..setup.blocks {
+        set_rand_none_element(g, Some(SpecificElementType::Block(setup.spawn.block_hp)));
+    }
+}
+
+// the preview of the next value is drawn again with the new weights
+pub fn set_spawn_weights<const C_W: usize, const C_H: usize, R: VariantRules>(g:&mut SpecificGame<C_W, C_H, R>, spawn_weights : Vec<(T, u32)>) {
+    g.game_variant_data.spawn_weights = spawn_weights;
+    if R::SPAWN_PLACE == SpawnPlace::TrailingEdge {
+        g.game_variant_data.next_value = get_rand_value(&mut g.game_variant_data.rand_manager, &g.game_variant_data.spawn_weights);
+    }
+}
+
+impl<const C_W: usize, const C_H: usize, R: VariantRules> GameVariant<C_W, C_H, R> {
 
-    // the size shall be under the capacity
-    pub fn new_game_specific_dim(dim : (usize, usize)) -> Result<SpecificGame<C_W, C_H>, String>{
-        if (dim.0 <= C_W && dim.1 <= C_H) {
-            let mut game_variant = GameVariant {
+    // the size (rows, columns) shall be under the capacity
+    pub fn new_game_specific_dim(dim : (usize, usize)) -> Result<SpecificGame<C_W, C_H, R>, String>{
+        Self::new_game_specific_dim_seeded(dim, RandManager::entropy_seed())
+    }
+
+    // same seed and same moves give the same game
+    pub fn new_game_specific_dim_seeded(dim : (usize, usize), seed : u64) -> Result<SpecificGame<C_W, C_H, R>, String>{
+        if dim.0 <= C_H && dim.1 <= C_W {
+            let game_variant = GameVariant {
                 array: [[None; C_W]; C_H],
                 mergers_infos: [[None; C_W]; C_H],
-                nones_number: C_W * C_H,
+                nones_number: dim.0 * dim.1,
                 score : 0,
+                rand_manager : RandManager::new_seeded(seed),
+                seed : Some(seed),
+                next_value : None,
+                spawn_config : SpawnConfig::NUMBERS_ONLY,
+                spawn_weights : R::SPAWN_WEIGHTS.to_vec(),
+                goal : Goal::ReachTile(R::TARGET_VALUE),
+                move_limit : None,
+                moves : 0,
+                spawn_sequence : None,
+                spawn_source : SpawnSource::Random,
+                rules : PhantomData,
             };
 
-            let mut g: Swap2DGame<GameVariant<C_W, C_H>> = SpecificGame::game_init(dim, (C_W, C_H), game_variant);
+            let mut g: SpecificGame<C_W, C_H, R> = SpecificGame::game_init(dim, (C_H, C_W), game_variant);
+            g.slide_mode = R::SLIDE_MODE;
+            if R::SPAWN_PLACE == SpawnPlace::TrailingEdge {
+                g.game_variant_data.next_value = get_rand_value(&mut g.game_variant_data.rand_manager, R::SPAWN_WEIGHTS);
+            }
 
-            return Ok(g);
+            Ok(g)
         }
         else {
             Err("The dimension wanted is above the capacity".to_string())
@@ -196,13 +566,249 @@ impl<const C_W: usize, const C_H: usize> GameVariant<C_W, C_H> {
     }
 
     
-    pub fn new_game() -> SpecificGame<C_W, C_H> {
+    pub fn new_game() -> SpecificGame<C_W, C_H, R> {
         // here we are sure that width and height are under capacity
-        match Self::new_game_specific_dim((C_W, C_H)){
+        match Self::new_game_specific_dim((C_H, C_W)){
             Ok(g) => g,
             Err(_e) => panic!("This should not happen"),
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::levels::{cell_from_text, cell_to_text};
+
+    const SEED: u64 = 2048;
+
+    // the board of the rows in the cells of the levels, nothing spawns after the moves so the
+    // boards after a move are exact
+    fn game<R: VariantRules>(rows: &[&str]) -> SpecificGame<4, 4, R> {
+        let size = (rows.len(), rows[0].split_whitespace().count());
+        let mut g = GameVariant::<4, 4, R>::new_game_specific_dim_seeded(size, SEED).unwrap();
+        g.game_variant_data.spawn_sequence = Some(VecDeque::new());
+        for (id_x, row) in rows.iter().enumerate() {
+            for (id_y, cell) in row.split_whitespace().enumerate() {
+                g.board_set_element((id_x, id_y), cell_from_text(cell).unwrap());
+            }
+        }
+        g
+    }
+
+    fn rows<R: VariantRules>(g: &SpecificGame<4, 4, R>) -> Vec<String> {
+        (0..g.board_size.0)
+            .map(|id_x| (0..g.board_size.1).map(|id_y| cell_to_text(g.board_get_element((id_x, id_y)))).collect::<Vec<_>>().join(" "))
+            .collect()
+    }
 
+    #[test]
+    fn huge_spawn_weights_do_not_overflow() {
+        let mut rand_manager = RandManager::new_seeded(SEED);
+        let weights = [(2, u32::MAX), (4, u32::MAX), (8, 1)];
+        for _ in 0..100 {
+            assert!(matches!(get_rand_value(&mut rand_manager, &weights), Some(SpecificElementType::Some(2 | 4 | 8))));
+        }
+        assert_eq!(get_rand_value(&mut rand_manager, &[(2, 0)]), None);
+    }
+
+    #[test]
+    fn spawn_weights_are_drawn_as_before() {
+        let weights = [(2, 9), (4, 1)];
+        let mut rand_manager = RandManager::new_seeded(SEED);
+        let mut reference = RandManager::new_seeded(SEED);
+        for _ in 0..100 {
+            let expected = if reference.gen_range(0, 10) < 9 { 2 } else { 4 };
+            assert_eq!(get_rand_value(&mut rand_manager, &weights), Some(SpecificElementType::Some(expected)));
+        }
+    }
+
+    #[test]
+    fn one_step_slide_moves_every_tile_a_single_cell() {
+        let mut g = game::<ThreesMergeRule>(&[". 3 . 6", "1 2 . 3", "3 3 3 3", "3 6 12 24"]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g), [
+            "3 . 6 .",
+            "3 . 3 .",
+            "6 3 3 .",
+            "3 6 12 24",
+        ]);
+        assert_eq!(g.game_variant_data.score, 9);
+        assert_eq!(g.game_variant_data.moves, 1);
+    }
+
+    #[test]
+    fn one_step_slide_keeps_the_blocked_lines() {
+        let mut g = game::<ThreesMergeRule>(&["1 1 . .", ". . . .", ". . . .", ". . . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g)[0], "1 1 . .");
+        assert_eq!(g.game_variant_data.moves, 0);
+        g.move_generic(AllowedMoves::RIGHT);
+        assert_eq!(rows(&g)[0], ". 1 1 .");
+    }
+
+    #[test]
+    fn one_step_spawn_enters_from_the_trailing_edge() {
+        let play = || {
+            let mut g = game::<ThreesMergeRule>(&["1 . . .", ". 3 . .", "2 3 . .", ". . . ."]);
+            g.game_variant_data.spawn_sequence = None;
+            g.move_generic(AllowedMoves::LEFT);
+            g
+        };
+        let g = play();
+        // only the second row moved, its last cell gets the tile announced in the preview
+        let new_row = rows(&g)[1].clone();
+        assert!(new_row.starts_with("3 . . ") && !new_row.ends_with(". . . ."));
+        assert_eq!(rows(&g)[0], "1 . . .");
+        assert_eq!(rows(&g)[2], "2 3 . .");
+        // the same seed spawns the same tile
+        assert_eq!(rows(&g), rows(&play()));
+    }
+
+    #[test]
+    fn joker_doubles_the_tile_it_merges_with() {
+        let mut g = game::<ClassicMergeRule>(&["* 2 . .", "8 . . *", "* * 2 .", "2 * 4 ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g), [
+            "4 . . .",
+            "16 . . .",
+            "* 2 . .",
+            "4 4 . .",
+        ]);
+        assert_eq!(g.game_variant_data.score, 24);
+    }
+
+    #[test]
+    fn joker_follows_the_sequence_of_the_rules() {
+        let mut g = game::<FibonacciMergeRule>(&["* 5 . .", "4 * . .", ". . . .", ". . . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        // 5 is followed by 8, 4 is not a Fibonacci number
+        assert_eq!(rows(&g)[..2], ["8 . . .", "4 * . ."]);
+        let mut g = game::<ThreesMergeRule>(&["* 1 . .", ". . . .", ". . . .", ". . . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g)[0], "3 . . .");
+    }
+
+    #[test]
+    fn joker_spawns_at_its_chance() {
+        let mut g = game::<ClassicMergeRule>(&["2 . . .", ". . . .", ". . . .", ". . . ."]);
+        g.game_variant_data.spawn_sequence = None;
+        g.game_variant_data.spawn_config = SpawnConfig { joker_per_mille: 1000, ..SpawnConfig::NUMBERS_ONLY };
+        g.move_generic(AllowedMoves::RIGHT);
+        let jokers = rows(&g).iter().map(|row| row.matches('*').count()).sum::<usize>();
+        assert_eq!(jokers, 1);
+    }
+
+    #[test]
+    fn bomb_counts_down_with_the_moves() {
+        let mut g = game::<ClassicMergeRule>(&["@3 . . .", ". . . 2", ". . . .", ". . . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g)[..2], ["@2 . . .", "2 . . ."]);
+    }
+
+    #[test]
+    fn bomb_clears_its_neighbourhood_at_the_end_of_its_countdown() {
+        let mut g = game::<ClassicMergeRule>(&["2 4 . .", "@1 # 8 .", "16 . . .", ". . . 2"]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g), [
+            ". . . .",
+            ". # 8 .",
+            ". . . .",
+            "2 . . .",
+        ]);
+        // the cleared numbers are scored, the fixed block stays
+        assert_eq!(g.game_variant_data.score, 22);
+    }
+
+    #[test]
+    fn merging_into_a_bomb_detonates_it_at_once() {
+        let mut g = game::<ClassicMergeRule>(&["2 @9 . .", ". 4 . .", ". . 8 .", ". . . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g), [
+            ". . . .",
+            ". . . .",
+            "8 . . .",
+            ". . . .",
+        ]);
+        assert_eq!(g.game_variant_data.score, 4);
+    }
+
+    #[test]
+    fn bombs_detonate_in_chain() {
+        let mut g = game::<ClassicMergeRule>(&["@1 . . .", ". @9 . .", ". . 4 .", ". . . 2"]);
+        g.move_generic(AllowedMoves::RIGHT);
+        // the first bomb sets off the second one, whose blast reaches the 4
+        assert_eq!(rows(&g), [
+            ". . . .",
+            ". . . .",
+            ". . . .",
+            ". . . 2",
+        ]);
+    }
+
+    #[test]
+    fn merges_damage_the_blocks_next_to_them() {
+        let mut g = game::<ClassicMergeRule>(&["2 2 B3 .", "B2 . . .", "# 8 8 .", ". . . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        // the block slides with the tiles, the fixed block is not damaged
+        assert_eq!(rows(&g), [
+            "4 B2 . .",
+            "B1 . . .",
+            "# 16 . .",
+            ". . . .",
+        ]);
+    }
+
+    #[test]
+    fn block_without_hit_points_is_removed() {
+        let mut g = game::<ClassicMergeRule>(&["B1 . . .", "2 2 . .", ". . . .", ". . . ."]);
+        g.game_variant_data.goal = Goal::ClearBlocks;
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g)[..2], [". . . .", "4 . . ."]);
+        assert!(matches!(g.game_variant_data.mergers_infos[0][0], Some(RetainerMergerInfo::Destroyed(SpecificElementType::Block(1)))));
+        assert_eq!(g.game_status, GameStatus::END_SUCCESS);
+    }
+
+    // the numbers of the board become timed tiles of these ages, row by row
+    fn timed_game(timed: TimedTiles, rows: &[&str], ages: &[usize]) -> SpecificGame<4, 4, ClassicMergeRule> {
+        let mut g = game::<ClassicMergeRule>(rows);
+        g.game_variant_data.spawn_config.timed = timed;
+        let mut ages = ages.iter();
+        for id_x in 0..g.board_size.0 {
+            for id_y in 0..g.board_size.1 {
+                if let Some(SpecificElementType::Some(a)) = g.board_get_element((id_x, id_y)) {
+                    g.board_set_element((id_x, id_y), Some(SpecificElementType::Timed(a, *ages.next().unwrap())));
+                }
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn timed_tiles_get_older_until_they_merge() {
+        let mut g = timed_game(TimedTiles::Decaying(3), &["2 . . 4", "2 2 . .", ". . . .", ". . . ."], &[1, 0, 1, 2]);
+        g.move_generic(AllowedMoves::LEFT);
+        let array = g.game_variant_data.array;
+        // the numbers that did not merge got older, whether they moved or not
+        assert_eq!(array[0][..2], [Some(SpecificElementType::Timed(2, 2)), Some(SpecificElementType::Timed(4, 1))]);
+        // a merge starts the age again
+        assert_eq!(array[1][0], Some(SpecificElementType::Timed(4, 0)));
+    }
+
+    #[test]
+    fn timed_tiles_vanish_at_their_limit() {
+        let mut g = timed_game(TimedTiles::Decaying(3), &["2 . . .", ". . . .", ". . . .", ". . . 4"], &[2, 0]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(g.game_variant_data.array[0][0], None);
+        assert!(matches!(g.game_variant_data.mergers_infos[0][0], Some(RetainerMergerInfo::Destroyed(SpecificElementType::Timed(2, 2)))));
+        assert_eq!(g.game_variant_data.array[3][0], Some(SpecificElementType::Timed(4, 1)));
+    }
+
+    #[test]
+    fn petrified_tiles_become_fixed_blocks() {
+        let mut g = timed_game(TimedTiles::Petrifying(2), &["8 . . .", ". . . .", ". . . .", ". . . 4"], &[1, 0]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(rows(&g)[0], "# . . .");
+        assert_eq!(g.game_variant_data.array[3][0], Some(SpecificElementType::Timed(4, 1)));
+    }
+}
@@ -0,0 +1,353 @@
+// A level is a designed starting board: its cells, the number of random tiles added at the start
+// and the goal. The levels are saved as text files:
+//
+// name First steps
+// size 4x4
+// goal tile 2048
+// start_tiles 2
+// board
+// . . # .
+// 2 . B3 .
+// . . . .
+// . 4 . *
+//
+// a cell is `.` (empty), a number, `B<hit points>` (block), `#` (fixed block), `*` (joker) or
+// `@<moves>` (bomb). The goal is `tile <value>`, `clear_blocks` or `score <points>`. The optional
+// keys are `moves <limit>`, `par <moves>` (for the stars), `spawns <values...>` (once used up no
+// tile spawns anymore) and `seed <seed>` (positions of the random tiles).
+//
+// A level pack is a list of levels separated by `---` lines.
+use std::collections::VecDeque;
+
+use crate::game::game_kernel::Swap2DGameConfig;
+use crate::game::game_variants::game_variant_1::{GameVariant, GameSetup, GameStatus, Goal, SpawnConfig, SpecificElementType, SpecificGame, VariantRules, populate_game};
+use crate::game::strategies::{apply_merge, ALL_MOVES};
+use crate::text::{parse_number, parse_size};
+
+// levels of the puzzle campaign
+pub const CAMPAIGN: &str = include_str!("campaign.txt");
+const PACK_SEPARATOR: &str = "---";
+
+// capacity of the boards built from levels
+pub const LEVEL_CAPACITY: usize = 8;
+pub const DEFAULT_BLOCK_HP: usize = 3;
+// the random starting tiles are checked with these seeds
+const VALIDATION_SEEDS: u64 = 16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub name: String,
+    // (rows, columns)
+    pub size: (usize, usize),
+    pub goal: Goal,
+    pub start_tiles: usize,
+    pub move_limit: Option<usize>,
+    // moves of a three stars solution
+    pub par: Option<usize>,
+    pub spawns: Option<Vec<i32>>,
+    pub seed: Option<u64>,
+    // [row][column]
+    pub board: Vec<Vec<Option<SpecificElementType>>>,
+}
+
+pub fn cell_to_text(cell: Option<SpecificElementType>) -> String {
+    match cell {
+        None => ".".to_string(),
+        Some(SpecificElementType::Some(a)) | Some(SpecificElementType::Timed(a, _)) => a.to_string(),
+        Some(SpecificElementType::Block(hp)) => format!("B{hp}"),
+        Some(SpecificElementType::BlockFixed) => "#".to_string(),
+        Some(SpecificElementType::Joker) => "*".to_string(),
+        Some(SpecificElementType::Bomb(countdown)) => format!("@{countdown}"),
+    }
+}
+
+pub fn cell_from_text(text: &str) -> Result<Option<SpecificElementType>, String> {
+    let invalid = || format!("invalid cell: {text}");
+    match text {
+        "." => Ok(None),
+        "#" => Ok(Some(SpecificElementType::BlockFixed)),
+        "*" => Ok(Some(SpecificElementType::Joker)),
+        "B" => Ok(Some(SpecificElementType::Block(DEFAULT_BLOCK_HP))),
+        _ => {
+            if let Some(hp) = text.strip_prefix('B') {
+                hp.parse().map(|hp| Some(SpecificElementType::Block(hp))).map_err(|_| invalid())
+            } else if let Some(countdown) = text.strip_prefix('@') {
+                countdown.parse().map(|countdown| Some(SpecificElementType::Bomb(countdown))).map_err(|_| invalid())
+            } else {
+                match text.parse() {
+                    Ok(a) if a > 0 => Ok(Some(SpecificElementType::Some(a))),
+                    _ => Err(invalid()),
+                }
+            }
+        }
+    }
+}
+
+pub fn goal_to_text(goal: Goal) -> String {
+    match goal {
+        Goal::ReachTile(a) => format!("tile {a}"),
+        Goal::ClearBlocks => "clear_blocks".to_string(),
+        Goal::Score(a) => format!("score {a}"),
+    }
+}
+
+// a single number is a tile to reach
+pub fn goal_from_text(text: &str) -> Result<Goal, String> {
+    let invalid = || format!("invalid goal: {text}");
+    if text == "clear_blocks" {
+        return Ok(Goal::ClearBlocks);
+    }
+    let (kind, value) = text.split_once(' ').unwrap_or(("tile", text));
+    match kind {
+        "tile" => value.parse().map(Goal::ReachTile).map_err(|_| invalid()),
+        "score" => value.parse().map(Goal::Score).map_err(|_| invalid()),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_level_size(value: &str) -> Result<(usize, usize), String> {
+    match parse_size(value) {
+        Ok((rows, columns)) if (2..=LEVEL_CAPACITY).contains(&rows) && (2..=LEVEL_CAPACITY).contains(&columns) => Ok((rows, columns)),
+        _ => Err(format!("the size shall be between 2x2 and {LEVEL_CAPACITY}x{LEVEL_CAPACITY}: {value}")),
+    }
+}
+
+impl Level {
+
+    pub fn new(size: (usize, usize)) -> Level {
+        Level {
+            name: String::new(),
+            size,
+            goal: Goal::ReachTile(2048),
+            start_tiles: 2,
+            move_limit: None,
+            par: None,
+            spawns: None,
+            seed: None,
+            board: vec![vec![None; size.1]; size.0],
+        }
+    }
+
+    // the cells inside the new size are kept
+    pub fn resize(&mut self, size: (usize, usize)) {
+        self.board.resize(size.0, vec![None; size.1]);
+        for row in self.board.iter_mut() {
+            row.resize(size.1, None);
+        }
+        self.size = size;
+    }
+
+    pub fn empty_cells(&self) -> usize {
+        self.board.iter().flatten().filter(|cell| cell.is_none()).count()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if !self.name.is_empty() {
+            text.push_str(&format!("name {}\n", self.name));
+        }
+        text.push_str(&format!("size {}x{}\ngoal {}\nstart_tiles {}\n", self.size.0, self.size.1, goal_to_text(self.goal), self.start_tiles));
+        if let Some(move_limit) = self.move_limit {
+            text.push_str(&format!("moves {move_limit}\n"));
+        }
+        if let Some(par) = self.par {
+            text.push_str(&format!("par {par}\n"));
+        }
+        if let Some(spawns) = &self.spawns {
+            let values: Vec<String> = spawns.iter().map(|a| a.to_string()).collect();
+            text.push_str(&format!("spawns {}\n", values.join(" ")));
+        }
+        if let Some(seed) = self.seed {
+            text.push_str(&format!("seed {seed}\n"));
+        }
+        text.push_str("board\n");
+        for row in &self.board {
+            let cells: Vec<String> = row.iter().map(|cell| cell_to_text(*cell)).collect();
+            text.push_str(&cells.join(" "));
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Level, String> {
+        let mut level = Level::new((4, 4));
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with("//"));
+        let mut board = None;
+
+        for line in lines.by_ref() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "name" => level.name = value.to_string(),
+                "size" => level.size = parse_level_size(value)?,
+                "goal" => level.goal = goal_from_text(value)?,
+                "start_tiles" => level.start_tiles = parse_number(key, value)?,
+                "moves" => level.move_limit = Some(parse_number(key, value)?),
+                "par" => level.par = Some(parse_number(key, value)?),
+                "spawns" => level.spawns = Some(value.split_whitespace().map(|a| parse_number(key, a)).collect::<Result<_, _>>()?),
+                "seed" => level.seed = Some(parse_number(key, value)?),
+                "board" => {
+                    board = Some(Vec::new());
+                    break;
+                }
+                _ => return Err(format!("unknown key: {key}")),
+            }
+        }
+
+        let mut board = board.ok_or("missing board")?;
+        for line in lines {
+            let row = line.split_whitespace().map(cell_from_text).collect::<Result<Vec<_>, String>>()?;
+            if row.len() != level.size.1 {
+                return Err(format!("the board row `{line}` shall have {} cells", level.size.1));
+            }
+            board.push(row);
+        }
+        if board.len() != level.size.0 {
+            return Err(format!("the board shall have {} rows", level.size.0));
+        }
+        level.board = board;
+        Ok(level)
+    }
+
+    pub fn parse_pack(text: &str) -> Result<Vec<Level>, String> {
+        let mut levels = Vec::new();
+        let mut level_text = String::new();
+        for line in text.lines().chain(std::iter::once(PACK_SEPARATOR)) {
+            if line.trim() != PACK_SEPARATOR {
+                level_text.push_str(line);
+                level_text.push('\n');
+            } else if !level_text.trim().is_empty() {
+                let level = Level::parse(&level_text).map_err(|e| format!("level {}: {e}", levels.len() + 1))?;
+                levels.push(level);
+                level_text.clear();
+            }
+        }
+        Ok(levels)
+    }
+
+    // 3 stars within the par, 2 stars within twice the par, 1 star otherwise
+    pub fn stars(&self, moves: usize) -> u8 {
+        match self.par {
+            Some(par) if moves > 2 * par => 1,
+            Some(par) if moves > par => 2,
+            _ => 3,
+        }
+    }
+
+    // the starting tiles are dropped at random empty cells following the seed
+    pub fn build<const C_W: usize, const C_H: usize, R: VariantRules>(&self, seed: u64) -> Result<SpecificGame<C_W, C_H, R>, String> {
+        let mut g = GameVariant::<C_W, C_H, R>::new_game_specific_dim_seeded(self.size, seed)?;
+        for (id_x, row) in self.board.iter().enumerate() {
+            for (id_y, cell) in row.iter().enumerate() {
+                g.board_set_element((id_x, id_y), *cell);
+            }
+        }
+        g.game_variant_data.goal = self.goal;
+        g.game_variant_data.move_limit = self.move_limit;
+        g.game_variant_data.spawn_sequence = self.spawns.as_ref().map(|spawns| spawns.iter().copied().collect::<VecDeque<_>>());
+        populate_game(&mut g, GameSetup { start_tiles: self.start_tiles, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY });
+        Ok(g)
+    }
+
+    // a valid level is not won nor dead on arrival whatever the random starting tiles
+    pub fn validate<R: VariantRules>(&self) -> Result<(), String> {
+        if self.start_tiles > self.empty_cells() {
+            return Err(format!("{} starting tiles do not fit in the {} empty cells", self.start_tiles, self.empty_cells()));
+        }
+        if self.move_limit == Some(0) {
+            return Err("the move limit shall allow at least one move".to_string());
+        }
+        for seed in 0..VALIDATION_SEEDS {
+            let g = self.build::<LEVEL_CAPACITY, LEVEL_CAPACITY, R>(seed)?;
+            if g.board_game_status_fn() == GameStatus::END_SUCCESS {
+                return Err("the goal is reached before the first move".to_string());
+            }
+            if !ALL_MOVES.iter().any(|m| apply_merge(&g, *m).is_some()) {
+                return Err("the level is dead on arrival: no move changes the board".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::merge_rules::ClassicMergeRule;
+
+    fn level(rows: &[&str], start_tiles: usize) -> Level {
+        let mut text = format!("size {}x{}\ngoal tile 2048\nstart_tiles {start_tiles}\nboard\n", rows.len(), rows[0].split_whitespace().count());
+        for row in rows {
+            text.push_str(row);
+            text.push('\n');
+        }
+        Level::parse(&text).unwrap()
+    }
+
+    #[test]
+    fn valid_level_is_accepted() {
+        assert_eq!(Level::new((4, 4)).validate::<ClassicMergeRule>(), Ok(()));
+        assert_eq!(level(&["2 4 2", "4 . 4", "2 4 2"], 0).validate::<ClassicMergeRule>(), Ok(()));
+    }
+
+    #[test]
+    fn dead_level_is_refused() {
+        let dead = level(&["2 4 2", "4 2 4", "2 4 2"], 0);
+        assert!(dead.validate::<ClassicMergeRule>().unwrap_err().contains("dead on arrival"));
+        // the last empty cell gets a 2 or a 4 between two 8s, no move is left either way
+        let filled = level(&["2 4 2", "4 2 8", "2 8 ."], 1);
+        assert!(filled.validate::<ClassicMergeRule>().unwrap_err().contains("dead on arrival"));
+    }
+
+    #[test]
+    fn full_level_is_refused() {
+        let full = level(&["2 4", "4 2"], 1);
+        assert_eq!(full.validate::<ClassicMergeRule>(), Err("1 starting tiles do not fit in the 0 empty cells".to_string()));
+    }
+
+    #[test]
+    fn won_level_and_no_moves_are_refused() {
+        assert!(level(&["2048 .", ". ."], 0).validate::<ClassicMergeRule>().is_err());
+        let mut no_moves = Level::new((4, 4));
+        no_moves.move_limit = Some(0);
+        assert!(no_moves.validate::<ClassicMergeRule>().is_err());
+    }
+
+    #[test]
+    fn bundled_campaign_parses_and_validates() {
+        let levels = Level::parse_pack(CAMPAIGN).unwrap();
+        assert!(!levels.is_empty());
+        for (idx, level) in levels.iter().enumerate() {
+            assert!(!level.name.is_empty(), "level {}", idx + 1);
+            assert_eq!(level.validate::<ClassicMergeRule>(), Ok(()), "level {}", idx + 1);
+        }
+    }
+
+    #[test]
+    fn level_text_round_trip() {
+        for level in Level::parse_pack(CAMPAIGN).unwrap() {
+            assert_eq!(Level::parse(&level.to_text()), Ok(level.clone()));
+        }
+        let text = "name Every key\nsize 3x4\ngoal score 500\nstart_tiles 1\nmoves 40\npar 12\nspawns 2 4 2\nseed 99\nboard\n. # B2 .\n2 * @5 .\n. . . 1024\n";
+        let level = Level::parse(text).unwrap();
+        assert_eq!(level.to_text(), text);
+        assert_eq!(level.board[1][2], Some(SpecificElementType::Bomb(5)));
+        assert_eq!(Level::parse(&level.to_text()), Ok(level));
+    }
+
+    #[test]
+    fn broken_levels_are_refused() {
+        assert!(Level::parse("size 2x2\nboard\n. .\n").is_err());
+        assert!(Level::parse("size 2x2\nboard\n. . .\n. .\n").is_err());
+        assert!(Level::parse("size 2x2\nwhat 3\nboard\n. .\n. .\n").is_err());
+        assert!(Level::parse("size 2x2\n").is_err());
+        assert_eq!(Level::parse_pack("size 2x2\nboard\n. .\n. .\n---\nsize 9x9\nboard\n"), Err("level 2: the size shall be between 2x2 and 8x8: 9x9".to_string()));
+    }
+
+    #[test]
+    fn stars_follow_the_par() {
+        let mut level = Level::new((4, 4));
+        assert_eq!(level.stars(1000), 3);
+        level.par = Some(10);
+        assert_eq!([1, 10, 11, 20, 21, 500].map(|moves| level.stars(moves)), [3, 3, 2, 2, 1, 1]);
+    }
+}
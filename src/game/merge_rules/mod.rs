@@ -0,0 +1,159 @@
+// The merge rules decide if two neighbour tiles of a line can merge and what they produce.
+// The retainers only handle the order of the tiles in the line, so a new merge semantic
+// only needs a new rule.
+use std::ops::Add;
+
+pub trait MergeRule<T: Copy> {
+    // `a` is the tile closest to the side of the move, None when the tiles can not merge
+    fn merge(a: T, b: T) -> Option<T>;
+
+    // the joker promotes the tile to the next value of the sequence
+    fn merge_with_joker(a: T) -> Option<T> {
+        Self::merge(a, a)
+    }
+}
+
+// equal tiles merge into their sum
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct ClassicMergeRule;
+
+impl<T: Copy + Add<Output = T> + PartialEq> MergeRule<T> for ClassicMergeRule {
+    fn merge(a: T, b: T) -> Option<T> {
+        if a == b {
+            Some(a + a)
+        } else {
+            None
+        }
+    }
+}
+
+// two consecutive numbers of the Fibonacci sequence (1, 1, 2, 3, 5, 8 ...) merge into the next one
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct FibonacciMergeRule;
+
+pub fn are_consecutive_fibonacci(a: i32, b: i32) -> bool {
+    let (small, big) = if a <= b { (a, b) } else { (b, a) };
+    let (mut f_0, mut f_1) = (1, 1);
+    while f_0 <= small {
+        if (f_0, f_1) == (small, big) {
+            return true;
+        }
+        (f_0, f_1) = (f_1, f_0 + f_1);
+    }
+    false
+}
+
+impl MergeRule<i32> for FibonacciMergeRule {
+    fn merge(a: i32, b: i32) -> Option<i32> {
+        if are_consecutive_fibonacci(a, b) {
+            Some(a + b)
+        } else {
+            None
+        }
+    }
+
+    fn merge_with_joker(a: i32) -> Option<i32> {
+        let (mut f_0, mut f_1) = (1, 2);
+        while f_0 < a {
+            (f_0, f_1) = (f_1, f_0 + f_1);
+        }
+        if f_0 == a {
+            Some(f_1)
+        } else {
+            None
+        }
+    }
+}
+
+// two equal tiles merge into their triple, the tiles are powers of 3. It is a two-tile merge like
+// the others, three equal tiles in a line do not merge at once.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct TriplingMergeRule;
+
+impl<T: Copy + Add<Output = T> + PartialEq> MergeRule<T> for TriplingMergeRule {
+    fn merge(a: T, b: T) -> Option<T> {
+        if a == b {
+            Some(a + a + a)
+        } else {
+            None
+        }
+    }
+}
+
+// Threes rules: 1 and 2 merge into 3, equal tiles of 3 or more merge into their sum
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct ThreesMergeRule;
+
+impl MergeRule<i32> for ThreesMergeRule {
+    fn merge(a: i32, b: i32) -> Option<i32> {
+        match (a, b) {
+            (1, 2) | (2, 1) => Some(3),
+            _ if a == b && a >= 3 => Some(a + b),
+            _ => None,
+        }
+    }
+
+    fn merge_with_joker(a: i32) -> Option<i32> {
+        match a {
+            1 | 2 => Some(3),
+            _ => Self::merge(a, a),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::VariantRules;
+
+    #[test]
+    fn classic_merges_equal_tiles() {
+        assert_eq!(<ClassicMergeRule as MergeRule<i32>>::merge(8, 8), Some(16));
+        assert_eq!(<ClassicMergeRule as MergeRule<i32>>::merge(8, 4), None);
+        assert_eq!(<ClassicMergeRule as MergeRule<i32>>::merge_with_joker(64), Some(128));
+    }
+
+    #[test]
+    fn fibonacci_merges_neighbours_of_the_sequence() {
+        assert_eq!(FibonacciMergeRule::merge(1, 1), Some(2));
+        assert_eq!(FibonacciMergeRule::merge(2, 1), Some(3));
+        assert_eq!(FibonacciMergeRule::merge(5, 8), Some(13));
+        assert_eq!(FibonacciMergeRule::merge(13, 8), Some(21));
+        assert_eq!(FibonacciMergeRule::merge(2, 2), None);
+        assert_eq!(FibonacciMergeRule::merge(3, 8), None);
+        assert_eq!(FibonacciMergeRule::merge(4, 7), None);
+        assert_eq!(FibonacciMergeRule::merge_with_joker(1), Some(2));
+        assert_eq!(FibonacciMergeRule::merge_with_joker(21), Some(34));
+        assert_eq!(FibonacciMergeRule::merge_with_joker(4), None);
+    }
+
+    #[test]
+    fn tripling_merges_two_equal_tiles_into_their_triple() {
+        assert_eq!(<TriplingMergeRule as MergeRule<i32>>::merge(3, 3), Some(9));
+        assert_eq!(<TriplingMergeRule as MergeRule<i32>>::merge(27, 27), Some(81));
+        assert_eq!(<TriplingMergeRule as MergeRule<i32>>::merge(9, 3), None);
+        assert_eq!(<TriplingMergeRule as MergeRule<i32>>::merge_with_joker(9), Some(27));
+    }
+
+    #[test]
+    fn threes_merges_one_and_two_then_equal_tiles() {
+        assert_eq!(ThreesMergeRule::merge(1, 2), Some(3));
+        assert_eq!(ThreesMergeRule::merge(2, 1), Some(3));
+        assert_eq!(ThreesMergeRule::merge(3, 3), Some(6));
+        assert_eq!(ThreesMergeRule::merge(12, 12), Some(24));
+        assert_eq!(ThreesMergeRule::merge(1, 1), None);
+        assert_eq!(ThreesMergeRule::merge(2, 2), None);
+        assert_eq!(ThreesMergeRule::merge(3, 6), None);
+        assert_eq!(ThreesMergeRule::merge_with_joker(1), Some(3));
+        assert_eq!(ThreesMergeRule::merge_with_joker(2), Some(3));
+        assert_eq!(ThreesMergeRule::merge_with_joker(6), Some(12));
+    }
+
+    #[test]
+    fn tile_ranks_follow_the_merge_sequences() {
+        assert_eq!([2, 4, 8, 2048].map(ClassicMergeRule::tile_rank), [0, 1, 2, 10]);
+        assert_eq!([1, 2, 3, 5, 8, 610].map(FibonacciMergeRule::tile_rank), [0, 1, 2, 3, 4, 13]);
+        assert_eq!([3, 9, 27, 177147].map(TriplingMergeRule::tile_rank), [0, 1, 2, 10]);
+        assert_eq!([1, 2, 3, 6, 12, 384].map(ThreesMergeRule::tile_rank), [0, 0, 1, 2, 3, 8]);
+    }
+}
@@ -1,3 +1,14 @@
+pub mod adversary;
+pub mod clock;
+pub mod daily;
 mod game_kernel;
 pub mod game_variants;
+pub mod levels;
+pub mod merge_rules;
+pub mod rand_manager;
 mod retainer_merger_variants;
+pub mod simulation;
+pub mod snapshot;
+pub mod strategies;
+pub mod undo;
+pub mod versus;
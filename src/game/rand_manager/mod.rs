@@ -1,3 +1,55 @@
 use getrandom;
 
-// to be implemented
\ No newline at end of file
+// Small seedable random generator (splitmix64) shared by the game variants.
+// A game created with the same seed and receiving the same moves will always
+// produce the same spawns, which is needed to replay or compare games.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RandManager {
+    state: u64,
+}
+
+impl RandManager {
+
+    pub fn new_seeded(seed: u64) -> RandManager {
+        RandManager { state: seed }
+    }
+
+    // seeded from the system entropy source
+    pub fn new() -> RandManager {
+        RandManager::new_seeded(Self::entropy_seed())
+    }
+
+    pub fn entropy_seed() -> u64 {
+        let mut random_value: [u8; 8] = [0; 8];
+        let _ = getrandom::getrandom(&mut random_value);
+        u64::from_le_bytes(random_value)
+    }
+
+    // the current state, a RandManager created from it will continue the same sequence
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+
+    // value in [start_idx, end_idx[
+    pub fn gen_range(&mut self, start_idx: usize, end_idx: usize) -> usize {
+        (self.next_u64() % ((end_idx - start_idx) as u64)) as usize + start_idx
+    }
+}
+
+impl Default for RandManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,7 @@
 use crate::game::game_kernel as game_kernel;
+use crate::game::merge_rules::{MergeRule, ClassicMergeRule};
 use game_kernel::{RetainerManager, CtxElementType, RetainerManagerElementType};
+use std::marker::PhantomData;
 use std::ops::Add;
 
 #[derive(Copy, Clone, Debug)]
@@ -9,29 +11,38 @@ pub enum RetainerMergerInfo<ElementType, CtxElementType> {
     None
 }
 
-pub struct RetainerMerger<ElementType:Copy+Add<Output=ElementType>+PartialEq>(Option<(CtxElementType, ElementType)>, Option<(CtxElementType, ElementType)>);
+pub struct RetainerMerger<ElementType:Copy+Add<Output=ElementType>+PartialEq, R = ClassicMergeRule>(Option<(CtxElementType, ElementType)>, Option<(CtxElementType, ElementType)>, PhantomData<R>);
+
+impl<ElementType : Copy+Add<Output=ElementType>+PartialEq, R: MergeRule<ElementType>> RetainerMerger<ElementType, R> {
+    fn retained_merge(&self) -> Option<ElementType> {
+        match (self.0, self.1) {
+            (Some((_, a)), Some((_, b))) => R::merge(a, b),
+            _ => None,
+        }
+    }
+}
+
+impl<ElementType : Copy+Add<Output=ElementType>+PartialEq, R: MergeRule<ElementType>> RetainerManager<ElementType> for RetainerMerger<ElementType, R> {
 
-impl<ElementType : Copy+Add<Output=ElementType>+PartialEq> RetainerManager<ElementType> for RetainerMerger<ElementType> {
-    
     type RetainerMergerInfoType = RetainerMergerInfo<ElementType, CtxElementType>;
 
-    fn new() -> RetainerMerger<ElementType> {
-        RetainerMerger(None, None)
+    fn new() -> RetainerMerger<ElementType, R> {
+        RetainerMerger(None, None, PhantomData)
     }
 
     fn push_and_pop_if_filled(&mut self, ctx_element: CtxElementType, element: Option<ElementType>) ->  (RetainerManagerElementType<ElementType>,Self::RetainerMergerInfoType) {
-        match (self.0, self.1) {
-            (Some((ctx_element_a, a)), Some((ctx_element_b, b))) if a == b => {
+        match (self.0, self.1, self.retained_merge()) {
+            (Some((ctx_element_a, a)), Some((ctx_element_b, b)), Some(merged)) => {
                 self.0 = None;
                 self.1 = element.map(|e| (ctx_element, e));
-                (RetainerManagerElementType::GameElement(a+a),RetainerMergerInfo::Merged((ctx_element_a, a), (ctx_element_b, b)))
+                (RetainerManagerElementType::GameElement(merged),RetainerMergerInfo::Merged((ctx_element_a, a), (ctx_element_b, b)))
             }
-            (Some((ctx_element_a, a)), Some((ctx_element_b, b))) => {
+            (Some((ctx_element_a, a)), Some(_), _) => {
                 self.0 = self.1;
                 self.1 = element.map(|e| (ctx_element, e));
                 (RetainerManagerElementType::GameElement(a),RetainerMergerInfo::NotMerged(ctx_element_a, a))
             }
-            (None, Some((ctx_element_b, b))) => {
+            (None, Some(_), _) => {
                 self.0 = self.1;
                 self.1 = element.map(|e| (ctx_element, e));
                 (RetainerManagerElementType::NeutralIgnore,RetainerMergerInfo::None)
@@ -43,29 +54,29 @@ impl<ElementType : Copy+Add<Output=ElementType>+PartialEq> RetainerManager<Eleme
         }
     }
     fn pop(&mut self) -> (RetainerManagerElementType<ElementType>,Self::RetainerMergerInfoType) {
-        match (self.0, self.1) {
-            (None, Some((ctx_element_b, b))) => {
+        match (self.0, self.1, self.retained_merge()) {
+            (None, Some((ctx_element_b, b)), _) => {
                 self.1 = None;
                 (RetainerManagerElementType::GameElement(b),RetainerMergerInfo::NotMerged(ctx_element_b, b))
 
             }
-            (Some((ctx_element_a, a)), Some((ctx_element_b, b))) if a==b => {
+            (Some((ctx_element_a, a)), Some((ctx_element_b, b)), Some(merged)) => {
                 self.0 = None;
                 self.1 = None;
-                (RetainerManagerElementType::GameElement(a+a),RetainerMergerInfo::Merged((ctx_element_a, a), (ctx_element_b, b)))
+                (RetainerManagerElementType::GameElement(merged),RetainerMergerInfo::Merged((ctx_element_a, a), (ctx_element_b, b)))
             }
-            (Some((ctx_element_a, a)), Some((ctx_element_b, b))) => {
+            (Some((ctx_element_a, a)), Some((ctx_element_b, b)), _) => {
                 self.0 =  Some((ctx_element_b, b));
                 self.1 = None;
                 (RetainerManagerElementType::GameElement(a),RetainerMergerInfo::NotMerged(ctx_element_a, a))
             }
-            (Some((ctx_element_a, a)), None) => {
+            (Some((ctx_element_a, a)), None, _) => {
                 self.0 =  None;
                 self.1 = None;
                 (RetainerManagerElementType::GameElement(a),RetainerMergerInfo::NotMerged(ctx_element_a, a))
             }
             /* will not occur */
-            _ => { 
+            _ => {
                 self.0 = self.1;
                 self.1 = None;
                 (RetainerManagerElementType::NeutralIgnore,RetainerMergerInfo::None)
@@ -73,4 +84,15 @@ impl<ElementType : Copy+Add<Output=ElementType>+PartialEq> RetainerManager<Eleme
         }
 
     }
+
+    fn step_into(target: Option<(CtxElementType, ElementType)>, moving: (CtxElementType, ElementType)) -> Option<(ElementType, Self::RetainerMergerInfoType)> {
+        match target {
+            None => Some((moving.1, RetainerMergerInfo::NotMerged(moving.0, moving.1))),
+            Some(target) => R::merge(target.1, moving.1).map(|merged| (merged, RetainerMergerInfo::Merged(target, moving))),
+        }
+    }
+
+    fn still(ctx_element: CtxElementType, element: ElementType) -> Self::RetainerMergerInfoType {
+        RetainerMergerInfo::NotMerged(ctx_element, element)
+    }
 }
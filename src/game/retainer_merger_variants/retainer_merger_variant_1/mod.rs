@@ -1,64 +1,120 @@
 use crate::game::game_kernel as game_kernel;
+use crate::game::merge_rules::{MergeRule, ClassicMergeRule};
 use game_kernel::{RetainerManager, CtxElementType, IdxType, RetainerManagerElementType};
+use std::marker::PhantomData;
 use std::ops::Add;
 
 
 #[derive(Debug,Clone,Copy, PartialEq)]
 pub enum SpecificElementType<T:Copy+Add<Output=T>+PartialEq> {
     Some(T),
-    Block,
-    BlockFixed
+    // hit points left, the merges next to the block damage it
+    Block(IdxType),
+    BlockFixed,
+    // merges with any number or with another joker
+    Joker,
+    // moves left before the detonation, a number merging into it detonates it at once
+    Bomb(IdxType),
+    // number with its age in moves since its last merge
+    Timed(T, IdxType)
+}
+
+impl<T:Copy+Add<Output=T>+PartialEq> SpecificElementType<T> {
+    // value of the numbers, timed or not
+    pub fn number(self) -> Option<T> {
+        match self {
+            SpecificElementType::Some(a) | SpecificElementType::Timed(a, _) => Some(a),
+            _ => None,
+        }
+    }
+
+    // a timed number stays timed and its age is reset
+    fn with_number(self, value: T) -> Self {
+        match self {
+            SpecificElementType::Timed(_, _) => SpecificElementType::Timed(value, 0),
+            _ => SpecificElementType::Some(value),
+        }
+    }
+
+    // result of the merge of two neighbour elements, `self` being the closest to the side of the move
+    pub fn merge_with<R: MergeRule<T>>(self, other: Self) -> Option<Self> {
+        match (self, other, self.number(), other.number()) {
+            (_, _, Some(a), Some(b)) => R::merge(a, b).map(|merged| match other {
+                SpecificElementType::Timed(_, _) => other.with_number(merged),
+                _ => self.with_number(merged),
+            }),
+            (SpecificElementType::Joker, number, _, Some(a)) | (number, SpecificElementType::Joker, Some(a), _) => R::merge_with_joker(a).map(|merged| number.with_number(merged)),
+            (SpecificElementType::Joker, SpecificElementType::Joker, _, _) => Some(SpecificElementType::Joker),
+            (SpecificElementType::Bomb(_), _, _, Some(_)) | (_, SpecificElementType::Bomb(_), Some(_), _) => Some(SpecificElementType::Bomb(0)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum RetainerMergerInfo<T:Copy+Add<Output=T>+PartialEq, CtxElementType> {
     Merged((CtxElementType, SpecificElementType<T>),(CtxElementType, SpecificElementType<T>)),
     NotMerged(CtxElementType, SpecificElementType<T>),
+    // cleared by the bomb detonated at the given position
+    Detonated(CtxElementType, SpecificElementType<T>),
+    // removed by the rules of the variant (broken block, decayed tile)
+    Destroyed(SpecificElementType<T>),
     None
 }
 
-pub struct RetainerMerger<T:Copy+Add<Output=T>+PartialEq>(IdxType, IdxType, (Option<(CtxElementType, SpecificElementType<T>)>, Option<(CtxElementType, SpecificElementType<T>)>));
+type RetainedElement<T> = Option<(CtxElementType, SpecificElementType<T>)>;
+
+pub struct RetainerMerger<T:Copy+Add<Output=T>+PartialEq, R = ClassicMergeRule>(IdxType, IdxType, (RetainedElement<T>, RetainedElement<T>), PhantomData<R>);
+
+impl<T : Copy+Add<Output=T>+PartialEq, R: MergeRule<T>> RetainerMerger<T, R> {
+    fn retained_merge(&self) -> Option<SpecificElementType<T>> {
+        match self.2 {
+            (Some((_, a)), Some((_, b))) => a.merge_with::<R>(b),
+            _ => None,
+        }
+    }
+}
+
+impl<T : Copy+Add<Output=T>+PartialEq, R: MergeRule<T>> RetainerManager<SpecificElementType<T>> for RetainerMerger<T, R> {
 
-impl<T : Copy+Add<Output=T>+PartialEq> RetainerManager<SpecificElementType<T>> for RetainerMerger<T> {
-    
     type RetainerMergerInfoType = RetainerMergerInfo<T, CtxElementType>;
 
-    fn new() -> RetainerMerger<T> {
-        RetainerMerger(0,0,(None, None))
+    fn new() -> RetainerMerger<T, R> {
+        RetainerMerger(0,0,(None, None), PhantomData)
     }
 
     fn push_and_pop_if_filled(&mut self, ctx_element: CtxElementType, element: Option<SpecificElementType<T>>) ->  (RetainerManagerElementType<SpecificElementType<T>>,Self::RetainerMergerInfoType) {
-        let ret = match (self.2.0, self.2.1) {
-            (Some((ctx_element_a, SpecificElementType::Some(a))), Some((ctx_element_b, SpecificElementType::Some(b)))) if a == b => {
+        let ret = match (self.2.0, self.2.1, self.retained_merge()) {
+            (Some((ctx_element_a, a)), Some((ctx_element_b, b)), Some(merged)) => {
                 self.2.0 = None;
                 self.2.1 = element.map(|e| (ctx_element, e));
                 self.1 +=1;
-                (RetainerManagerElementType::GameElement(SpecificElementType::Some(a+a)),RetainerMergerInfo::Merged((ctx_element_a, SpecificElementType::Some(a)), (ctx_element_b, SpecificElementType::Some(b))))
-            }
-            (Some((ctx_element_a, SpecificElementType::Some(a))), Some(_)) => {
-                self.2.0 = self.2.1;
-                self.2.1 = element.map(|e| (ctx_element, e));
-                self.1 +=1;
-                (RetainerManagerElementType::GameElement(SpecificElementType::Some(a)),RetainerMergerInfo::NotMerged(ctx_element_a, SpecificElementType::Some(a)))
-            }
-            (Some((ctx_element_a, SpecificElementType::Block)), _) => {
-                self.2.0 = self.2.1;
-                self.2.1 = element.map(|e: SpecificElementType<T>| (ctx_element, e));
-                self.1 +=1;
-                (RetainerManagerElementType::GameElement(SpecificElementType::Block),RetainerMergerInfo::NotMerged(ctx_element_a, SpecificElementType::Block))
+                (RetainerManagerElementType::GameElement(merged),RetainerMergerInfo::Merged((ctx_element_a, a), (ctx_element_b, b)))
             }
-            (Some((ctx_element_a, SpecificElementType::BlockFixed)),_) => {
+            (Some((ctx_element_a, SpecificElementType::BlockFixed)),_, _) => {
                 self.2.0 = self.2.1;
                 self.2.1 = element.map(|e: SpecificElementType<T>| (ctx_element, e));
 
                 let steps = (self.0 - 1 - 1) - self.1;
-                
+
                 self.1 = self.0 - 1-1;
 
                 self.1 += 1;
                 (RetainerManagerElementType::GameElementWithIncrementalIgnore(steps, SpecificElementType::BlockFixed),RetainerMergerInfo::NotMerged(ctx_element_a, SpecificElementType::BlockFixed))
             }
-            (None, Some(_)) => {
+            (Some((ctx_element_a, a)), Some(_), _) => {
+                self.2.0 = self.2.1;
+                self.2.1 = element.map(|e| (ctx_element, e));
+                self.1 +=1;
+                (RetainerManagerElementType::GameElement(a),RetainerMergerInfo::NotMerged(ctx_element_a, a))
+            }
+            (Some((ctx_element_a, SpecificElementType::Block(hp))), None, _) => {
+                self.2.0 = self.2.1;
+                self.2.1 = element.map(|e: SpecificElementType<T>| (ctx_element, e));
+                self.1 +=1;
+                (RetainerManagerElementType::GameElement(SpecificElementType::Block(hp)),RetainerMergerInfo::NotMerged(ctx_element_a, SpecificElementType::Block(hp)))
+            }
+            (None, Some(_), _) => {
                 self.2.0 = self.2.1;
                 self.2.1 = element.map(|e| (ctx_element, e));
                 //self.1 += 1;
@@ -74,27 +130,27 @@ impl<T : Copy+Add<Output=T>+PartialEq> RetainerManager<SpecificElementType<T>> f
     }
 
     fn pop(&mut self) -> (RetainerManagerElementType<SpecificElementType<T>>,Self::RetainerMergerInfoType) {
-        let ret = match (self.2.0, self.2.1) {
-            (None, Some((ctx_element_a, SpecificElementType::BlockFixed))) => {
+        let ret = match (self.2.0, self.2.1, self.retained_merge()) {
+            (None, Some((ctx_element_a, SpecificElementType::BlockFixed)), _) => {
                 self.2.1 = None;
                 let steps = (self.0 -1) - self.1;
                 self.1 = self.0;
                 self.1 += 1;
                 (RetainerManagerElementType::GameElementWithIncrementalIgnore(steps, SpecificElementType::BlockFixed),RetainerMergerInfo::NotMerged(ctx_element_a, SpecificElementType::BlockFixed))
             }
-            (None, Some((ctx_element_b, specific_element_type))) => {
+            (None, Some((ctx_element_b, specific_element_type)), _) => {
                 self.2.1 = None;
                 self.1 +=1;
                 (RetainerManagerElementType::GameElement(specific_element_type),RetainerMergerInfo::NotMerged(ctx_element_b,specific_element_type))
 
             }
-            (Some((ctx_element_a, SpecificElementType::Some(a))), Some((ctx_element_b, SpecificElementType::Some(b)))) if a==b => {
+            (Some((ctx_element_a, a)), Some((ctx_element_b, b)), Some(merged)) => {
                 self.2.0 = None;
                 self.2.1 = None;
                 self.1 +=1;
-                (RetainerManagerElementType::GameElement(SpecificElementType::Some(a+a)),RetainerMergerInfo::Merged((ctx_element_a, SpecificElementType::Some(a)), (ctx_element_b, SpecificElementType::Some(b))))
+                (RetainerManagerElementType::GameElement(merged),RetainerMergerInfo::Merged((ctx_element_a, a), (ctx_element_b, b)))
             }
-            (Some((ctx_element_a, SpecificElementType::BlockFixed)),_) => {
+            (Some((ctx_element_a, SpecificElementType::BlockFixed)), _, _) => {
                 self.2.0 = self.2.1;
                 self.2.1 = None;
                 let steps = (self.0 - 1 - 1) - self.1;
@@ -102,19 +158,19 @@ impl<T : Copy+Add<Output=T>+PartialEq> RetainerManager<SpecificElementType<T>> f
                 self.1 += 1;
                 (RetainerManagerElementType::GameElementWithIncrementalIgnore(steps, SpecificElementType::BlockFixed),RetainerMergerInfo::NotMerged(ctx_element_a, SpecificElementType::BlockFixed))
             }
-            (Some((ctx_element_a, specific_element_type_a)), Some((ctx_element_b, specific_element_type_b))) => {
+            (Some((ctx_element_a, specific_element_type_a)), Some((ctx_element_b, specific_element_type_b)), _) => {
                 self.2.0 =  Some((ctx_element_b, specific_element_type_b));
                 self.2.1 = None;
                 self.1 +=1;
                 (RetainerManagerElementType::GameElement(specific_element_type_a),RetainerMergerInfo::NotMerged(ctx_element_a, specific_element_type_a))
             }
-            (Some((ctx_element_a, specific_element_type)), None) => {
+            (Some((ctx_element_a, specific_element_type)), None, _) => {
                 self.2.0 =  None;
                 self.2.1 = None;
                 self.1 +=1;
                 (RetainerManagerElementType::GameElement(specific_element_type),RetainerMergerInfo::NotMerged(ctx_element_a, specific_element_type))
             }
-            _ => { 
+            _ => {
                 self.2.0 = self.2.1;
                 self.2.1 = None;
                 (RetainerManagerElementType::NeutralIgnore,RetainerMergerInfo::None)
@@ -123,4 +179,16 @@ impl<T : Copy+Add<Output=T>+PartialEq> RetainerManager<SpecificElementType<T>> f
         self.0 +=1;
         ret
     }
+
+    fn step_into(target: Option<(CtxElementType, SpecificElementType<T>)>, moving: (CtxElementType, SpecificElementType<T>)) -> Option<(SpecificElementType<T>, Self::RetainerMergerInfoType)> {
+        match (target, moving.1) {
+            (_, SpecificElementType::BlockFixed) => None,
+            (None, a) => Some((a, RetainerMergerInfo::NotMerged(moving.0, a))),
+            (Some(target), b) => target.1.merge_with::<R>(b).map(|merged| (merged, RetainerMergerInfo::Merged(target, moving))),
+        }
+    }
+
+    fn still(ctx_element: CtxElementType, element: SpecificElementType<T>) -> Self::RetainerMergerInfoType {
+        RetainerMergerInfo::NotMerged(ctx_element, element)
+    }
 }
@@ -0,0 +1,222 @@
+// Plays many games of a variant with an automatic strategy, used to compare
+// the balance of the variants (see the `simulate` binary).
+use std::collections::BTreeMap;
+use std::thread;
+
+use crate::game::game_variants::game_variant_1::{GameVariant, GameSetup, GameStatus, VariantRules, max_tile, populate_game};
+use crate::game::merge_rules::{ClassicMergeRule, FibonacciMergeRule, TriplingMergeRule, ThreesMergeRule};
+use crate::game::rand_manager::RandManager;
+use crate::game::strategies::Strategy;
+use crate::variants::Rules;
+
+// capacity of the simulated boards, the played size is chosen at runtime
+pub const MAX_BOARD_SIDE: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub games: usize,
+    pub setup: GameSetup,
+    pub rules: Rules,
+    // (rows, columns)
+    pub size: (usize, usize),
+    pub strategy: Strategy,
+    pub threads: usize,
+    pub seed: u64,
+    // safety limit for strategies that could loop forever
+    pub max_moves: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    pub seed: u64,
+    pub score: i32,
+    pub max_tile: i32,
+    pub moves: usize,
+    pub status: GameStatus,
+}
+
+// every game seed is derived from the simulation seed and the game index, so the
+// results do not depend on the number of threads
+pub fn game_seed(simulation_seed: u64, game_idx: usize) -> u64 {
+    RandManager::new_seeded(simulation_seed.wrapping_add(game_idx as u64)).next_u64()
+}
+
+pub fn play_game<R: VariantRules>(config: &SimulationConfig, seed: u64) -> GameRecord {
+    let mut g = match GameVariant::<MAX_BOARD_SIDE, MAX_BOARD_SIDE, R>::new_game_specific_dim_seeded(config.size, seed) {
+        Ok(g) => g,
+        Err(e) => panic!("{e}"),
+    };
+    populate_game(&mut g, config.setup);
+
+    let mut strategy_rand_manager = RandManager::new_seeded(!seed);
+    let mut moves = 0;
+    while g.game_status == GameStatus::INPROGRESS && moves < config.max_moves {
+        match config.strategy.choose_move(&g, &mut strategy_rand_manager) {
+            Some(m) => g.move_generic(m),
+            None => break,
+        }
+        moves += 1;
+    }
+
+    GameRecord {
+        seed,
+        score: g.game_variant_data.score,
+        max_tile: max_tile(&g),
+        moves,
+        status: g.game_status,
+    }
+}
+
+// the records are returned in the order of the games indexes
+pub fn run_simulation(config: &SimulationConfig) -> Vec<GameRecord> {
+    match config.rules {
+        Rules::Classic => run_simulation_with_rules::<ClassicMergeRule>(config),
+        Rules::Fibonacci => run_simulation_with_rules::<FibonacciMergeRule>(config),
+        Rules::Tripling => run_simulation_with_rules::<TriplingMergeRule>(config),
+        Rules::Threes => run_simulation_with_rules::<ThreesMergeRule>(config),
+    }
+}
+
+fn run_simulation_with_rules<R: VariantRules>(config: &SimulationConfig) -> Vec<GameRecord> {
+    let threads = config.threads.clamp(1, config.games.max(1));
+    let mut records: Vec<Option<GameRecord>> = vec![None; config.games];
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread_idx| {
+                scope.spawn(move || {
+                    (thread_idx..config.games)
+                        .step_by(threads)
+                        .map(|game_idx| (game_idx, play_game::<R>(config, game_seed(config.seed, game_idx))))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            for (game_idx, record) in handle.join().expect("a simulation thread panicked") {
+                records[game_idx] = Some(record);
+            }
+        }
+    });
+
+    records.into_iter().flatten().collect()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Distribution {
+    pub min: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    pub fn from_values(mut values: Vec<f64>) -> Distribution {
+        if values.is_empty() {
+            return Distribution::default();
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+        let len = values.len();
+        let median = if len.is_multiple_of(2) {
+            (values[len / 2 - 1] + values[len / 2]) / 2.0
+        } else {
+            values[len / 2]
+        };
+        Distribution {
+            min: values[0],
+            mean: values.iter().sum::<f64>() / len as f64,
+            median,
+            max: values[len - 1],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationSummary {
+    pub games: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub scores: Distribution,
+    pub moves: Distribution,
+    // number of games per max tile reached
+    pub max_tiles: BTreeMap<i32, usize>,
+    pub total_moves: usize,
+    pub moves_per_second: f64,
+}
+
+pub fn summarize(records: &[GameRecord], elapsed_seconds: f64) -> SimulationSummary {
+    let wins = records.iter().filter(|r| r.status == GameStatus::END_SUCCESS).count();
+    let total_moves = records.iter().map(|r| r.moves).sum();
+    let mut max_tiles = BTreeMap::new();
+    for r in records {
+        *max_tiles.entry(r.max_tile).or_insert(0) += 1;
+    }
+    SimulationSummary {
+        games: records.len(),
+        wins,
+        win_rate: if records.is_empty() { 0.0 } else { wins as f64 / records.len() as f64 },
+        scores: Distribution::from_values(records.iter().map(|r| r.score as f64).collect()),
+        moves: Distribution::from_values(records.iter().map(|r| r.moves as f64).collect()),
+        max_tiles,
+        total_moves,
+        moves_per_second: if elapsed_seconds > 0.0 { total_moves as f64 / elapsed_seconds } else { 0.0 },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::VARIANTS;
+
+    fn config(threads: usize) -> SimulationConfig {
+        SimulationConfig {
+            games: 7,
+            setup: VARIANTS[0].setup,
+            rules: Rules::Classic,
+            size: (4, 4),
+            strategy: Strategy::Random,
+            threads,
+            seed: 42,
+            max_moves: 200,
+        }
+    }
+
+    fn record(score: i32, moves: usize, max_tile: i32, status: GameStatus) -> GameRecord {
+        GameRecord { seed: 0, score, max_tile, moves, status }
+    }
+
+    #[test]
+    fn seeded_simulation_does_not_depend_on_the_threads() {
+        let outline = |records: Vec<GameRecord>| records.into_iter().map(|r| (r.seed, r.score, r.max_tile, r.moves)).collect::<Vec<_>>();
+        let single = outline(run_simulation(&config(1)));
+        assert_eq!(single.len(), 7);
+        assert_eq!(single, outline(run_simulation(&config(3))));
+        assert_eq!(single, outline(run_simulation(&config(16))));
+    }
+
+    #[test]
+    fn summary_of_the_records() {
+        let records = [
+            record(40, 9, 16, GameStatus::END_FAIL),
+            record(10, 3, 8, GameStatus::END_FAIL),
+            record(30, 7, 16, GameStatus::END_SUCCESS),
+            record(20, 5, 32, GameStatus::END_FAIL),
+        ];
+        let summary = summarize(&records, 2.0);
+        assert_eq!(summary.games, 4);
+        assert_eq!(summary.wins, 1);
+        assert_eq!(summary.win_rate, 0.25);
+        assert_eq!((summary.scores.min, summary.scores.mean, summary.scores.median, summary.scores.max), (10.0, 25.0, 25.0, 40.0));
+        assert_eq!((summary.moves.min, summary.moves.median, summary.moves.max), (3.0, 6.0, 9.0));
+        assert_eq!(summary.max_tiles, BTreeMap::from([(8, 1), (16, 2), (32, 1)]));
+        assert_eq!(summary.total_moves, 24);
+        assert_eq!(summary.moves_per_second, 12.0);
+    }
+
+    #[test]
+    fn odd_median_and_empty_summary() {
+        assert_eq!(Distribution::from_values(vec![5.0, 1.0, 3.0]).median, 3.0);
+        let summary = summarize(&[], 0.0);
+        assert_eq!((summary.games, summary.win_rate, summary.moves_per_second), (0, 0.0, 0.0));
+    }
+}
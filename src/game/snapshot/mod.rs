@@ -0,0 +1,212 @@
+// Versioned text snapshot of a game in progress, enough to continue it exactly where it stopped:
+//
+// version 3
+// size 4x4
+// score 1234
+// moves 321
+// status in_progress
+// rng 987654321
+// seed 123456789
+// goal tile 2048
+// move_limit -
+// spawn 0 0 0 15 3 off
+// weights 2:9 4:1
+// next -
+// sequence -
+// source random
+// board
+// 2 . B3 #
+// ...
+//
+// the cells use the level format, a timed tile is `t<value>.<age>`. The spawn line is the joker,
+// bomb and block chances, the bomb countdown, the block hit points and the timed tiles
+// (`off`, `decaying.<moves>` or `petrifying.<moves>`). The weights are the spawned values with
+// their weights, the version 1 had none and used the ones of the rules. The seed the game was
+// created with is `-` when unknown, the versions 1 and 2 did not keep it.
+use std::collections::VecDeque;
+
+use crate::game::game_kernel::Swap2DGameConfig;
+use crate::game::game_variants::game_variant_1::{GameVariant, SpawnConfig, SpawnSource, SpecificElementType, SpecificGame, TimedTiles, VariantRules};
+use crate::game::levels::{cell_from_text, cell_to_text, goal_from_text, goal_to_text};
+use crate::game::rand_manager::RandManager;
+use crate::text::{parse_number, parse_size, status_from_text, status_to_text};
+
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+fn snapshot_cell_to_text(cell: Option<SpecificElementType>) -> String {
+    match cell {
+        Some(SpecificElementType::Timed(a, age)) => format!("t{a}.{age}"),
+        _ => cell_to_text(cell),
+    }
+}
+
+fn snapshot_cell_from_text(text: &str) -> Result<Option<SpecificElementType>, String> {
+    match text.strip_prefix('t').and_then(|timed| timed.split_once('.')) {
+        Some((a, age)) => match (a.parse(), age.parse()) {
+            (Ok(a), Ok(age)) => Ok(Some(SpecificElementType::Timed(a, age))),
+            _ => Err(format!("invalid cell: {text}")),
+        },
+        None => cell_from_text(text),
+    }
+}
+
+fn timed_to_text(timed: TimedTiles) -> String {
+    match timed {
+        TimedTiles::Off => "off".to_string(),
+        TimedTiles::Decaying(limit) => format!("decaying.{limit}"),
+        TimedTiles::Petrifying(limit) => format!("petrifying.{limit}"),
+    }
+}
+
+fn timed_from_text(text: &str) -> Result<TimedTiles, String> {
+    let invalid = || format!("invalid timed tiles: {text}");
+    match text.split_once('.') {
+        None if text == "off" => Ok(TimedTiles::Off),
+        Some(("decaying", limit)) => limit.parse().map(TimedTiles::Decaying).map_err(|_| invalid()),
+        Some(("petrifying", limit)) => limit.parse().map(TimedTiles::Petrifying).map_err(|_| invalid()),
+        _ => Err(invalid()),
+    }
+}
+
+pub fn weights_to_text(spawn_weights: &[(i32, u32)]) -> String {
+    spawn_weights.iter().map(|(value, weight)| format!("{value}:{weight}")).collect::<Vec<_>>().join(" ")
+}
+
+pub fn weights_from_text(text: &str) -> Result<Vec<(i32, u32)>, String> {
+    text.split_whitespace()
+        .map(|pair| match pair.split_once(':') {
+            Some((value, weight)) => Ok((parse_number("weights", value)?, parse_number("weights", weight)?)),
+            None => Err(format!("invalid weights: {pair}")),
+        })
+        .collect()
+}
+
+fn optional_to_text(value: Option<String>) -> String {
+    value.unwrap_or("-".to_string())
+}
+
+pub fn game_to_text<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>) -> String {
+    let data = &g.game_variant_data;
+    let spawn = data.spawn_config;
+    let sequence = data.spawn_sequence.as_ref().map(|sequence| sequence.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(" "));
+    let source = match data.spawn_source {
+        SpawnSource::Random => "random",
+        SpawnSource::External => "external",
+    };
+    let mut text = format!("version {SNAPSHOT_VERSION}\n");
+    text.push_str(&format!("size {}x{}\n", g.board_size.0, g.board_size.1));
+    text.push_str(&format!("score {}\n", data.score));
+    text.push_str(&format!("moves {}\n", data.moves));
+    text.push_str(&format!("status {}\n", status_to_text(g.game_status)));
+    text.push_str(&format!("rng {}\n", data.rand_manager.state()));
+    text.push_str(&format!("seed {}\n", optional_to_text(data.seed.map(|seed| seed.to_string()))));
+    text.push_str(&format!("goal {}\n", goal_to_text(data.goal)));
+    text.push_str(&format!("move_limit {}\n", optional_to_text(data.move_limit.map(|a| a.to_string()))));
+    text.push_str(&format!(
+        "spawn {} {} {} {} {} {}\n",
+        spawn.joker_per_mille, spawn.bomb_per_mille, spawn.bomb_countdown, spawn.block_per_mille, spawn.block_hp, timed_to_text(spawn.timed)
+    ));
+    text.push_str(&format!("weights {}\n", weights_to_text(&data.spawn_weights)));
+    text.push_str(&format!("next {}\n", optional_to_text(data.next_value.map(|e| snapshot_cell_to_text(Some(e))))));
+    text.push_str(&format!("sequence {}\n", optional_to_text(sequence)));
+    text.push_str(&format!("source {source}\n"));
+    text.push_str("board\n");
+    for id_x in 0..g.board_size.0 {
+        let cells: Vec<String> = (0..g.board_size.1).map(|id_y| snapshot_cell_to_text(g.board_get_element((id_x, id_y)))).collect();
+        text.push_str(&cells.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+// the snapshots of older versions are migrated to the current one, the newer ones are rejected
+fn migrate<R: VariantRules>(version: u32, mut lines: Vec<String>) -> Result<Vec<String>, String> {
+    if version > SNAPSHOT_VERSION {
+        return Err(format!("the snapshot comes from a newer version ({version})"));
+    }
+    if version == 0 {
+        return Err(format!("unsupported snapshot version {version}"));
+    }
+    if version < 2 {
+        lines.insert(0, format!("weights {}", weights_to_text(R::SPAWN_WEIGHTS)));
+    }
+    if version < 3 {
+        lines.insert(0, "seed -".to_string());
+    }
+    Ok(lines)
+}
+
+pub fn game_from_text<const C_W: usize, const C_H: usize, R: VariantRules>(text: &str) -> Result<SpecificGame<C_W, C_H, R>, String> {
+    let mut lines: Vec<String> = text.lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+    let version = match lines.first().and_then(|line| line.strip_prefix("version ")) {
+        Some(version) => parse_number("version", version)?,
+        None => return Err("the snapshot has no version".to_string()),
+    };
+    lines = migrate::<R>(version, lines.split_off(1))?;
+
+    let value_of = |key: &str| -> Result<&str, String> {
+        lines
+            .iter()
+            .find_map(|line| line.strip_prefix(key).and_then(|rest| rest.strip_prefix(' ')))
+            .ok_or(format!("missing {key}"))
+    };
+    let size = parse_size(value_of("size")?)?;
+    let mut g = GameVariant::<C_W, C_H, R>::new_game_specific_dim_seeded(size, 0)?;
+    let data = &mut g.game_variant_data;
+    data.score = parse_number("score", value_of("score")?)?;
+    data.moves = parse_number("moves", value_of("moves")?)?;
+    data.rand_manager = RandManager::new_seeded(parse_number("rng", value_of("rng")?)?);
+    data.seed = match value_of("seed")? {
+        "-" => None,
+        seed => Some(parse_number("seed", seed)?),
+    };
+    data.goal = goal_from_text(value_of("goal")?)?;
+    data.move_limit = match value_of("move_limit")? {
+        "-" => None,
+        limit => Some(parse_number("move_limit", limit)?),
+    };
+    let spawn: Vec<&str> = value_of("spawn")?.split_whitespace().collect();
+    data.spawn_config = match spawn.as_slice() {
+        [joker, bomb, countdown, block, hp, timed] => SpawnConfig {
+            joker_per_mille: parse_number("spawn", joker)?,
+            bomb_per_mille: parse_number("spawn", bomb)?,
+            bomb_countdown: parse_number("spawn", countdown)?,
+            block_per_mille: parse_number("spawn", block)?,
+            block_hp: parse_number("spawn", hp)?,
+            timed: timed_from_text(timed)?,
+        },
+        _ => return Err("invalid spawn".to_string()),
+    };
+    data.spawn_weights = weights_from_text(value_of("weights")?)?;
+    data.next_value = match value_of("next")? {
+        "-" => None,
+        next => snapshot_cell_from_text(next)?,
+    };
+    data.spawn_sequence = match value_of("sequence")? {
+        "-" => None,
+        sequence => Some(sequence.split_whitespace().map(|a| parse_number("sequence", a)).collect::<Result<VecDeque<_>, _>>()?),
+    };
+    data.spawn_source = match value_of("source")? {
+        "random" => SpawnSource::Random,
+        "external" => SpawnSource::External,
+        source => return Err(format!("invalid source: {source}")),
+    };
+    g.game_status = status_from_text(value_of("status")?)?;
+
+    let board_start = lines.iter().position(|line| line == "board").ok_or("missing board")? + 1;
+    let rows = &lines[board_start..];
+    if rows.len() != size.0 {
+        return Err(format!("the board shall have {} rows", size.0));
+    }
+    for (id_x, row) in rows.iter().enumerate() {
+        let cells = row.split_whitespace().map(snapshot_cell_from_text).collect::<Result<Vec<_>, String>>()?;
+        if cells.len() != size.1 {
+            return Err(format!("the board row `{row}` shall have {} cells", size.1));
+        }
+        for (id_y, cell) in cells.into_iter().enumerate() {
+            g.board_set_element((id_x, id_y), cell);
+        }
+    }
+    Ok(g)
+}
+
@@ -0,0 +1,244 @@
+// Automatic players used by the simulation runner, they only rely on the public
+// game api: a move is evaluated on a copy of the game without spawning a new tile.
+use crate::game::game_kernel::Swap2DGameConfig;
+use crate::game::game_variants::game_variant_1::{SpecificGame, SpecificElementType, AllowedMoves, VariantRules, is_status_changed};
+use crate::game::rand_manager::RandManager;
+
+pub const ALL_MOVES: [AllowedMoves; 4] = [AllowedMoves::UP, AllowedMoves::DOWN, AllowedMoves::LEFT, AllowedMoves::RIGHT];
+
+// the corner strategy keeps the biggest tiles in the bottom left corner
+const CORNER_MOVES_PRIORITY: [AllowedMoves; 4] = [AllowedMoves::DOWN, AllowedMoves::LEFT, AllowedMoves::RIGHT, AllowedMoves::UP];
+
+// number of player moves looked ahead by the ai after the evaluated move, the spawns are
+// approximated by the most common spawned value
+const AI_DEPTH: usize = 1;
+// above this number of empty cells, the ai only evaluates a spread subset of the spawns
+const AI_MAX_CHANCE_CELLS: usize = 8;
+const AI_LOST_PENALTY: f64 = 100_000.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Strategy {
+    Random,
+    Greedy,
+    Corner,
+    Ai,
+}
+
+const STRATEGIES_NAMES: [(Strategy, &str); 4] = [
+    (Strategy::Random, "random"),
+    (Strategy::Greedy, "greedy"),
+    (Strategy::Corner, "corner"),
+    (Strategy::Ai, "ai"),
+];
+
+impl Strategy {
+
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        STRATEGIES_NAMES.iter().find(|(_, n)| *n == name).map(|(s, _)| *s)
+    }
+
+    pub fn name(&self) -> &'static str {
+        STRATEGIES_NAMES.iter().find(|(s, _)| s == self).map(|(_, n)| *n).unwrap_or("")
+    }
+
+    // None when no move changes the board
+    pub fn choose_move<const C_W: usize, const C_H: usize, R: VariantRules>(&self, g: &SpecificGame<C_W, C_H, R>, rand_manager: &mut RandManager) -> Option<AllowedMoves> {
+        let candidates: Vec<(AllowedMoves, SpecificGame<C_W, C_H, R>)> = ALL_MOVES
+            .iter()
+            .filter_map(|m| apply_merge(g, *m).map(|after| (*m, after)))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match self {
+            Strategy::Random => Some(candidates[rand_manager.gen_range(0, candidates.len())].0),
+            Strategy::Greedy => best_move(&candidates, |after| {
+                (after.game_variant_data.score - g.game_variant_data.score) as f64 + after.game_variant_data.nones_number as f64 * 0.1
+            }),
+            Strategy::Corner => CORNER_MOVES_PRIORITY
+                .iter()
+                .find(|m| candidates.iter().any(|(c, _)| c == *m))
+                .copied(),
+            Strategy::Ai => best_move(&candidates, |after| chance_node(after, AI_DEPTH)),
+        }
+    }
+}
+
+fn best_move<const C_W: usize, const C_H: usize, R: VariantRules>(candidates: &[(AllowedMoves, SpecificGame<C_W, C_H, R>)], evaluation: impl Fn(&SpecificGame<C_W, C_H, R>) -> f64) -> Option<AllowedMoves> {
+    let mut best: Option<(AllowedMoves, f64)> = None;
+    for (m, after) in candidates {
+        let value = evaluation(after);
+        match best {
+            Some((_, best_value)) if best_value >= value => {}
+            _ => best = Some((*m, value)),
+        }
+    }
+    best.map(|(m, _)| m)
+}
+
+// the game after the move without the spawn, None if the move does not change the board
+pub fn apply_merge<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, move_type: AllowedMoves) -> Option<SpecificGame<C_W, C_H, R>> {
+    let mut after = g.clone();
+    after.merge(move_type);
+    if is_status_changed(&after) {
+        Some(after)
+    } else {
+        None
+    }
+}
+
+pub fn empty_cells<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            if g.board_get_element((id_x, id_y)).is_none() {
+                cells.push((id_x, id_y));
+            }
+        }
+    }
+    cells
+}
+
+// value of the position for the player about to move, the evil spawner minimizes it
+pub fn player_value<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>) -> f64 {
+    max_node(g, 1)
+}
+
+// average over the possible spawns
+fn chance_node<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, depth: usize) -> f64 {
+    let cells = empty_cells(g);
+    if cells.is_empty() {
+        return max_node(g, depth);
+    }
+    let step = cells.len().div_ceil(AI_MAX_CHANCE_CELLS);
+    let mut total = 0.0;
+    let mut count = 0.0;
+    for idx in cells.iter().step_by(step) {
+        let mut spawned = g.clone();
+        spawned.board_set_element(*idx, Some(SpecificElementType::Some(R::SPAWN_WEIGHTS[0].0)));
+        total += max_node(&spawned, depth);
+        count += 1.0;
+    }
+    total / count
+}
+
+fn max_node<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, depth: usize) -> f64 {
+    if depth == 0 {
+        return heuristic(g);
+    }
+    let mut best: Option<f64> = None;
+    for m in ALL_MOVES {
+        if let Some(after) = apply_merge(g, m) {
+            let value = chance_node(&after, depth - 1);
+            if best.is_none_or(|b| value > b) {
+                best = Some(value);
+            }
+        }
+    }
+    best.unwrap_or(heuristic(g) - AI_LOST_PENALTY)
+}
+
+fn tile_rank<R: VariantRules>(el: Option<SpecificElementType>) -> f64 {
+    el.and_then(|e| e.number()).map_or(0.0, |a| R::tile_rank(a) as f64 + 1.0)
+}
+
+// rewards empty cells, neighbours of the same value, monotonic lines and the biggest tile in a corner
+fn heuristic<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>) -> f64 {
+    let (rows, cols) = g.board_size;
+    let mut empties = 0.0;
+    let mut same_neighbours = 0.0;
+    let mut monotonic_penalty = 0.0;
+    let mut max_rank = 0.0;
+
+    for id_x in 0..rows {
+        for id_y in 0..cols {
+            let el = g.board_get_element((id_x, id_y));
+            let rank = tile_rank::<R>(el);
+            if el.is_none() {
+                empties += 1.0;
+            }
+            if rank > max_rank {
+                max_rank = rank;
+            }
+            if id_y + 1 < cols {
+                let right = tile_rank::<R>(g.board_get_element((id_x, id_y + 1)));
+                if rank > 0.0 && rank == right {
+                    same_neighbours += rank;
+                }
+                if right > rank {
+                    monotonic_penalty += right - rank;
+                }
+            }
+            if id_x + 1 < rows {
+                let down = tile_rank::<R>(g.board_get_element((id_x + 1, id_y)));
+                if rank > 0.0 && rank == down {
+                    same_neighbours += rank;
+                }
+                if rank > down {
+                    monotonic_penalty += rank - down;
+                }
+            }
+        }
+    }
+
+    let corner_rank = tile_rank::<R>(g.board_get_element((rows - 1, 0)));
+    let corner_bonus = if corner_rank == max_rank { max_rank } else { 0.0 };
+
+    g.game_variant_data.score as f64 + 270.0 * empties + 70.0 * same_neighbours - 47.0 * monotonic_penalty + 100.0 * corner_bonus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::GameVariant;
+    use crate::game::levels::cell_from_text;
+
+    const STRATEGIES: [Strategy; 4] = [Strategy::Random, Strategy::Greedy, Strategy::Corner, Strategy::Ai];
+
+    fn game(rows: &[&str]) -> SpecificGame<4, 4> {
+        let mut g = GameVariant::<4, 4>::new_game_specific_dim_seeded((4, 4), 7).unwrap();
+        for (id_x, row) in rows.iter().enumerate() {
+            for (id_y, cell) in row.split_whitespace().enumerate() {
+                g.board_set_element((id_x, id_y), cell_from_text(cell).unwrap());
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn strategies_only_choose_moves_that_change_the_board() {
+        // only up and right move the lone tile of the bottom left corner
+        let g = game(&[". . . .", ". . . .", ". . . .", "2 . . ."]);
+        for strategy in STRATEGIES {
+            let mut rand_manager = RandManager::new_seeded(1);
+            for _ in 0..10 {
+                let chosen = strategy.choose_move(&g, &mut rand_manager);
+                assert!(matches!(chosen, Some(AllowedMoves::UP) | Some(AllowedMoves::RIGHT)), "{} chose {chosen:?}", strategy.name());
+            }
+        }
+    }
+
+    #[test]
+    fn strategies_give_up_on_a_stuck_board() {
+        let g = game(&["2 4 2 4", "4 2 4 2", "2 4 2 4", "4 2 4 2"]);
+        for strategy in STRATEGIES {
+            assert_eq!(strategy.choose_move(&g, &mut RandManager::new_seeded(1)), None, "{}", strategy.name());
+        }
+    }
+
+    #[test]
+    fn greedy_takes_the_biggest_merge() {
+        let g = game(&["2 2 . .", ". . . .", ". . . .", "8 8 . ."]);
+        let chosen = Strategy::Greedy.choose_move(&g, &mut RandManager::new_seeded(1));
+        assert!(matches!(chosen, Some(AllowedMoves::LEFT) | Some(AllowedMoves::RIGHT)));
+    }
+
+    #[test]
+    fn strategies_names_round_trip() {
+        for strategy in STRATEGIES {
+            assert_eq!(Strategy::from_name(strategy.name()), Some(strategy));
+        }
+        assert_eq!(Strategy::from_name("cheat"), None);
+    }
+}
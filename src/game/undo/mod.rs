@@ -0,0 +1,39 @@
+// Undo of the last moves: the states before the moves are kept, as many as the undos left
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndoHistory<G> {
+    // oldest first
+    pub states: Vec<G>,
+    pub undos_left: usize,
+}
+
+impl<G: Clone> UndoHistory<G> {
+
+    pub fn new(allowance: usize) -> UndoHistory<G> {
+        UndoHistory { states: Vec::new(), undos_left: allowance }
+    }
+
+    // called with the state before every move that changed the board
+    pub fn record(&mut self, state: G) {
+        if self.undos_left == 0 {
+            return;
+        }
+        self.states.push(state);
+        if self.states.len() > self.undos_left {
+            self.states.remove(0);
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undos_left > 0 && !self.states.is_empty()
+    }
+
+    // the state before the last move, it uses one undo
+    pub fn undo(&mut self) -> Option<G> {
+        if self.undos_left == 0 {
+            return None;
+        }
+        let state = self.states.pop()?;
+        self.undos_left -= 1;
+        Some(state)
+    }
+}
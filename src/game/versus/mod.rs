@@ -0,0 +1,138 @@
+// Hot-seat versus: both boards are created with the same seed so they receive the same spawns,
+// and the big merges of a player drop blocks (garbage) on the opponent's board
+use crate::game::game_kernel::Swap2DGameConfig;
+use crate::game::game_variants::game_variant_1::{GameVariant, GameStatus, MergerInfo, RetainerMergerInfo, SpawnConfig, SpecificElementType, SpecificGame, GameSetup, populate_game, set_nth_none_element};
+use crate::game::rand_manager::RandManager;
+
+// a merge into a tile of at least this value sends a block
+pub const GARBAGE_THRESHOLD: i32 = 32;
+pub const GARBAGE_BLOCK_HP: usize = 2;
+
+const VERSUS_SETUP: GameSetup = GameSetup { start_tiles: 2, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY };
+
+pub fn new_boards<const C_W: usize, const C_H: usize>(size: (usize, usize), seed: u64) -> Result<[SpecificGame<C_W, C_H>; 2], String> {
+    let mut g = GameVariant::<C_W, C_H>::new_game_specific_dim_seeded(size, seed)?;
+    populate_game(&mut g, VERSUS_SETUP);
+    Ok([g.clone(), g])
+}
+
+// merges of the last move into a tile of at least the threshold
+pub fn big_merges<const C_W: usize, const C_H: usize>(g: &SpecificGame<C_W, C_H>) -> usize {
+    let mut count = 0;
+    for id_x in 0..g.board_size.0 {
+        for id_y in 0..g.board_size.1 {
+            let info: Option<MergerInfo> = g.game_variant_data.mergers_infos[id_x][id_y];
+            let merged = g.board_get_element((id_x, id_y)).and_then(|e| e.number());
+            if matches!(info, Some(RetainerMergerInfo::Merged(_, _))) && merged.is_some_and(|a| a >= GARBAGE_THRESHOLD) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// the garbage has its own random generator so the spawns of both boards stay the same
+pub fn drop_garbage<const C_W: usize, const C_H: usize>(g: &mut SpecificGame<C_W, C_H>, blocks: usize, rand_manager: &mut RandManager) {
+    if g.game_status != GameStatus::INPROGRESS {
+        return;
+    }
+    for _ in 0..blocks {
+        if g.game_variant_data.nones_number == 0 {
+            break;
+        }
+        let insert_idx = rand_manager.gen_range(0, g.game_variant_data.nones_number);
+        set_nth_none_element(g, insert_idx, Some(SpecificElementType::Block(GARBAGE_BLOCK_HP)));
+    }
+    g.game_status = g.board_game_status_fn();
+}
+
+// index of the winner: the first player to lose loses the match, the first to win wins it
+pub fn winner<const C_W: usize, const C_H: usize>(boards: &[SpecificGame<C_W, C_H>; 2]) -> Option<usize> {
+    match (boards[0].game_status, boards[1].game_status) {
+        (GameStatus::END_FAIL, _) | (_, GameStatus::END_SUCCESS) => Some(1),
+        (GameStatus::END_SUCCESS, _) | (_, GameStatus::END_FAIL) => Some(0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use crate::game::game_variants::game_variant_1::AllowedMoves;
+    use crate::game::levels::cell_from_text;
+
+    fn game(rows: &[&str]) -> SpecificGame<4, 4> {
+        let mut g = GameVariant::<4, 4>::new_game_specific_dim_seeded((4, 4), 5).unwrap();
+        g.game_variant_data.spawn_sequence = Some(VecDeque::new());
+        for (id_x, row) in rows.iter().enumerate() {
+            for (id_y, cell) in row.split_whitespace().enumerate() {
+                g.board_set_element((id_x, id_y), cell_from_text(cell).unwrap());
+            }
+        }
+        g.game_variant_data.nones_number = rows.iter().flat_map(|row| row.split_whitespace()).filter(|cell| *cell == ".").count();
+        g
+    }
+
+    fn with_status(status: GameStatus) -> SpecificGame<4, 4> {
+        let mut g = game(&[". . . .", ". . . .", ". . . .", ". . . ."]);
+        g.game_status = status;
+        g
+    }
+
+    #[test]
+    fn winner_of_every_outcome() {
+        use GameStatus::{END_FAIL, END_SUCCESS, INPROGRESS};
+        let cases = [
+            ((INPROGRESS, INPROGRESS), None),
+            ((END_FAIL, INPROGRESS), Some(1)),
+            ((INPROGRESS, END_FAIL), Some(0)),
+            ((END_SUCCESS, INPROGRESS), Some(0)),
+            ((INPROGRESS, END_SUCCESS), Some(1)),
+            ((END_SUCCESS, END_FAIL), Some(0)),
+            ((END_FAIL, END_SUCCESS), Some(1)),
+            // the moves are made one at a time, two ends at once fall in the first arm
+            ((END_FAIL, END_FAIL), Some(1)),
+            ((END_SUCCESS, END_SUCCESS), Some(1)),
+        ];
+        for ((first, second), expected) in cases {
+            assert_eq!(winner(&[with_status(first), with_status(second)]), expected, "{first:?} {second:?}");
+        }
+    }
+
+    #[test]
+    fn only_merges_reaching_the_threshold_send_garbage() {
+        let mut g = game(&["16 16 . .", "8 8 . .", "16 16 . .", "4 4 . ."]);
+        g.move_generic(AllowedMoves::LEFT);
+        assert_eq!(big_merges(&g), 2);
+        let mut small = game(&["8 8 . .", ". . . .", ". . . .", "2 2 . ."]);
+        small.move_generic(AllowedMoves::LEFT);
+        assert_eq!(big_merges(&small), 0);
+    }
+
+    #[test]
+    fn garbage_fills_the_free_cells_only() {
+        let mut rand_manager = RandManager::new_seeded(3);
+        let mut full = game(&["2 4 2 4", "4 2 4 2", "2 4 2 4", "4 2 4 2"]);
+        let before = full.game_variant_data.array;
+        drop_garbage(&mut full, 3, &mut rand_manager);
+        assert_eq!(full.game_variant_data.array, before);
+        assert_eq!(full.game_status, GameStatus::END_FAIL);
+
+        let mut almost_full = game(&["2 4 2 4", "4 2 4 2", "2 4 2 4", "4 2 4 ."]);
+        drop_garbage(&mut almost_full, 3, &mut rand_manager);
+        assert_eq!(almost_full.game_variant_data.array[3][3], Some(SpecificElementType::Block(GARBAGE_BLOCK_HP)));
+        assert_eq!(almost_full.game_variant_data.nones_number, 0);
+    }
+
+    #[test]
+    fn both_boards_get_the_same_spawns() {
+        let [mut first, mut second] = new_boards::<4, 4>((4, 4), 77).unwrap();
+        assert_eq!(first.game_variant_data.array, second.game_variant_data.array);
+        for move_type in [AllowedMoves::LEFT, AllowedMoves::DOWN, AllowedMoves::RIGHT, AllowedMoves::UP, AllowedMoves::LEFT] {
+            first.move_generic(move_type);
+            second.move_generic(move_type);
+            assert_eq!(first.game_variant_data.array, second.game_variant_data.array);
+        }
+    }
+}
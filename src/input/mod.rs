@@ -0,0 +1,321 @@
+// Input layer: the keys, the swipes and the on-screen buttons become game actions here, the
+// boards only run the actions. The keys are bound by presets or by the player, the bindings are
+// saved in the settings as text lines:
+//
+// binding <key> <action>
+//
+// nothing here touches the DOM, the pages hand the key names and the lengths of the swipes, the
+// touch swipes and the mouse or trackpad drags alike.
+use std::f64::consts::{PI, FRAC_PI_2, FRAC_PI_8, TAU};
+
+use crate::game::game_variants::game_variant_1::AllowedMoves;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameAction {
+    Move(AllowedMoves),
+    Undo,
+    Restart,
+    Hint,
+}
+
+pub const ACTIONS: [GameAction; 7] = [
+    GameAction::Move(AllowedMoves::LEFT),
+    GameAction::Move(AllowedMoves::RIGHT),
+    GameAction::Move(AllowedMoves::UP),
+    GameAction::Move(AllowedMoves::DOWN),
+    GameAction::Undo,
+    GameAction::Restart,
+    GameAction::Hint,
+];
+
+const ACTIONS_NAMES: [(GameAction, &str, &str); 7] = [
+    (GameAction::Move(AllowedMoves::LEFT), "left", "Move left"),
+    (GameAction::Move(AllowedMoves::RIGHT), "right", "Move right"),
+    (GameAction::Move(AllowedMoves::UP), "up", "Move up"),
+    (GameAction::Move(AllowedMoves::DOWN), "down", "Move down"),
+    (GameAction::Undo, "undo", "Undo"),
+    (GameAction::Restart, "restart", "Restart"),
+    (GameAction::Hint, "hint", "Hint"),
+];
+
+impl GameAction {
+
+    pub fn from_name(name: &str) -> Option<GameAction> {
+        ACTIONS_NAMES.iter().find(|(_, n, _)| *n == name).map(|(a, _, _)| *a)
+    }
+
+    pub fn name(&self) -> &'static str {
+        ACTIONS_NAMES.iter().find(|(a, _, _)| a == self).map(|(_, n, _)| *n).unwrap_or("")
+    }
+
+    pub fn label(&self) -> &'static str {
+        ACTIONS_NAMES.iter().find(|(a, _, _)| a == self).map(|(_, _, l)| *l).unwrap_or("")
+    }
+}
+
+// what the player did, the key names are the ones of the browser ("ArrowLeft", "a", " "...)
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    Key(String),
+    // a swipe on the screen in pixels, y goes down, shorter than min_length it is not a move
+    Swipe { diff_x: f64, diff_y: f64, min_length: f64 },
+    Button(GameAction),
+}
+
+// the letters are bound whatever the case, the space has a name to be written in a line
+pub fn key_name(key: &str) -> String {
+    match key {
+        " " => "Space".to_string(),
+        _ if key.chars().count() == 1 => key.to_lowercase(),
+        _ => key.to_string(),
+    }
+}
+
+// (key name, action) of every action
+type PresetKeys = [(&'static str, GameAction); 7];
+
+// the presets bind the moves and the other actions, the player edits them from there
+pub const PRESETS: [(&str, &str, PresetKeys); 3] = [
+    ("arrows", "Arrows", [
+        ("ArrowLeft", GameAction::Move(AllowedMoves::LEFT)),
+        ("ArrowRight", GameAction::Move(AllowedMoves::RIGHT)),
+        ("ArrowUp", GameAction::Move(AllowedMoves::UP)),
+        ("ArrowDown", GameAction::Move(AllowedMoves::DOWN)),
+        ("u", GameAction::Undo),
+        ("r", GameAction::Restart),
+        ("h", GameAction::Hint),
+    ]),
+    ("wasd", "WASD", [
+        ("a", GameAction::Move(AllowedMoves::LEFT)),
+        ("d", GameAction::Move(AllowedMoves::RIGHT)),
+        ("w", GameAction::Move(AllowedMoves::UP)),
+        ("s", GameAction::Move(AllowedMoves::DOWN)),
+        ("z", GameAction::Undo),
+        ("r", GameAction::Restart),
+        ("h", GameAction::Hint),
+    ]),
+    ("vim", "Vim", [
+        ("h", GameAction::Move(AllowedMoves::LEFT)),
+        ("l", GameAction::Move(AllowedMoves::RIGHT)),
+        ("k", GameAction::Move(AllowedMoves::UP)),
+        ("j", GameAction::Move(AllowedMoves::DOWN)),
+        ("u", GameAction::Undo),
+        ("r", GameAction::Restart),
+        ("?", GameAction::Hint),
+    ]),
+];
+
+// (key name, action), a key has one action, an action may have several keys
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bindings {
+    pub keys: Vec<(String, GameAction)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Bindings {
+        Bindings::preset(PRESETS[0].0).unwrap_or(Bindings { keys: Vec::new() })
+    }
+}
+
+impl Bindings {
+
+    pub fn preset(name: &str) -> Option<Bindings> {
+        PRESETS.iter().find(|(n, _, _)| *n == name).map(|(_, _, keys)| Bindings {
+            keys: keys.iter().map(|(key, action)| (key.to_string(), *action)).collect(),
+        })
+    }
+
+    // name of the preset the bindings are equal to, None once the player changed them
+    pub fn preset_name(&self) -> Option<&'static str> {
+        PRESETS.iter().map(|(name, _, _)| *name).find(|name| Bindings::preset(name).as_ref() == Some(self))
+    }
+
+    pub fn action(&self, key: &str) -> Option<GameAction> {
+        let key = key_name(key);
+        self.keys.iter().find(|(k, _)| *k == key).map(|(_, action)| *action)
+    }
+
+    pub fn keys_of(&self, action: GameAction) -> Vec<&str> {
+        self.keys.iter().filter(|(_, a)| *a == action).map(|(key, _)| key.as_str()).collect()
+    }
+
+    // the keys of the four moves, for the help texts of the boards
+    pub fn move_keys(&self) -> String {
+        [AllowedMoves::LEFT, AllowedMoves::RIGHT, AllowedMoves::UP, AllowedMoves::DOWN]
+            .iter()
+            .flat_map(|m| self.keys_of(GameAction::Move(*m)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // the key leaves its previous action
+    pub fn bind(&mut self, key: &str, action: GameAction) {
+        let key = key_name(key);
+        self.keys.retain(|(k, _)| *k != key);
+        self.keys.push((key, action));
+    }
+
+    pub fn unbind(&mut self, key: &str) {
+        let key = key_name(key);
+        self.keys.retain(|(k, _)| *k != key);
+    }
+
+    pub fn resolve(&self, event: &InputEvent) -> Option<GameAction> {
+        match event {
+            InputEvent::Key(key) => self.action(key),
+            InputEvent::Swipe { diff_x, diff_y, min_length } => swipe_move(*diff_x, *diff_y, *min_length).map(GameAction::Move),
+            InputEvent::Button(action) => Some(*action),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.keys.iter().map(|(key, action)| format!("binding {key} {}\n", action.name())).collect()
+    }
+
+    // one binding of a line, None for a line that is not a binding
+    pub fn parse_line(line: &str) -> Option<(String, GameAction)> {
+        match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["binding", key, action] => Some((key_name(key), GameAction::from_name(action)?)),
+            _ => None,
+        }
+    }
+}
+
+struct DirectionInfoSwapType {
+    asociated_direction : AllowedMoves,
+    angle_ranges : [Option<(f64,f64)>;2],
+}
+
+const DIRECTION_INFOS : [DirectionInfoSwapType;4]= [
+    DirectionInfoSwapType{
+        asociated_direction : AllowedMoves::RIGHT,
+        angle_ranges : [Some((0.0,0.0 + FRAC_PI_8)), Some((TAU-FRAC_PI_8,TAU))],
+    },
+    DirectionInfoSwapType{
+        asociated_direction : AllowedMoves::UP,
+        angle_ranges : [Some((FRAC_PI_2 - FRAC_PI_8,FRAC_PI_2 + FRAC_PI_8)),None],
+
+    },
+    DirectionInfoSwapType{
+        asociated_direction : AllowedMoves::LEFT,
+        angle_ranges : [Some((PI - FRAC_PI_8,PI + FRAC_PI_8)),None],
+
+    },
+    DirectionInfoSwapType{
+        asociated_direction : AllowedMoves::DOWN,
+        angle_ranges : [Some((3.0*FRAC_PI_2 - FRAC_PI_8,3.0*FRAC_PI_2 + FRAC_PI_8)),None],
+    },
+
+];
+
+fn is_part_of_this_angle_range(angle:f64, angle_ref_info:&DirectionInfoSwapType)-> bool{
+    for (min, max) in angle_ref_info.angle_ranges.into_iter().flatten() {
+        if angle >= min && angle <= max {
+            return true
+        }
+    }
+    false
+}
+
+// the swipes are at least this long whatever the board, a shaky click is not a move
+pub const MIN_SWIPE_PX : f64 = 20.0;
+// share of the board width a swipe shall cover, in percent
+pub const DEFAULT_SWIPE_PERCENT : u32 = 10;
+pub const MAX_SWIPE_PERCENT : u32 = 50;
+
+// minimal length of a swipe on a board of this width, the bigger boards need longer swipes
+pub fn min_swipe_length(board_width : f64, percent : u32) -> f64 {
+    (board_width * percent as f64 / 100.0).max(MIN_SWIPE_PX)
+}
+
+// directions of a swipe, y goes up
+fn get_swap_directions(diff_x : f64, diff_y:f64, min_length : f64) -> Vec<AllowedMoves> {
+
+    let mut directions: Vec<AllowedMoves> = Vec::new();
+    if diff_x.hypot(diff_y) < min_length {
+        return directions
+    }
+
+    // angle in [0, 2π), the horizontal swipes to the left are π
+    let phi = diff_y.atan2(diff_x).rem_euclid(TAU);
+
+    for direction_info in DIRECTION_INFOS {
+        if is_part_of_this_angle_range(phi, &direction_info) {
+            directions.push(direction_info.asociated_direction)
+        }
+    }
+    directions
+
+}
+
+// the move of a swipe on the screen, None for a swipe too short or too diagonal
+pub fn swipe_move(diff_x: f64, diff_y: f64, min_length: f64) -> Option<AllowedMoves> {
+    // because the direction axis of y is down on the screen
+    match get_swap_directions(diff_x, -diff_y, min_length).as_slice() {
+        [direction] => Some(*direction),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_aligned_swipes() {
+        assert_eq!(swipe_move(100.0, 0.0, MIN_SWIPE_PX), Some(AllowedMoves::RIGHT));
+        assert_eq!(swipe_move(-100.0, 0.0, MIN_SWIPE_PX), Some(AllowedMoves::LEFT));
+        // y goes down on the screen
+        assert_eq!(swipe_move(0.0, -100.0, MIN_SWIPE_PX), Some(AllowedMoves::UP));
+        assert_eq!(swipe_move(0.0, 100.0, MIN_SWIPE_PX), Some(AllowedMoves::DOWN));
+    }
+
+    #[test]
+    fn short_and_diagonal_swipes_are_not_moves() {
+        assert_eq!(swipe_move(10.0, 0.0, MIN_SWIPE_PX), None);
+        assert_eq!(swipe_move(100.0, 100.0, MIN_SWIPE_PX), None);
+        // slightly off the axis is still a move
+        assert_eq!(swipe_move(-100.0, 20.0, MIN_SWIPE_PX), Some(AllowedMoves::LEFT));
+        assert_eq!(min_swipe_length(400.0, DEFAULT_SWIPE_PERCENT), 40.0);
+        assert_eq!(min_swipe_length(100.0, DEFAULT_SWIPE_PERCENT), MIN_SWIPE_PX);
+    }
+
+    #[test]
+    fn resolve_keys_swipes_and_buttons() {
+        let mut bindings = Bindings::preset("wasd").unwrap();
+        assert_eq!(bindings.resolve(&InputEvent::Key("a".to_string())), Some(GameAction::Move(AllowedMoves::LEFT)));
+        // the letters whatever the case
+        assert_eq!(bindings.resolve(&InputEvent::Key("W".to_string())), Some(GameAction::Move(AllowedMoves::UP)));
+        assert_eq!(bindings.resolve(&InputEvent::Key("ArrowLeft".to_string())), None);
+        bindings.bind(" ", GameAction::Hint);
+        assert_eq!(bindings.resolve(&InputEvent::Key(" ".to_string())), Some(GameAction::Hint));
+        // a key rebound leaves its previous action
+        bindings.bind("a", GameAction::Undo);
+        assert_eq!(bindings.resolve(&InputEvent::Key("a".to_string())), Some(GameAction::Undo));
+        assert!(bindings.keys_of(GameAction::Move(AllowedMoves::LEFT)).is_empty());
+        assert_eq!(bindings.preset_name(), None);
+
+        let swipe = InputEvent::Swipe { diff_x: 0.0, diff_y: 80.0, min_length: MIN_SWIPE_PX };
+        assert_eq!(bindings.resolve(&swipe), Some(GameAction::Move(AllowedMoves::DOWN)));
+        assert_eq!(bindings.resolve(&InputEvent::Button(GameAction::Restart)), Some(GameAction::Restart));
+    }
+
+    #[test]
+    fn move_keys_follow_the_bindings() {
+        assert_eq!(Bindings::preset("wasd").unwrap().move_keys(), "a, d, w, s");
+        let mut bindings = Bindings::preset("vim").unwrap();
+        bindings.bind("ArrowLeft", GameAction::Move(AllowedMoves::LEFT));
+        assert_eq!(bindings.move_keys(), "h, ArrowLeft, l, k, j");
+    }
+
+    #[test]
+    fn bindings_text_round_trip() {
+        let mut bindings = Bindings::preset("vim").unwrap();
+        bindings.bind(" ", GameAction::Restart);
+        let keys: Vec<(String, GameAction)> = bindings.to_text().lines().filter_map(Bindings::parse_line).collect();
+        assert_eq!(Bindings { keys }, bindings);
+        assert_eq!(Bindings::parse_line("binding Space restart"), Some(("Space".to_string(), GameAction::Restart)));
+        assert_eq!(Bindings::parse_line("binding q jump"), None);
+        assert_eq!(Bindings::parse_line("theme dark"), None);
+    }
+}
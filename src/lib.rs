@@ -0,0 +1,18 @@
+// The game logic is shared by the dioxus application and the command line tools
+pub mod game;
+// the multiplayer protocol is shared by the online mode and the relay server
+pub mod net;
+// the best scores, the history and the saved games
+pub mod storage;
+// the keys, the swipes and the buttons turned into game actions, without the DOM
+pub mod input;
+// the games played again from their seed and their moves, shared as links
+pub mod replay;
+// the variant pages, their rules and setups
+pub mod variants;
+// the themes of the boards, shared by the app and the exported images
+pub mod theme;
+// the boards and the replays as svg and gif files
+pub mod export;
+// parsing helpers shared by the text formats and the tools
+pub mod text;
@@ -1,44 +1,96 @@
 
 mod frontendcomponents;
-mod game;
+
+use rusty_2048::game;
+use rusty_2048::input;
+use rusty_2048::replay;
+use rusty_2048::net;
+use rusty_2048::storage;
+use rusty_2048::storage::campaign::CampaignProgress;
+use rusty_2048::storage::scores::ScoreBook;
+use rusty_2048::storage::settings;
+use rusty_2048::theme;
+use rusty_2048::variants::{self, VARIANTS};
+use rusty_2048::export;
 
 use dioxus::prelude::*;
 
-use self::frontendcomponents::{classic_2048, variant_1_2048, variant_2_2048, variant_3_2048};
+use self::frontendcomponents::variant_board;
+use self::frontendcomponents::adversary::adversary_2048;
+use self::frontendcomponents::campaign::{campaign_level, campaign_select};
+use self::frontendcomponents::daily::daily_challenge;
+use self::frontendcomponents::level_editor::level_editor;
+use self::frontendcomponents::online::online_2048;
+use self::frontendcomponents::settings::settings_page;
+use self::frontendcomponents::share::shared_game;
+use self::frontendcomponents::stats::stats_page;
+use self::frontendcomponents::versus::versus_2048;
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const MAIN_CSS: Asset = asset!("/assets/main.css");
-const HEADER_SVG: Asset = asset!("/assets/header.svg");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 const COMPONENTS_CSS: Asset = asset!("/assets/dx-components-theme.css");
 
-#[derive(Routable, Clone, Copy)]
+#[derive(Routable, Clone)]
 #[rustfmt::skip]
-enum Route {
+pub enum Route {
     /// The home page that present the game options
     #[route("/")]
     Home {},
     
-    #[route("/classic_2048")]
-    Classic2048 {},
+    // a variant of the registry, the old pages of the variants redirect to theirs
+    #[route("/play/:variant_id")]
+    Play { variant_id: String },
+
+    #[redirect("/:variant_id", |variant_id: String| Route::Play { variant_id })]
+
+    #[route("/level_editor")]
+    LevelEditor {},
+
+    #[route("/campaign")]
+    Campaign {},
+
+    #[route("/campaign/:level")]
+    CampaignLevel { level: usize },
+
+    #[route("/daily")]
+    Daily {},
+
+    #[route("/versus")]
+    Versus {},
 
-    #[route("/classic_2048_variant_1")]
-    Classic2048Variant1 {},
+    #[route("/adversary")]
+    Adversary {},
 
-    #[route("/classic_2048_variant_2")]
-    Classic2048Variant2 {},
+    #[route("/online")]
+    Online {},
 
-    #[route("/classic_2048_variant_3")]
-    Classic2048Variant3 {},
+    #[route("/settings")]
+    Settings {},
+
+    #[route("/stats")]
+    Stats {},
+
+    // a finished game shared as a link, see the replay module for the code
+    #[route("/shared/:variant/:code")]
+    Shared { variant: String, code: String },
     
 }
 
 
 fn main() {
-    //dioxus::launch(App);
-    dioxus::launch(|| rsx! {
+    dioxus::launch(App);
+}
+
+#[component]
+fn App() -> Element {
+    // the campaign progress, the scores and the settings are saved
+    use_context_provider(|| Signal::new(CampaignProgress::load(&*storage::default_storage())));
+    use_context_provider(|| Signal::new(ScoreBook::load(&*storage::default_storage())));
+    use_context_provider(|| Signal::new(settings::Settings::load(&*storage::default_storage())));
+    rsx! {
         Router::<Route> {}
-    });
+    }
 }
 
 #[component]
@@ -90,70 +142,114 @@ pub fn Home() -> Element {
                 // VARIANTS GRID
                 section { class: "grid gap-8 sm:grid-cols-2 lg:grid-cols-3",
 
-                    // Variant Card — Classic
+                    // Variant Cards, one by variant of the registry
+                    for def in VARIANTS.iter() {
+                        Link {
+                            key: "{def.id}",
+                            to: Route::Play { variant_id: def.id.to_string() },
+                            class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
+
+                            h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "{def.title}" }
+
+                            p { class: "text-sm text-[#776e65] mt-2", "{def.description}" }
+                        }
+                    }
+
+                    // Daily challenge
                     Link {
-                        to: Route::Classic2048 {},
+                        to: Route::Daily {},
                         class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
 
-                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Classic 2048" }
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Daily Challenge" }
 
                         p { class: "text-sm text-[#776e65] mt-2",
-                            "The original 4×4 grid. Combine tiles to reach 2048!"
+                            "The same game for everyone today. One run per day, then share your result!"
                         }
                     }
 
-                    // Variant Card — Classic
+                    // Versus
                     Link {
-                        to: Route::Classic2048Variant1 {},
+                        to: Route::Versus {},
                         class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
 
-                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2",
-                            "Classic 2048 Variant 1"
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Versus" }
+
+                        p { class: "text-sm text-[#776e65] mt-2",
+                            "Two players on one keyboard with the same tiles. Big merges send blocks to your opponent!"
                         }
+                    }
+
+                    // Adversary
+                    Link {
+                        to: Route::Adversary {},
+                        class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
+
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Adversary" }
 
                         p { class: "text-sm text-[#776e65] mt-2",
-                            "The original 4×4 grid variant 1 with moving blocks. Combine tiles to reach 2048!"
+                            "A friend or an evil AI chooses where every new tile appears. Survive as long as you can!"
                         }
                     }
 
-                    // Variant Card — Classic
+                    // Online race
                     Link {
-                        to: Route::Classic2048Variant2 {},
+                        to: Route::Online {},
                         class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
 
-                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2",
-                            "Classic 2048 Variant 2"
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Online Race" }
+
+                        p { class: "text-sm text-[#776e65] mt-2",
+                            "Race your friends on the same board through a relay server. First to the goal wins!"
                         }
+                    }
+
+                    // Puzzle campaign
+                    Link {
+                        to: Route::Campaign {},
+                        class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
+
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Puzzle Campaign" }
 
                         p { class: "text-sm text-[#776e65] mt-2",
-                            "The original 4×4 grid variant 2 with moving/fixed blocks . Combine tiles to reach 2048!"
+                            "Designed boards with a goal and a move limit. Solve them in few moves to earn three stars!"
                         }
                     }
 
-                    // // Variant Card — Classic
-                    // Link {
-                    //     to: Route::Classic2048Variant3 {},
-                    //     class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
+                    // Level editor
+                    Link {
+                        to: Route::LevelEditor {},
+                        class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
 
-                    //     h2 { class: "text-xl font-semibold text-[#776e65] mt-2",
-                    //         "Classic 2048 Variant 3"
-                    //     }
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Level Editor" }
 
-                    //     p { class: "text-sm text-[#776e65] mt-2",
-                    //         "The original 4×4 grid variant 3 with moving/fixed blocks and flexible size. Combine tiles to reach 2048!"
-                    //     }
-                    // }
+                        p { class: "text-sm text-[#776e65] mt-2",
+                            "Design your own board with tiles and blocks, test it and share it as a level file."
+                        }
+                    }
 
-                    // Placeholder Variant
-                    div { class: "bg-white rounded-xl shadow p-4 text-center opacity-60 cursor-not-allowed",
+                    // Settings
+                    Link {
+                        to: Route::Settings {},
+                        class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
 
-                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "More Coming Soon" }
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Settings" }
 
                         p { class: "text-sm text-[#776e65] mt-2",
-                            "New modes and ideas are on the way."
+                            "Pick the board size, the spawns, the target tile, the obstacles and the undos of every variant."
+                        }
+                    }
+
+                    // Statistics
+                    Link {
+                        to: Route::Stats {},
+                        class: "bg-white rounded-xl shadow hover:shadow-lg transition p-4 text-center",
+
+                        h2 { class: "text-xl font-semibold text-[#776e65] mt-2", "Statistics" }
+
+                        p { class: "text-sm text-[#776e65] mt-2",
+                            "Compare the variants: games played, win rate, scores, max tiles and streaks."
                         }
                     }
-                
                 }
             }
         
@@ -163,47 +259,122 @@ pub fn Home() -> Element {
 
 
 #[component]
-pub fn Classic2048() -> Element {
+pub fn Play(variant_id: String) -> Element {
     rsx!{
         Head {}
         div { class: "bg-[#faf8ef]",
             Navbar {}
-            classic_2048 {}
+            variant_board { key: "{variant_id}", variant_id }
         }
     }
 }
 
+#[component]
+pub fn LevelEditor() -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            level_editor {}
+        }
+    }
+}
+
+#[component]
+pub fn Campaign() -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            campaign_select {}
+        }
+    }
+}
+
+#[component]
+pub fn CampaignLevel(level: usize) -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            campaign_level { level }
+        }
+    }
+}
+
+#[component]
+pub fn Daily() -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            daily_challenge {}
+        }
+    }
+}
+
+#[component]
+pub fn Versus() -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            versus_2048 {}
+        }
+    }
+}
 
 #[component]
-pub fn Classic2048Variant1() -> Element {
+pub fn Adversary() -> Element {
     rsx!{
         Head {}
         div { class: "bg-[#faf8ef]",
             Navbar {}
-            variant_1_2048 {}
+            adversary_2048 {}
         }
     }
 }
 
+#[component]
+pub fn Online() -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            online_2048 {}
+        }
+    }
+}
+
+#[component]
+pub fn Settings() -> Element {
+    rsx!{
+        Head {}
+        div { class: "bg-[#faf8ef]",
+            Navbar {}
+            settings_page {}
+        }
+    }
+}
 
 #[component]
-pub fn Classic2048Variant2() -> Element {
+pub fn Stats() -> Element {
     rsx!{
         Head {}
         div { class: "bg-[#faf8ef]",
             Navbar {}
-            variant_2_2048 {}
+            stats_page {}
         }
     }
 }
 
 #[component]
-pub fn Classic2048Variant3() -> Element {
+pub fn Shared(variant: String, code: String) -> Element {
     rsx!{
         Head {}
         div { class: "bg-[#faf8ef]",
             Navbar {}
-            variant_3_2048 {}
+            shared_game { key: "{variant}/{code}", variant_id: variant, code }
         }
     }
 }
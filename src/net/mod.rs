@@ -0,0 +1,5 @@
+// Online races: the players race on identically seeded boards and a relay server forwards their
+// moves. Only the moves travel, every board is rebuilt from the seed and the move log.
+pub mod protocol;
+pub mod relay;
+pub mod websocket;
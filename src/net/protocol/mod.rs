@@ -0,0 +1,158 @@
+// Line protocol between the players and the relay server, one message per line (or per websocket
+// text frame):
+//
+// client -> server              server -> client
+// join <room> <name> [<token>]  welcome <seed> <goal> <token> <moves log>
+// move <U|D|L|R>                moved <name> <U|D|L|R>
+//                               score <name> <score> <moves> <in_progress|lost|won>
+//                               winner <name>
+//                               error <text>
+//
+// a moves log is written as the moves of the replays, `<number>.<packed directions>`. The token
+// is given to a player on its first join, it is needed to join again under the same name.
+use crate::game::game_variants::game_variant_1::{AllowedMoves, GameStatus};
+use crate::replay::{moves_from_text, moves_to_text};
+use crate::text::{parse_number, status_from_text, status_to_text};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientMessage {
+    Join { room: String, name: String, token: Option<String> },
+    Move(AllowedMoves),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    Welcome { seed: u64, goal: i32, token: String, log: Vec<AllowedMoves> },
+    Moved { name: String, move_type: AllowedMoves },
+    Score { name: String, score: i32, moves: usize, status: GameStatus },
+    Winner { name: String },
+    Error(String),
+}
+
+pub fn move_to_char(move_type: AllowedMoves) -> char {
+    match move_type {
+        AllowedMoves::UP => 'U',
+        AllowedMoves::DOWN => 'D',
+        AllowedMoves::LEFT => 'L',
+        AllowedMoves::RIGHT => 'R',
+    }
+}
+
+pub fn move_from_char(c: char) -> Result<AllowedMoves, String> {
+    match c {
+        'U' => Ok(AllowedMoves::UP),
+        'D' => Ok(AllowedMoves::DOWN),
+        'L' => Ok(AllowedMoves::LEFT),
+        'R' => Ok(AllowedMoves::RIGHT),
+        _ => Err(format!("invalid move: {c}")),
+    }
+}
+
+// a name is one word so it fits in the lines
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 32 && !name.contains(char::is_whitespace)
+}
+
+impl ClientMessage {
+
+    pub fn to_line(&self) -> String {
+        match self {
+            ClientMessage::Join { room, name, token: None } => format!("join {room} {name}"),
+            ClientMessage::Join { room, name, token: Some(token) } => format!("join {room} {name} {token}"),
+            ClientMessage::Move(move_type) => format!("move {}", move_to_char(*move_type)),
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<ClientMessage, String> {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next(), words.next(), words.next()) {
+            (Some("join"), Some(room), Some(name), token, None) if is_valid_name(room) && is_valid_name(name) && token.is_none_or(is_valid_name) => {
+                Ok(ClientMessage::Join { room: room.to_string(), name: name.to_string(), token: token.map(str::to_string) })
+            }
+            (Some("move"), Some(move_text), None, None, None) if move_text.len() == 1 => move_from_char(move_text.chars().next().unwrap_or(' ')).map(ClientMessage::Move),
+            _ => Err(format!("invalid message: {line}")),
+        }
+    }
+}
+
+impl ServerMessage {
+
+    pub fn to_line(&self) -> String {
+        match self {
+            ServerMessage::Welcome { seed, goal, token, log } => format!("welcome {seed} {goal} {token} {}", moves_to_text(log)),
+            ServerMessage::Moved { name, move_type } => format!("moved {name} {}", move_to_char(*move_type)),
+            ServerMessage::Score { name, score, moves, status } => format!("score {name} {score} {moves} {}", status_to_text(*status)),
+            ServerMessage::Winner { name } => format!("winner {name}"),
+            ServerMessage::Error(text) => format!("error {text}"),
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<ServerMessage, String> {
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut words = rest.split_whitespace();
+        match kind {
+            "welcome" => Ok(ServerMessage::Welcome {
+                seed: parse_number("seed", words.next().unwrap_or(""))?,
+                goal: parse_number("goal", words.next().unwrap_or(""))?,
+                token: words.next().ok_or("missing token")?.to_string(),
+                log: moves_from_text(words.next().unwrap_or(""))?,
+            }),
+            "moved" => {
+                let name = words.next().ok_or("missing name")?.to_string();
+                let move_text = words.next().ok_or("missing move")?;
+                Ok(ServerMessage::Moved { name, move_type: move_from_char(move_text.chars().next().unwrap_or(' '))? })
+            }
+            "score" => Ok(ServerMessage::Score {
+                name: words.next().ok_or("missing name")?.to_string(),
+                score: parse_number("score", words.next().unwrap_or(""))?,
+                moves: parse_number("moves", words.next().unwrap_or(""))?,
+                status: status_from_text(words.next().unwrap_or(""))?,
+            }),
+            "winner" => Ok(ServerMessage::Winner { name: words.next().ok_or("missing name")?.to_string() }),
+            "error" => Ok(ServerMessage::Error(rest.to_string())),
+            _ => Err(format!("invalid message: {line}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_messages_round_trip() {
+        let messages = [
+            ClientMessage::Join { room: "lobby".to_string(), name: "alice".to_string(), token: None },
+            ClientMessage::Join { room: "lobby".to_string(), name: "alice".to_string(), token: Some("0123456789abcdef".to_string()) },
+            ClientMessage::Move(AllowedMoves::UP),
+            ClientMessage::Move(AllowedMoves::RIGHT),
+        ];
+        for message in messages {
+            assert_eq!(ClientMessage::parse(&message.to_line()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn server_messages_round_trip() {
+        let messages = [
+            ServerMessage::Welcome { seed: 42, goal: 2048, token: "0123456789abcdef".to_string(), log: Vec::new() },
+            ServerMessage::Welcome { seed: u64::MAX, goal: 8, token: "t".to_string(), log: vec![AllowedMoves::LEFT, AllowedMoves::DOWN, AllowedMoves::UP, AllowedMoves::RIGHT] },
+            ServerMessage::Moved { name: "bob".to_string(), move_type: AllowedMoves::DOWN },
+            ServerMessage::Score { name: "bob".to_string(), score: 1234, moves: 56, status: GameStatus::END_FAIL },
+            ServerMessage::Winner { name: "alice".to_string() },
+            ServerMessage::Error("the race is over".to_string()),
+        ];
+        for message in messages {
+            assert_eq!(ServerMessage::parse(&message.to_line()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn invalid_lines_are_refused() {
+        assert!(ClientMessage::parse("join lobby").is_err());
+        assert!(ClientMessage::parse("join lobby alice token extra").is_err());
+        assert!(ClientMessage::parse("move X").is_err());
+        assert!(ServerMessage::parse("welcome 42 2048").is_err());
+        assert!(ServerMessage::parse("score bob 1 2 paused").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// State of a race room kept by the relay server: the server replays the moves of every player on
+// its own board, so the scores it broadcasts and the winner it declares cannot be forged
+use crate::game::game_variants::game_variant_1::{AllowedMoves, GameStatus, GameVariant, Goal, SpecificGame, populate_game};
+use crate::game::rand_manager::RandManager;
+use crate::variants::variant;
+use crate::net::protocol::ServerMessage;
+
+pub const RACE_BOARD_SIDE: usize = 4;
+pub const DEFAULT_RACE_GOAL: i32 = 2048;
+
+pub type RaceGame = SpecificGame<RACE_BOARD_SIDE, RACE_BOARD_SIDE>;
+
+// the board of a player after the moves of the log, the same on the clients and on the server
+pub fn race_board(seed: u64, goal: i32, log: &[AllowedMoves]) -> RaceGame {
+    let mut g = match GameVariant::<RACE_BOARD_SIDE, RACE_BOARD_SIDE>::new_game_specific_dim_seeded((RACE_BOARD_SIDE, RACE_BOARD_SIDE), seed) {
+        Ok(g) => g,
+        Err(e) => panic!("{e}"),
+    };
+    g.game_variant_data.goal = Goal::ReachTile(goal);
+    if let Some(def) = variant("classic_2048") {
+        populate_game(&mut g, def.setup);
+    }
+    for move_type in log {
+        g.move_generic(*move_type);
+    }
+    g
+}
+
+#[derive(Clone)]
+pub struct RacePlayer {
+    pub name: String,
+    // secret of the seat, proves a player joining again is the one who took the name
+    pub token: String,
+    pub log: Vec<AllowedMoves>,
+    pub game: RaceGame,
+}
+
+impl RacePlayer {
+
+    pub fn score_message(&self) -> ServerMessage {
+        ServerMessage::Score {
+            name: self.name.clone(),
+            score: self.game.game_variant_data.score,
+            moves: self.game.game_variant_data.moves,
+            status: self.game.game_status,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Room {
+    pub seed: u64,
+    pub goal: i32,
+    pub players: Vec<RacePlayer>,
+    pub winner: Option<String>,
+}
+
+impl Room {
+
+    pub fn new(seed: u64, goal: i32) -> Room {
+        Room { seed, goal, players: Vec::new(), winner: None }
+    }
+
+    // a new name takes a seat and gets its token, a player joining again with the token of its seat
+    // gets its moves log back to rebuild its board
+    pub fn join(&mut self, name: &str, token: Option<&str>) -> Result<ServerMessage, String> {
+        let player = match self.players.iter().position(|p| p.name == name) {
+            Some(idx) if token == Some(self.players[idx].token.as_str()) => &self.players[idx],
+            Some(_) => return Err(format!("the name {name} is taken")),
+            None => {
+                let token = format!("{:016x}", RandManager::entropy_seed());
+                self.players.push(RacePlayer { name: name.to_string(), token, log: Vec::new(), game: race_board(self.seed, self.goal, &[]) });
+                &self.players[self.players.len() - 1]
+            }
+        };
+        Ok(ServerMessage::Welcome { seed: self.seed, goal: self.goal, token: player.token.clone(), log: player.log.clone() })
+    }
+
+    // the scores of the players and the winner if any, sent to a player joining
+    pub fn state_messages(&self) -> Vec<ServerMessage> {
+        let mut messages: Vec<ServerMessage> = self.players.iter().map(|p| p.score_message()).collect();
+        if let Some(name) = &self.winner {
+            messages.push(ServerMessage::Winner { name: name.clone() });
+        }
+        messages
+    }
+
+    // the messages to broadcast to the room
+    pub fn play(&mut self, name: &str, move_type: AllowedMoves) -> Result<Vec<ServerMessage>, String> {
+        if self.winner.is_some() {
+            return Err("the race is over".to_string());
+        }
+        let player = self.players.iter_mut().find(|p| p.name == name).ok_or(format!("unknown player: {name}"))?;
+        if player.game.game_status != GameStatus::INPROGRESS {
+            return Err("your game is over".to_string());
+        }
+        player.log.push(move_type);
+        player.game.move_generic(move_type);
+        let mut messages = vec![ServerMessage::Moved { name: name.to_string(), move_type }, player.score_message()];
+
+        // the first to reach the goal wins, otherwise the best score once every game is over
+        if player.game.game_status == GameStatus::END_SUCCESS {
+            self.winner = Some(name.to_string());
+        } else if self.players.iter().all(|p| p.game.game_status != GameStatus::INPROGRESS) {
+            self.winner = self.players.iter().max_by_key(|p| p.game.game_variant_data.score).map(|p| p.name.clone());
+        }
+        if let Some(winner) = &self.winner {
+            messages.push(ServerMessage::Winner { name: winner.clone() });
+        }
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOVES: [AllowedMoves; 4] = [AllowedMoves::LEFT, AllowedMoves::UP, AllowedMoves::RIGHT, AllowedMoves::DOWN];
+
+    fn welcome_parts(message: ServerMessage) -> (String, Vec<AllowedMoves>) {
+        match message {
+            ServerMessage::Welcome { token, log, .. } => (token, log),
+            other => panic!("not a welcome: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn join_takes_a_seat_with_a_token() {
+        let mut room = Room::new(42, DEFAULT_RACE_GOAL);
+        let (token, log) = welcome_parts(room.join("alice", None).unwrap());
+        assert!(!token.is_empty());
+        assert!(log.is_empty());
+        let (other_token, _) = welcome_parts(room.join("bob", None).unwrap());
+        assert_ne!(token, other_token);
+        assert_eq!(room.players.len(), 2);
+    }
+
+    #[test]
+    fn a_taken_name_needs_its_token() {
+        let mut room = Room::new(42, DEFAULT_RACE_GOAL);
+        let (token, _) = welcome_parts(room.join("alice", None).unwrap());
+        assert!(room.join("alice", None).is_err());
+        assert!(room.join("alice", Some("0000000000000000")).is_err());
+        assert!(room.join("alice", Some(&token)).is_ok());
+        assert_eq!(room.players.len(), 1);
+    }
+
+    #[test]
+    fn reconnect_catches_up_with_the_moves_log() {
+        let mut room = Room::new(42, DEFAULT_RACE_GOAL);
+        let (token, _) = welcome_parts(room.join("alice", None).unwrap());
+        for move_type in MOVES {
+            room.play("alice", move_type).unwrap();
+        }
+        let (_, log) = welcome_parts(room.join("alice", Some(&token)).unwrap());
+        assert_eq!(log, MOVES);
+        // the client rebuilds the very board of the server from the log
+        let rebuilt = race_board(42, DEFAULT_RACE_GOAL, &log);
+        assert_eq!(rebuilt.game_variant_data.array, room.players[0].game.game_variant_data.array);
+        assert_eq!(rebuilt.game_variant_data.score, room.players[0].game.game_variant_data.score);
+    }
+
+    #[test]
+    fn play_broadcasts_the_move_and_the_score() {
+        let mut room = Room::new(42, DEFAULT_RACE_GOAL);
+        room.join("alice", None).unwrap();
+        let messages = room.play("alice", AllowedMoves::LEFT).unwrap();
+        assert_eq!(messages[0], ServerMessage::Moved { name: "alice".to_string(), move_type: AllowedMoves::LEFT });
+        assert_eq!(messages[1], room.players[0].score_message());
+        assert!(room.play("bob", AllowedMoves::LEFT).is_err());
+    }
+
+    #[test]
+    fn the_first_to_reach_the_goal_wins() {
+        let mut room = Room::new(42, 8);
+        room.join("alice", None).unwrap();
+        room.join("bob", None).unwrap();
+        for move_type in MOVES.iter().cycle().take(200) {
+            if room.play("alice", *move_type).unwrap().contains(&ServerMessage::Winner { name: "alice".to_string() }) {
+                break;
+            }
+        }
+        assert_eq!(room.winner.as_deref(), Some("alice"));
+        assert!(room.play("bob", AllowedMoves::LEFT).is_err());
+    }
+}
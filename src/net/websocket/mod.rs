@@ -0,0 +1,229 @@
+// Minimal websocket server side (RFC 6455) for the relay server: the opening handshake and the
+// text frames, which is all the browsers need to talk to it
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+// larger messages are refused, whatever their number of frames, the protocol lines are short
+const MAX_MESSAGE_LENGTH: u64 = 1 << 16;
+// close status of a message too large to process
+pub const CLOSE_MESSAGE_TOO_BIG: u16 = 1009;
+
+// the error of read_text when a message goes over MAX_MESSAGE_LENGTH
+#[derive(Debug)]
+pub struct MessageTooBig;
+
+impl fmt::Display for MessageTooBig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message larger than {MAX_MESSAGE_LENGTH} bytes")
+    }
+}
+
+impl std::error::Error for MessageTooBig {}
+
+pub fn is_message_too_big(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<MessageTooBig>())
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476, 0xC3D2_E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([chunk[4 * i], chunk[4 * i + 1], chunk[4 * i + 2], chunk[4 * i + 3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (hi, x) in h.iter_mut().zip([a, b, c, d, e]) {
+            *hi = hi.wrapping_add(x);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, hi) in h.iter().enumerate() {
+        digest[4 * i..4 * i + 4].copy_from_slice(&hi.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{WEBSOCKET_GUID}", key.trim()).as_bytes()))
+}
+
+// reads the headers of the upgrade request whose request line was already read, and answers it
+pub fn accept<R: BufRead, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
+    let mut key = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            }
+        }
+    }
+    let key = key.ok_or(io::Error::new(io::ErrorKind::InvalidData, "missing Sec-WebSocket-Key"))?;
+    write!(
+        writer,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&key)
+    )?;
+    writer.flush()
+}
+
+// the next text message, None when the client closes the connection; the other frames are skipped
+pub fn read_text<R: Read>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut message = Vec::new();
+    loop {
+        let mut header = [0u8; 2];
+        reader.read_exact(&mut header)?;
+        let fin = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0F;
+        let masked = header[1] & 0x80 != 0;
+        let mut length = u64::from(header[1] & 0x7F);
+        if length == 126 {
+            let mut extended = [0u8; 2];
+            reader.read_exact(&mut extended)?;
+            length = u64::from(u16::from_be_bytes(extended));
+        } else if length == 127 {
+            let mut extended = [0u8; 8];
+            reader.read_exact(&mut extended)?;
+            length = u64::from_be_bytes(extended);
+        }
+        // the continuation frames count with the ones before them
+        if message.len() as u64 + length > MAX_MESSAGE_LENGTH {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, MessageTooBig));
+        }
+        let mut mask = [0u8; 4];
+        if masked {
+            reader.read_exact(&mut mask)?;
+        }
+        let mut payload = vec![0u8; length as usize];
+        reader.read_exact(&mut payload)?;
+        if masked {
+            for (i, byte) in payload.iter_mut().enumerate() {
+                *byte ^= mask[i % 4];
+            }
+        }
+
+        match opcode {
+            OPCODE_CLOSE => return Ok(None),
+            OPCODE_TEXT | OPCODE_CONTINUATION => {
+                message.extend_from_slice(&payload);
+                if fin {
+                    return String::from_utf8(message).map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+                }
+            }
+            // ping, pong and binary frames
+            _ => {}
+        }
+    }
+}
+
+// the status is the whole payload of the close frame
+pub fn write_close<W: Write>(writer: &mut W, status: u16) -> io::Result<()> {
+    let [high, low] = status.to_be_bytes();
+    writer.write_all(&[0x80 | OPCODE_CLOSE, 2, high, low])?;
+    writer.flush()
+}
+
+// the server frames are not masked
+pub fn write_text<W: Write>(writer: &mut W, text: &str) -> io::Result<()> {
+    let payload = text.as_bytes();
+    let mut frame = vec![0x80 | OPCODE_TEXT];
+    match payload.len() {
+        0..=125 => frame.push(payload.len() as u8),
+        126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        }
+        _ => {
+            frame.push(127);
+            frame.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    writer.write_all(&frame)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // an unmasked frame of the client, the length on 2 extra bytes
+    fn frame(fin: bool, opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![if fin { 0x80 | opcode } else { opcode }, 126];
+        bytes.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn continuation_frames_make_one_message() {
+        let mut bytes = frame(false, OPCODE_TEXT, b"move ");
+        bytes.extend(frame(true, OPCODE_CONTINUATION, b"U"));
+        assert_eq!(read_text(&mut bytes.as_slice()).unwrap(), Some("move U".to_string()));
+    }
+
+    #[test]
+    fn continuation_frames_are_capped_together() {
+        let chunk = vec![b'a'; 60_000];
+        let mut bytes = frame(false, OPCODE_TEXT, &chunk);
+        bytes.extend(frame(true, OPCODE_CONTINUATION, &chunk));
+        let e = read_text(&mut bytes.as_slice()).unwrap_err();
+        assert!(is_message_too_big(&e));
+    }
+
+    #[test]
+    fn close_frame_carries_the_status() {
+        let mut bytes = Vec::new();
+        write_close(&mut bytes, CLOSE_MESSAGE_TOO_BIG).unwrap();
+        assert_eq!(bytes, [0x88, 2, 0x03, 0xF1]);
+    }
+}
@@ -0,0 +1,242 @@
+// Replays of the games: the variant page, the settings of the board, the seed and the moves are
+// enough to play a game again exactly, since the same seed spawns the same tiles for the same
+// moves. A replay fits in a link:
+//
+// /shared/<variant>/<rows>x<columns>.<target>.<blocks>.<fixed blocks>.<weights>.<seed>.<moves>
+//
+// the weights are `<value>w<weight>` joined by `_`, the moves are their number then their
+// directions packed 3 by character in base64url (2 bits each, left, right, up, down).
+use crate::game::game_variants::game_variant_1::{max_tile, AllowedMoves, GameSetup, GameStatus, Goal, SpecificElementType, SpecificGame, VariantRules};
+use crate::storage::settings::VariantSettings;
+use crate::text::parse_number;
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const MOVES_BITS: [AllowedMoves; 4] = [AllowedMoves::LEFT, AllowedMoves::RIGHT, AllowedMoves::UP, AllowedMoves::DOWN];
+// the ranks under the target tile, the tiles at the target are stars
+const RANK_EMOJIS: [&str; 6] = ["🟦", "🟩", "🟨", "🟧", "🟥", "🟪"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    // key of the variant page
+    pub variant: String,
+    pub settings: VariantSettings,
+    pub seed: u64,
+    pub moves: Vec<AllowedMoves>,
+}
+
+// `<number>.<packed directions>`, also the moves log of the saves
+pub fn moves_to_text(moves: &[AllowedMoves]) -> String {
+    let packed: String = moves
+        .chunks(3)
+        .map(|chunk| {
+            let bits = chunk.iter().enumerate().fold(0, |bits, (idx, m)| bits | (MOVES_BITS.iter().position(|b| b == m).unwrap_or(0) << (2 * idx)));
+            BASE64URL[bits] as char
+        })
+        .collect();
+    format!("{}.{packed}", moves.len())
+}
+
+pub fn moves_from_text(text: &str) -> Result<Vec<AllowedMoves>, String> {
+    let (count, packed) = text.split_once('.').ok_or(format!("invalid moves: {text}"))?;
+    let count: usize = parse_number("moves", count)?;
+    if packed.len() != count.div_ceil(3) {
+        return Err(format!("the moves shall be {count}"));
+    }
+    let mut moves = Vec::with_capacity(count);
+    for c in packed.bytes() {
+        let bits = BASE64URL.iter().position(|b| *b == c).ok_or(format!("invalid move: {}", c as char))?;
+        for idx in 0..3 {
+            moves.push(MOVES_BITS[(bits >> (2 * idx)) & 3]);
+        }
+    }
+    moves.truncate(count);
+    Ok(moves)
+}
+
+impl Replay {
+
+    pub fn to_code(&self) -> String {
+        let s = &self.settings;
+        let weights = s.spawn_weights.iter().map(|(value, weight)| format!("{value}w{weight}")).collect::<Vec<_>>().join("_");
+        format!("{}x{}.{}.{}.{}.{weights}.{}.{}", s.size.0, s.size.1, s.target, s.blocks, s.blocks_fixed, self.seed, moves_to_text(&self.moves))
+    }
+
+    pub fn from_code(variant: &str, code: &str) -> Result<Replay, String> {
+        let fields: Vec<&str> = code.splitn(7, '.').collect();
+        let [size, target, blocks, blocks_fixed, weights, seed, moves] = fields.as_slice() else {
+            return Err("the replay code is incomplete".to_string());
+        };
+        let (rows, columns) = size.split_once('x').ok_or(format!("invalid size: {size}"))?;
+        let spawn_weights = weights
+            .split('_')
+            .map(|pair| match pair.split_once('w') {
+                Some((value, weight)) => Ok((parse_number("weights", value)?, parse_number("weights", weight)?)),
+                None => Err(format!("invalid weights: {pair}")),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Replay {
+            variant: variant.to_string(),
+            settings: VariantSettings {
+                size: (parse_number("size", rows)?, parse_number("size", columns)?),
+                target: parse_number("target", target)?,
+                spawn_weights,
+                blocks: parse_number("blocks", blocks)?,
+                blocks_fixed: parse_number("fixed blocks", blocks_fixed)?,
+                undos: 0,
+            },
+            seed: parse_number("seed", seed)?,
+            moves: moves_from_text(moves)?,
+        })
+    }
+
+    // a shared link, its path or `<variant>/<code>`
+    pub fn from_link(link: &str) -> Result<Replay, String> {
+        let path = link.rsplit_once("/shared/").map(|(_, path)| path).unwrap_or(link);
+        let (variant, code) = path.trim_end_matches('/').split_once('/').ok_or(format!("invalid replay link: {link}"))?;
+        Replay::from_code(variant, code)
+    }
+
+    // path of the shared route, the page adds its origin
+    pub fn path(&self) -> String {
+        format!("/shared/{}/{}", self.variant, self.to_code())
+    }
+
+    // the start of the game then the game after every move, a move that does not change the board
+    // means the replay is not the one of this variant
+    pub fn states<const C_W: usize, const C_H: usize, R: VariantRules>(&self, setup: GameSetup) -> Result<Vec<SpecificGame<C_W, C_H, R>>, String> {
+        let mut g = self.settings.build_seeded::<C_W, C_H, R>(setup, self.seed)?;
+        let mut states = vec![g.clone()];
+        for (idx, move_type) in self.moves.iter().enumerate() {
+            if g.game_status != GameStatus::INPROGRESS {
+                return Err(format!("the game ends before the move {}", idx + 1));
+            }
+            let moves_before = g.game_variant_data.moves;
+            g.move_generic(*move_type);
+            if g.game_variant_data.moves == moves_before {
+                return Err(format!("the move {} does not change the board", idx + 1));
+            }
+            states.push(g.clone());
+        }
+        Ok(states)
+    }
+}
+
+fn cell_emoji<R: VariantRules>(element: Option<SpecificElementType>, target: i32) -> &'static str {
+    match element {
+        None => "⬜",
+        Some(SpecificElementType::Block(_)) | Some(SpecificElementType::BlockFixed) => "⬛",
+        Some(SpecificElementType::Joker) => "🃏",
+        Some(SpecificElementType::Bomb(_)) => "💣",
+        Some(SpecificElementType::Some(a)) | Some(SpecificElementType::Timed(a, _)) => {
+            if a >= target {
+                "⭐"
+            } else {
+                let rank = R::tile_rank(a) * RANK_EMOJIS.len() / R::tile_rank(target).max(1);
+                RANK_EMOJIS[rank.min(RANK_EMOJIS.len() - 1)]
+            }
+        }
+    }
+}
+
+// one line of squares per row, the colours go up with the tiles towards the target
+pub fn emoji_grid<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>) -> String {
+    let target = match g.game_variant_data.goal {
+        Goal::ReachTile(target) => target,
+        _ => R::TARGET_VALUE,
+    };
+    (0..g.board_size.0)
+        .map(|id_x| (0..g.board_size.1).map(|id_y| cell_emoji::<R>(g.game_variant_data.array[id_x][id_y], target)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// text shared at the end of a game, the link opens its replay
+pub fn share_text<const C_W: usize, const C_H: usize, R: VariantRules>(title: &str, g: &SpecificGame<C_W, C_H, R>, url: &str) -> String {
+    let outcome = match g.game_status {
+        GameStatus::END_SUCCESS => "won",
+        GameStatus::END_FAIL => "lost",
+        GameStatus::INPROGRESS => "unfinished",
+    };
+    format!(
+        "rusty-2048 {title} {}x{}\n{}\nscore {} | max tile {} | {} moves | {outcome}\n{url}",
+        g.board_size.0, g.board_size.1, emoji_grid(g), g.game_variant_data.score, max_tile(g), g.game_variant_data.moves
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::SpawnConfig;
+    use crate::game::levels::Level;
+    use crate::game::merge_rules::ClassicMergeRule;
+
+    const SETUP: GameSetup = GameSetup { start_tiles: 2, blocks_fixed: 1, blocks: 1, spawn: SpawnConfig::NUMBERS_ONLY };
+
+    fn replay(moves: Vec<AllowedMoves>) -> Replay {
+        let settings = VariantSettings { size: (4, 5), target: 1024, spawn_weights: vec![(2, 9), (4, 1)], blocks: 1, blocks_fixed: 1, undos: 0 };
+        Replay { variant: "classic_2048_variant_2".to_string(), settings, seed: 18_446_744_073_709_551_557, moves }
+    }
+
+    #[test]
+    fn replay_code_round_trip() {
+        let moves = vec![AllowedMoves::LEFT, AllowedMoves::DOWN, AllowedMoves::DOWN, AllowedMoves::UP, AllowedMoves::RIGHT, AllowedMoves::LEFT, AllowedMoves::UP];
+        let replay = replay(moves);
+        assert_eq!(Replay::from_code(&replay.variant, &replay.to_code()), Ok(replay.clone()));
+        assert_eq!(Replay::from_link(&format!("https://example.org{}", replay.path())), Ok(replay.clone()));
+        let empty = self::replay(Vec::new());
+        assert_eq!(Replay::from_code(&empty.variant, &empty.to_code()), Ok(empty));
+    }
+
+    #[test]
+    fn damaged_codes_are_errors() {
+        let code = replay(vec![AllowedMoves::UP; 5]).to_code();
+        for end in 0..code.len() - 1 {
+            assert!(Replay::from_code("classic_2048", &code[..end]).is_err(), "{}", &code[..end]);
+        }
+        for garbage in ["", "4x4", "4x4.2048.0.0.2w9.7.1.A.extra", "4y4.2048.0.0.2w9.7.0.", "4x4.2048.0.0.2x9.7.0.", "4x4.2048.0.0.2w9.-7.0.", "4x4.2048.0.0.2w9.7.2.!"] {
+            assert!(Replay::from_code("classic_2048", garbage).is_err(), "{garbage}");
+        }
+        assert!(Replay::from_link("https://example.org/shared/classic_2048").is_err());
+    }
+
+    #[test]
+    fn replay_states_follow_the_live_game() {
+        let settings = replay(Vec::new()).settings;
+        let mut live = settings.build_seeded::<5, 5, ClassicMergeRule>(SETUP, 42).unwrap();
+        let mut moves = Vec::new();
+        for move_type in MOVES_BITS.iter().cycle().take(60) {
+            if live.game_status != GameStatus::INPROGRESS {
+                break;
+            }
+            let moves_before = live.game_variant_data.moves;
+            live.move_generic(*move_type);
+            if live.game_variant_data.moves != moves_before {
+                moves.push(*move_type);
+            }
+        }
+        let replay = Replay { seed: 42, ..replay(moves.clone()) };
+        let states = replay.states::<5, 5, ClassicMergeRule>(SETUP).unwrap();
+        assert_eq!(states.len(), moves.len() + 1);
+        let last = states.last().unwrap();
+        assert_eq!(last.game_variant_data.array, live.game_variant_data.array);
+        assert_eq!((last.game_variant_data.score, last.game_status), (live.game_variant_data.score, live.game_status));
+    }
+
+    #[test]
+    fn a_move_that_changes_nothing_is_an_error() {
+        let replay = replay(vec![AllowedMoves::LEFT; 40]);
+        let error = replay.states::<5, 5, ClassicMergeRule>(SETUP).err().unwrap();
+        assert!(error.ends_with("does not change the board"), "{error}");
+    }
+
+    #[test]
+    fn share_text_of_a_known_board() {
+        let level = Level::parse("size 2x4\ngoal tile 2048\nstart_tiles 0\nboard\n2 . # *\n64 512 2048 @3").unwrap();
+        let g = level.build::<4, 4, ClassicMergeRule>(0).unwrap();
+        assert_eq!(emoji_grid(&g), "🟦⬜⬛🃏\n🟧🟥⭐💣");
+        assert_eq!(
+            share_text("Classic 2048", &g, "https://example.org/shared/classic_2048/x"),
+            "rusty-2048 Classic 2048 2x4\n🟦⬜⬛🃏\n🟧🟥⭐💣\nscore 0 | max tile 2048 | 0 moves | unfinished\nhttps://example.org/shared/classic_2048/x"
+        );
+    }
+}
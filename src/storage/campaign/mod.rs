@@ -0,0 +1,97 @@
+// Progress of the puzzle campaign, the best stars of the completed levels, saved as text lines:
+//
+// level <index> <stars>
+//
+// the index counts from 0 in the bundled pack, a level without line is not completed
+use crate::storage::Storage;
+
+const CAMPAIGN_KEY: &str = "campaign";
+pub const MAX_STARS: u8 = 3;
+
+// best stars per level, None while the level is not completed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CampaignProgress {
+    pub best_stars: Vec<Option<u8>>,
+}
+
+impl CampaignProgress {
+
+    pub fn stars(&self, level: usize) -> Option<u8> {
+        self.best_stars.get(level).copied().flatten()
+    }
+
+    pub fn record(&mut self, level: usize, stars: u8) {
+        if self.best_stars.len() <= level {
+            self.best_stars.resize(level + 1, None);
+        }
+        if self.stars(level).is_none_or(|best| stars > best) {
+            self.best_stars[level] = Some(stars);
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (level, stars) in self.best_stars.iter().enumerate() {
+            if let Some(stars) = stars {
+                text.push_str(&format!("level {level} {stars}\n"));
+            }
+        }
+        text
+    }
+
+    // the invalid lines are skipped, a damaged save only loses them
+    pub fn parse(text: &str) -> CampaignProgress {
+        let mut progress = CampaignProgress::default();
+        for line in text.lines() {
+            if let ["level", level, stars] = line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                match (level.parse(), stars.parse()) {
+                    (Ok(level), Ok(stars)) if (1..=MAX_STARS).contains(&stars) => progress.record(level, stars),
+                    _ => {}
+                }
+            }
+        }
+        progress
+    }
+
+    pub fn load(storage: &dyn Storage) -> CampaignProgress {
+        storage.load(CAMPAIGN_KEY).map(|text| CampaignProgress::parse(&text)).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) -> Result<(), String> {
+        storage.save(CAMPAIGN_KEY, &self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn campaign_progress_round_trip_in_memory() {
+        let mut progress = CampaignProgress::default();
+        progress.record(0, 2);
+        progress.record(3, 1);
+        progress.record(0, 3);
+        progress.record(3, 1);
+        let mut storage = MemoryStorage::default();
+        progress.save(&mut storage).unwrap();
+        let loaded = CampaignProgress::load(&storage);
+        assert_eq!(loaded, progress);
+        assert_eq!((loaded.stars(0), loaded.stars(1), loaded.stars(3), loaded.stars(9)), (Some(3), None, Some(1), None));
+    }
+
+    #[test]
+    fn best_stars_are_kept() {
+        let mut progress = CampaignProgress::default();
+        progress.record(1, 3);
+        progress.record(1, 2);
+        assert_eq!(progress.stars(1), Some(3));
+    }
+
+    #[test]
+    fn damaged_lines_are_skipped() {
+        let progress = CampaignProgress::parse("level 0 2\nlevel 1 7\nlevel x 1\nstars 2\n");
+        assert_eq!(progress.best_stars, vec![Some(2)]);
+    }
+}
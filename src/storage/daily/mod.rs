@@ -0,0 +1,65 @@
+// Attempt at the daily challenge of a day, saved from its first move on so a reload shows the
+// result instead of a new run, as one text line:
+//
+// daily <day> <score> <max tile> <moves> <in_progress|lost|won>
+use crate::game::daily::DailyResult;
+use crate::storage::Storage;
+use crate::text::{parse_number, status_from_text, status_to_text};
+
+pub fn daily_key(day: u64) -> String {
+    format!("daily.{day}")
+}
+
+pub fn result_to_text(result: &DailyResult) -> String {
+    format!(
+        "daily {} {} {} {} {}\n",
+        result.day, result.score, result.max_tile, result.moves, status_to_text(result.status)
+    )
+}
+
+pub fn result_from_text(text: &str) -> Result<DailyResult, String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["daily", day, score, max_tile, moves, status] => Ok(DailyResult {
+            day: parse_number("day", day)?,
+            score: parse_number("score", score)?,
+            max_tile: parse_number("max tile", max_tile)?,
+            moves: parse_number("moves", moves)?,
+            status: status_from_text(status)?,
+        }),
+        _ => Err(format!("invalid daily result: {}", text.trim())),
+    }
+}
+
+pub fn save_daily_result(storage: &mut dyn Storage, result: &DailyResult) -> Result<(), String> {
+    storage.save(&daily_key(result.day), &result_to_text(result))
+}
+
+// None when the day was not played, an error for a result that cannot be read anymore
+pub fn load_daily_result(storage: &dyn Storage, day: u64) -> Option<Result<DailyResult, String>> {
+    storage.load(&daily_key(day)).map(|text| result_from_text(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::GameStatus;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn daily_result_round_trip_in_memory() {
+        let result = DailyResult { day: 20_380, score: 5120, max_tile: 512, moves: 431, status: GameStatus::END_FAIL };
+        let mut storage = MemoryStorage::default();
+        save_daily_result(&mut storage, &result).unwrap();
+        assert_eq!(load_daily_result(&storage, 20_380), Some(Ok(result)));
+        assert_eq!(load_daily_result(&storage, 20_381), None);
+    }
+
+    #[test]
+    fn damaged_result_is_an_error() {
+        let mut storage = MemoryStorage::default();
+        storage.save(&daily_key(3), "daily 3 12 4 2 paused\n").unwrap();
+        assert!(matches!(load_daily_result(&storage, 3), Some(Err(_))));
+        assert!(result_from_text("daily 3 12").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// Key-value storage of the saved data: the browser's localStorage on the web, one file per key
+// on the other platforms, and an in-memory backend for the tools and the tests. The values are
+// plain text so the saves stay readable.
+use std::collections::HashMap;
+
+pub mod campaign;
+pub mod daily;
+pub mod saves;
+pub mod scores;
+pub mod settings;
+pub mod stats;
+
+pub trait Storage {
+    fn load(&self, key: &str) -> Option<String>;
+    fn save(&mut self, key: &str, value: &str) -> Result<(), String>;
+    fn remove(&mut self, key: &str) -> Result<(), String>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    values: HashMap<String, String>,
+}
+
+impl Storage for MemoryStorage {
+
+    fn load(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+
+    fn save(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.values.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), String> {
+        self.values.remove(key);
+        Ok(())
+    }
+}
+
+// the keys are namespaced so the app does not collide with other pages of the same origin
+#[cfg(target_arch = "wasm32")]
+const LOCAL_STORAGE_PREFIX: &str = "rusty_2048.";
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Debug, Default)]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn storage() -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or("the localStorage is not available".to_string())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+
+    fn load(&self, key: &str) -> Option<String> {
+        Self::storage().ok()?.get_item(&format!("{LOCAL_STORAGE_PREFIX}{key}")).ok().flatten()
+    }
+
+    fn save(&mut self, key: &str, value: &str) -> Result<(), String> {
+        Self::storage()?.set_item(&format!("{LOCAL_STORAGE_PREFIX}{key}"), value).map_err(|_| format!("cannot save {key}"))
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), String> {
+        Self::storage()?.remove_item(&format!("{LOCAL_STORAGE_PREFIX}{key}")).map_err(|_| format!("cannot remove {key}"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> FileStorage {
+        FileStorage { dir: dir.into() }
+    }
+
+    // $RUSTY_2048_DATA, or ~/.rusty_2048, or the working directory
+    pub fn default_dir() -> std::path::PathBuf {
+        match (std::env::var_os("RUSTY_2048_DATA"), std::env::var_os("HOME")) {
+            (Some(dir), _) => dir.into(),
+            (None, Some(home)) => std::path::Path::new(&home).join(".rusty_2048"),
+            (None, None) => std::path::PathBuf::from("."),
+        }
+    }
+
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        self.dir.join(format!("{key}.txt"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+
+    fn load(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn save(&mut self, key: &str, value: &str) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        std::fs::write(self.path(key), value).map_err(|e| e.to_string())
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), String> {
+        match std::fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// the backend of the platform
+#[cfg(target_arch = "wasm32")]
+pub fn default_storage() -> Box<dyn Storage> {
+    Box::new(LocalStorage)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_storage() -> Box<dyn Storage> {
+    Box::new(FileStorage::new(FileStorage::default_dir()))
+}
@@ -0,0 +1,175 @@
+// Saved in-progress games, one per variant, written after every move so a game survives a reload
+// or a restart of the app. A save is the moves log of the game, the snapshot of the game and its
+// undo history:
+//
+// save 3
+// undos_left 3
+// log 12.Aw9_
+// ---
+// <snapshot of the game>
+// ---
+// <snapshot of the oldest state that can be restored>
+// ...
+//
+// the version 1 saves were a bare snapshot without undo history, the version 2 had no moves log.
+// The log is the number of moves then their directions packed 3 by character, as in the replay
+// links (`replay::moves_to_text`). It is `-` when not known, the game cannot be replayed then.
+use crate::game::game_variants::game_variant_1::{AllowedMoves, SpecificGame, VariantRules};
+use crate::game::snapshot::{game_from_text, game_to_text};
+use crate::game::undo::UndoHistory;
+use crate::replay::{moves_from_text, moves_to_text};
+use crate::storage::Storage;
+
+pub const SAVE_VERSION: u32 = 3;
+const SECTION_SEPARATOR: &str = "---";
+
+// (game, undo history, moves log)
+pub type SavedGame<const C_W: usize, const C_H: usize, R> = (SpecificGame<C_W, C_H, R>, UndoHistory<SpecificGame<C_W, C_H, R>>, Option<Vec<AllowedMoves>>);
+
+pub fn save_key(variant: &str) -> String {
+    format!("save.{variant}")
+}
+
+pub fn save_to_text<const C_W: usize, const C_H: usize, R: VariantRules>(g: &SpecificGame<C_W, C_H, R>, history: &UndoHistory<SpecificGame<C_W, C_H, R>>, log: Option<&[AllowedMoves]>) -> String {
+    let mut text = format!("save {SAVE_VERSION}\nundos_left {}\n", history.undos_left);
+    text.push_str(&format!("log {}\n", log.map(moves_to_text).unwrap_or("-".to_string())));
+    for state in std::iter::once(g).chain(history.states.iter()) {
+        text.push_str(SECTION_SEPARATOR);
+        text.push('\n');
+        text.push_str(&game_to_text(state));
+    }
+    text
+}
+
+pub fn save_from_text<const C_W: usize, const C_H: usize, R: VariantRules>(text: &str) -> Result<SavedGame<C_W, C_H, R>, String> {
+    let mut sections: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in text.lines() {
+        if line.trim() == SECTION_SEPARATOR {
+            sections.push(Vec::new());
+        } else if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    let header: Vec<&str> = sections.remove(0).into_iter().map(str::trim).filter(|line| !line.is_empty()).collect();
+
+    // a version 1 save is only the snapshot, its game has no undo
+    let Some(version) = header.first().and_then(|line| line.strip_prefix("save ")) else {
+        return Ok((game_from_text(text)?, UndoHistory::new(0), None));
+    };
+    let version: u32 = version.parse().map_err(|_| format!("invalid save version: {version}"))?;
+    if version > SAVE_VERSION {
+        return Err(format!("the save comes from a newer version ({version})"));
+    }
+    let undos_left = match header.get(1).and_then(|line| line.strip_prefix("undos_left ")) {
+        Some(undos_left) => undos_left.parse().map_err(|_| format!("invalid undos_left: {undos_left}"))?,
+        None => return Err("missing undos_left".to_string()),
+    };
+    let log = match header.iter().find_map(|line| line.strip_prefix("log ")) {
+        None | Some("-") => None,
+        Some(log) => Some(moves_from_text(log)?),
+    };
+    let mut states = sections.into_iter().map(|section| game_from_text(&section.join("\n"))).collect::<Result<Vec<_>, String>>()?;
+    if states.is_empty() {
+        return Err("the save has no game".to_string());
+    }
+    let g = states.remove(0);
+    Ok((g, UndoHistory { states, undos_left }, log))
+}
+
+pub fn save_game<const C_W: usize, const C_H: usize, R: VariantRules>(storage: &mut dyn Storage, variant: &str, g: &SpecificGame<C_W, C_H, R>, history: &UndoHistory<SpecificGame<C_W, C_H, R>>, log: Option<&[AllowedMoves]>) -> Result<(), String> {
+    storage.save(&save_key(variant), &save_to_text(g, history, log))
+}
+
+// None without a save, an error for a save that cannot be read anymore
+pub fn load_game<const C_W: usize, const C_H: usize, R: VariantRules>(storage: &dyn Storage, variant: &str) -> Option<Result<SavedGame<C_W, C_H, R>, String>> {
+    storage.load(&save_key(variant)).map(|text| save_from_text(&text))
+}
+
+pub fn remove_game(storage: &mut dyn Storage, variant: &str) -> Result<(), String> {
+    storage.remove(&save_key(variant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::{populate_game, GameSetup, GameVariant, SpawnConfig};
+    use crate::game::merge_rules::ClassicMergeRule;
+    use crate::game::snapshot::SNAPSHOT_VERSION;
+    use crate::storage::MemoryStorage;
+
+    type Game = SpecificGame<4, 4>;
+
+    const SETUP: GameSetup = GameSetup { start_tiles: 2, blocks_fixed: 0, blocks: 1, spawn: SpawnConfig::NUMBERS_ONLY };
+    const MOVES: [AllowedMoves; 4] = [AllowedMoves::LEFT, AllowedMoves::UP, AllowedMoves::RIGHT, AllowedMoves::DOWN];
+
+    // a game after the moves, with the states before them in the history
+    fn played_game() -> (Game, UndoHistory<Game>) {
+        let mut g = GameVariant::<4, 4>::new_game_specific_dim_seeded((4, 4), 7).unwrap();
+        populate_game(&mut g, SETUP);
+        let mut history = UndoHistory::new(2);
+        for move_type in MOVES {
+            history.record(g.clone());
+            g.move_generic(move_type);
+        }
+        (g, history)
+    }
+
+    #[test]
+    fn save_round_trip_in_memory() {
+        let (g, history) = played_game();
+        let mut storage = MemoryStorage::default();
+        save_game(&mut storage, "classic_2048", &g, &history, Some(&MOVES)).unwrap();
+        let (loaded, loaded_history, log) = load_game::<4, 4, ClassicMergeRule>(&storage, "classic_2048").unwrap().unwrap();
+        assert_eq!(game_to_text(&loaded), game_to_text(&g));
+        assert_eq!(loaded_history.undos_left, 2);
+        assert_eq!(loaded_history.states.iter().map(game_to_text).collect::<Vec<_>>(), history.states.iter().map(game_to_text).collect::<Vec<_>>());
+        assert_eq!(log.as_deref(), Some(&MOVES[..]));
+
+        remove_game(&mut storage, "classic_2048").unwrap();
+        assert!(load_game::<4, 4, ClassicMergeRule>(&storage, "classic_2048").is_none());
+    }
+
+    #[test]
+    fn version_1_save_is_a_bare_snapshot() {
+        let (g, _) = played_game();
+        let (loaded, history, log) = save_from_text::<4, 4, ClassicMergeRule>(&game_to_text(&g)).unwrap();
+        assert_eq!(game_to_text(&loaded), game_to_text(&g));
+        assert_eq!(history.undos_left, 0);
+        assert!(log.is_none());
+    }
+
+    #[test]
+    fn version_2_save_has_no_log() {
+        let (g, _) = played_game();
+        let text = format!("save 2\nundos_left 3\n---\n{}", game_to_text(&g));
+        let (loaded, history, log) = save_from_text::<4, 4, ClassicMergeRule>(&text).unwrap();
+        assert_eq!(game_to_text(&loaded), game_to_text(&g));
+        assert_eq!(history.undos_left, 3);
+        assert!(history.states.is_empty());
+        assert!(log.is_none());
+    }
+
+    #[test]
+    fn version_1_snapshot_gets_the_weights_of_the_rules() {
+        let (g, _) = played_game();
+        let text: String = game_to_text(&g)
+            .lines()
+            .filter(|line| !line.starts_with("weights ") && !line.starts_with("seed "))
+            .map(|line| if line.starts_with("version ") { "version 1\n".to_string() } else { format!("{line}\n") })
+            .collect();
+        let (loaded, _, _) = save_from_text::<4, 4, ClassicMergeRule>(&text).unwrap();
+        assert_eq!(loaded.game_variant_data.spawn_weights, ClassicMergeRule::SPAWN_WEIGHTS.to_vec());
+        assert_eq!(loaded.game_variant_data.seed, None);
+        assert_eq!(loaded.game_variant_data.score, g.game_variant_data.score);
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let (g, history) = played_game();
+        let text = save_to_text(&g, &history, None);
+        let newer_save = text.replacen(&format!("save {SAVE_VERSION}"), &format!("save {}", SAVE_VERSION + 1), 1);
+        assert!(save_from_text::<4, 4, ClassicMergeRule>(&newer_save).is_err());
+        let newer_snapshot = text.replace(&format!("version {SNAPSHOT_VERSION}"), &format!("version {}", SNAPSHOT_VERSION + 1));
+        assert!(save_from_text::<4, 4, ClassicMergeRule>(&newer_snapshot).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+// Best score of every variant and board size, and the history of the finished games, saved as
+// text lines:
+//
+// best <variant> <rows>x<columns> <score>
+// game <variant> <rows>x<columns> <date> <score> <max tile> <moves> <duration> <won|lost>
+//
+// the date is in milliseconds since 1970-01-01 and the duration in milliseconds
+use std::collections::BTreeMap;
+
+use crate::storage::Storage;
+use crate::text::parse_size;
+
+const SCORES_KEY: &str = "scores";
+// the oldest games are forgotten beyond this number
+pub const MAX_HISTORY: usize = 500;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinishedGame {
+    pub variant: String,
+    // (rows, columns)
+    pub size: (usize, usize),
+    pub date: u64,
+    pub score: i32,
+    pub max_tile: i32,
+    pub moves: usize,
+    pub duration: u64,
+    pub won: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScoreBook {
+    // (variant, size) -> best score
+    pub best: BTreeMap<(String, (usize, usize)), i32>,
+    // oldest first
+    pub history: Vec<FinishedGame>,
+}
+
+fn parse_game(words: &[&str]) -> Option<FinishedGame> {
+    match words {
+        [variant, size, date, score, max_tile, moves, duration, outcome] => Some(FinishedGame {
+            variant: variant.to_string(),
+            size: parse_size(size).ok()?,
+            date: date.parse().ok()?,
+            score: score.parse().ok()?,
+            max_tile: max_tile.parse().ok()?,
+            moves: moves.parse().ok()?,
+            duration: duration.parse().ok()?,
+            won: *outcome == "won",
+        }),
+        _ => None,
+    }
+}
+
+impl ScoreBook {
+
+    pub fn best(&self, variant: &str, size: (usize, usize)) -> Option<i32> {
+        self.best.get(&(variant.to_string(), size)).copied()
+    }
+
+    pub fn record(&mut self, game: FinishedGame) {
+        let best = self.best.entry((game.variant.clone(), game.size)).or_insert(game.score);
+        *best = (*best).max(game.score);
+        self.history.push(game);
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for ((variant, size), score) in &self.best {
+            text.push_str(&format!("best {variant} {}x{} {score}\n", size.0, size.1));
+        }
+        for g in &self.history {
+            text.push_str(&format!(
+                "game {} {}x{} {} {} {} {} {} {}\n",
+                g.variant, g.size.0, g.size.1, g.date, g.score, g.max_tile, g.moves, g.duration, if g.won { "won" } else { "lost" }
+            ));
+        }
+        text
+    }
+
+    // the invalid lines are skipped, a damaged save only loses them
+    pub fn parse(text: &str) -> ScoreBook {
+        let mut book = ScoreBook::default();
+        for line in text.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["best", variant, size, score] => {
+                    if let (Some(size), Ok(score)) = (parse_size(size).ok(), score.parse()) {
+                        book.best.insert((variant.to_string(), size), score);
+                    }
+                }
+                ["game", rest @ ..] => {
+                    if let Some(g) = parse_game(rest) {
+                        book.history.push(g);
+                    }
+                }
+                _ => {}
+            }
+        }
+        book
+    }
+
+    pub fn load(storage: &dyn Storage) -> ScoreBook {
+        storage.load(SCORES_KEY).map(|text| ScoreBook::parse(&text)).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) -> Result<(), String> {
+        storage.save(SCORES_KEY, &self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn finished(variant: &str, score: i32, won: bool) -> FinishedGame {
+        FinishedGame { variant: variant.to_string(), size: (4, 4), date: 1_700_000_000_000, score, max_tile: 256, moves: 321, duration: 90_000, won }
+    }
+
+    #[test]
+    fn score_book_round_trip_in_memory() {
+        let mut book = ScoreBook::default();
+        book.record(finished("classic_2048", 1200, false));
+        book.record(finished("classic_2048", 800, false));
+        book.record(finished("threes", 3000, true));
+        let mut storage = MemoryStorage::default();
+        book.save(&mut storage).unwrap();
+        let loaded = ScoreBook::load(&storage);
+        assert_eq!(loaded, book);
+        assert_eq!(loaded.best("classic_2048", (4, 4)), Some(1200));
+        assert_eq!(loaded.best("classic_2048", (5, 5)), None);
+    }
+
+    #[test]
+    fn nothing_saved_is_an_empty_book() {
+        assert_eq!(ScoreBook::load(&MemoryStorage::default()), ScoreBook::default());
+    }
+
+    #[test]
+    fn damaged_lines_are_skipped() {
+        let book = ScoreBook::parse("best classic_2048 4x4 1200\nbest threes 4x? 10\ngame threes 4x4 oops\n");
+        assert_eq!(book.best.len(), 1);
+        assert!(book.history.is_empty());
+    }
+
+    #[test]
+    fn history_forgets_the_oldest_games() {
+        let mut book = ScoreBook::default();
+        for score in 0..MAX_HISTORY as i32 + 3 {
+            book.record(finished("classic_2048", score, false));
+        }
+        assert_eq!(book.history.len(), MAX_HISTORY);
+        assert_eq!(book.history[0].score, 3);
+    }
+}
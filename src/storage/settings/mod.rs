@@ -0,0 +1,237 @@
+// Settings of the variant pages chosen by the player, saved as text lines:
+//
+// variant <variant> <rows>x<columns> <target tile> <blocks> <fixed blocks> <undos> <value:weight>...
+// reduced_motion <on|off>
+// theme <name>
+// binding <key> <action>
+// swipe_length <percent of the board width>
+//
+// a variant without settings is played with the defaults of its page, without binding lines the
+// keys are the arrows preset
+use std::collections::BTreeMap;
+
+use crate::game::game_variants::game_variant_1::{GameSetup, GameVariant, Goal, SpecificGame, VariantRules, populate_game, set_spawn_weights};
+use crate::game::rand_manager::RandManager;
+use crate::game::snapshot::{weights_from_text, weights_to_text};
+use crate::input::{Bindings, DEFAULT_SWIPE_PERCENT, MAX_SWIPE_PERCENT};
+use crate::storage::Storage;
+use crate::text::parse_size;
+
+const SETTINGS_KEY: &str = "settings";
+// capacity of the boards built from the settings
+pub const SETTINGS_CAPACITY: usize = 8;
+pub const MIN_SIDE: usize = 2;
+pub const MAX_UNDOS: usize = 99;
+// every spawn weight, the total of the weights is drawn from
+pub const MAX_SPAWN_WEIGHT: u32 = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariantSettings {
+    // (rows, columns)
+    pub size: (usize, usize),
+    pub target: i32,
+    pub spawn_weights: Vec<(i32, u32)>,
+    pub blocks: usize,
+    pub blocks_fixed: usize,
+    // undos allowed in a game, 0 hides the undo
+    pub undos: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub variants: BTreeMap<String, VariantSettings>,
+    // the tiles do not slide, pop nor fade in
+    pub reduced_motion: bool,
+    // name of the board theme, the classic one when empty or unknown
+    pub theme: String,
+    pub bindings: Bindings,
+    // minimal length of the swipes and the drags, in percent of the board width
+    pub swipe_percent: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            variants: BTreeMap::new(),
+            reduced_motion: false,
+            theme: String::new(),
+            bindings: Bindings::default(),
+            swipe_percent: DEFAULT_SWIPE_PERCENT,
+        }
+    }
+}
+
+impl VariantSettings {
+
+    // the board of the variant, the goal and the spawns of the rules, the obstacles of the setup
+    pub fn defaults<R: VariantRules>(size: (usize, usize), setup: GameSetup) -> VariantSettings {
+        VariantSettings {
+            size,
+            target: R::TARGET_VALUE,
+            spawn_weights: R::SPAWN_WEIGHTS.to_vec(),
+            blocks: setup.blocks,
+            blocks_fixed: setup.blocks_fixed,
+            undos: 0,
+        }
+    }
+
+    // the start tiles of the setup shall fit next to the obstacles
+    pub fn validate(&self, setup: GameSetup) -> Result<(), String> {
+        let (rows, columns) = self.size;
+        if !(MIN_SIDE..=SETTINGS_CAPACITY).contains(&rows) || !(MIN_SIDE..=SETTINGS_CAPACITY).contains(&columns) {
+            return Err(format!("the sides shall be between {MIN_SIDE} and {SETTINGS_CAPACITY}"));
+        }
+        if self.target <= 0 {
+            return Err("the target tile shall be positive".to_string());
+        }
+        if self.spawn_weights.iter().all(|(_, weight)| *weight == 0) {
+            return Err("at least one spawned value shall have a weight".to_string());
+        }
+        if self.spawn_weights.iter().any(|(_, weight)| *weight > MAX_SPAWN_WEIGHT) {
+            return Err(format!("the spawn weights shall be at most {MAX_SPAWN_WEIGHT}"));
+        }
+        if self.spawn_weights.iter().try_fold(0u32, |total, (_, weight)| total.checked_add(*weight)).is_none() {
+            return Err("the spawn weights are too many".to_string());
+        }
+        if self.blocks + self.blocks_fixed + setup.start_tiles >= rows * columns {
+            return Err("the obstacles leave no room to play".to_string());
+        }
+        if self.undos > MAX_UNDOS {
+            return Err(format!("at most {MAX_UNDOS} undos"));
+        }
+        Ok(())
+    }
+
+    pub fn build<const C_W: usize, const C_H: usize, R: VariantRules>(&self, setup: GameSetup) -> Result<SpecificGame<C_W, C_H, R>, String> {
+        self.build_seeded(setup, RandManager::entropy_seed())
+    }
+
+    // the same settings, setup and seed place the same obstacles and start tiles
+    pub fn build_seeded<const C_W: usize, const C_H: usize, R: VariantRules>(&self, setup: GameSetup, seed: u64) -> Result<SpecificGame<C_W, C_H, R>, String> {
+        self.validate(setup)?;
+        let mut g = GameVariant::<C_W, C_H, R>::new_game_specific_dim_seeded(self.size, seed)?;
+        g.game_variant_data.goal = Goal::ReachTile(self.target);
+        set_spawn_weights(&mut g, self.spawn_weights.clone());
+        populate_game(&mut g, GameSetup { blocks: self.blocks, blocks_fixed: self.blocks_fixed, ..setup });
+        Ok(g)
+    }
+}
+
+impl Settings {
+
+    pub fn variant(&self, variant: &str) -> Option<&VariantSettings> {
+        self.variants.get(variant)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("reduced_motion {}\n", if self.reduced_motion { "on" } else { "off" });
+        text.push_str(&format!("swipe_length {}\n", self.swipe_percent));
+        if !self.theme.is_empty() {
+            text.push_str(&format!("theme {}\n", self.theme));
+        }
+        for (variant, s) in &self.variants {
+            text.push_str(&format!(
+                "variant {variant} {}x{} {} {} {} {} {}\n",
+                s.size.0, s.size.1, s.target, s.blocks, s.blocks_fixed, s.undos, weights_to_text(&s.spawn_weights)
+            ));
+        }
+        text.push_str(&self.bindings.to_text());
+        text
+    }
+
+    // the invalid lines are skipped, their variants go back to the defaults
+    pub fn parse(text: &str) -> Settings {
+        let mut settings = Settings::default();
+        let mut bindings = Vec::new();
+        for line in text.lines() {
+            if let Some(binding) = Bindings::parse_line(line) {
+                bindings.push(binding);
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["variant", variant, size, target, blocks, blocks_fixed, undos, weights @ ..] => {
+                    let parsed = (parse_size(size).ok(), target.parse(), blocks.parse(), blocks_fixed.parse(), undos.parse(), weights_from_text(&weights.join(" ")));
+                    if let (Some(size), Ok(target), Ok(blocks), Ok(blocks_fixed), Ok(undos), Ok(spawn_weights)) = parsed {
+                        settings.variants.insert(variant.to_string(), VariantSettings { size, target, spawn_weights, blocks, blocks_fixed, undos });
+                    }
+                }
+                ["reduced_motion", value] => settings.reduced_motion = *value == "on",
+                ["theme", name] => settings.theme = name.to_string(),
+                ["swipe_length", percent] => {
+                    if let Ok(percent @ 1..=MAX_SWIPE_PERCENT) = percent.parse() {
+                        settings.swipe_percent = percent;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !bindings.is_empty() {
+            settings.bindings = Bindings { keys: bindings };
+        }
+        settings
+    }
+
+    pub fn load(storage: &dyn Storage) -> Settings {
+        storage.load(SETTINGS_KEY).map(|text| Settings::parse(&text)).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) -> Result<(), String> {
+        storage.save(SETTINGS_KEY, &self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::{AllowedMoves, SpawnConfig};
+    use crate::game::merge_rules::ClassicMergeRule;
+    use crate::input::GameAction;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn settings_round_trip_in_memory() {
+        let mut settings = Settings { reduced_motion: true, theme: "dark".to_string(), swipe_percent: 25, ..Settings::default() };
+        let mut variant = VariantSettings::defaults::<ClassicMergeRule>((5, 6), GameSetup { start_tiles: 1, blocks_fixed: 1, blocks: 2, spawn: SpawnConfig::NUMBERS_ONLY });
+        variant.undos = 3;
+        variant.spawn_weights = vec![(2, 8), (4, 2)];
+        settings.variants.insert("classic_2048_variant_3".to_string(), variant);
+        settings.bindings = Bindings::preset("vim").unwrap();
+        settings.bindings.bind("x", GameAction::Restart);
+
+        let mut storage = MemoryStorage::default();
+        settings.save(&mut storage).unwrap();
+        assert_eq!(Settings::load(&storage), settings);
+    }
+
+    #[test]
+    fn huge_spawn_weights_are_refused() {
+        let setup = GameSetup { start_tiles: 2, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY };
+        let mut variant = VariantSettings::defaults::<ClassicMergeRule>((4, 4), setup);
+        variant.spawn_weights = vec![(2, 3_000_000_000), (4, 3_000_000_000)];
+        assert!(variant.validate(setup).is_err());
+        assert!(variant.build_seeded::<4, 4, ClassicMergeRule>(setup, 1).is_err());
+
+        variant.spawn_weights = vec![(2, MAX_SPAWN_WEIGHT), (4, MAX_SPAWN_WEIGHT)];
+        let mut g = variant.build_seeded::<4, 4, ClassicMergeRule>(setup, 1).unwrap();
+        for move_type in [AllowedMoves::LEFT, AllowedMoves::UP, AllowedMoves::RIGHT, AllowedMoves::DOWN] {
+            g.move_generic(move_type);
+        }
+        assert!(g.game_variant_data.moves > 0);
+    }
+
+    #[test]
+    fn nothing_saved_is_the_defaults() {
+        let settings = Settings::load(&MemoryStorage::default());
+        assert_eq!(settings, Settings::default());
+        assert_eq!(settings.bindings.preset_name(), Some("arrows"));
+    }
+
+    #[test]
+    fn invalid_lines_fall_back_to_the_defaults() {
+        let settings = Settings::parse("variant classic_2048 4xfour 2048 0 0 0 2:9\nswipe_length 90\nbinding q jump\n");
+        assert!(settings.variants.is_empty());
+        assert_eq!(settings.swipe_percent, DEFAULT_SWIPE_PERCENT);
+        assert_eq!(settings.bindings, Bindings::default());
+    }
+}
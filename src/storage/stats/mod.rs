@@ -0,0 +1,117 @@
+// Statistics of the finished games of the history, by variant: the games played, the wins, the
+// scores, the max tiles reached and the longest win streak. The stats page draws them.
+use std::collections::BTreeMap;
+
+use crate::storage::scores::FinishedGame;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VariantStats {
+    pub variant: String,
+    pub games: usize,
+    pub wins: usize,
+    pub best_score: i32,
+    pub total_score: i64,
+    // max tile reached -> games
+    pub max_tiles: BTreeMap<i32, usize>,
+    // most wins in a row
+    pub longest_streak: usize,
+    // (date, score) of every game, oldest first
+    pub scores: Vec<(u64, i32)>,
+}
+
+impl VariantStats {
+
+    // in percent, 0 without games
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 { 0.0 } else { self.wins as f64 * 100.0 / self.games as f64 }
+    }
+
+    pub fn average_score(&self) -> f64 {
+        if self.games == 0 { 0.0 } else { self.total_score as f64 / self.games as f64 }
+    }
+}
+
+// the history is oldest first, so are the scores and the streaks
+pub fn variant_stats(history: &[FinishedGame], variant: &str) -> VariantStats {
+    let mut stats = VariantStats { variant: variant.to_string(), ..VariantStats::default() };
+    let mut streak = 0;
+    for g in history.iter().filter(|g| g.variant == variant) {
+        stats.games += 1;
+        stats.best_score = stats.best_score.max(g.score);
+        stats.total_score += g.score as i64;
+        *stats.max_tiles.entry(g.max_tile).or_insert(0) += 1;
+        stats.scores.push((g.date, g.score));
+        if g.won {
+            stats.wins += 1;
+            streak += 1;
+            stats.longest_streak = stats.longest_streak.max(streak);
+        } else {
+            streak = 0;
+        }
+    }
+    stats
+}
+
+// stats of the given variants played in the history, in their order: the other modes (campaign,
+// daily challenge) are left out of the comparison
+pub fn all_stats(history: &[FinishedGame], variants: &[&str]) -> Vec<VariantStats> {
+    variants
+        .iter()
+        .filter(|variant| history.iter().any(|g| g.variant == **variant))
+        .map(|variant| variant_stats(history, variant))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(variant: &str, date: u64, score: i32, max_tile: i32, won: bool) -> FinishedGame {
+        FinishedGame { variant: variant.to_string(), size: (4, 4), date, score, max_tile, moves: 100, duration: 60_000, won }
+    }
+
+    fn history() -> Vec<FinishedGame> {
+        vec![
+            finished("classic_2048", 1, 3000, 256, false),
+            finished("classic_2048", 2, 21000, 2048, true),
+            finished("bombs_2048", 3, 500, 64, false),
+            finished("classic_2048", 4, 22000, 2048, true),
+            finished("classic_2048", 5, 1000, 128, false),
+            finished("classic_2048", 6, 25000, 2048, true),
+            finished("campaign_0", 7, 900, 128, true),
+        ]
+    }
+
+    #[test]
+    fn variant_stats_of_the_history() {
+        let stats = variant_stats(&history(), "classic_2048");
+        assert_eq!((stats.games, stats.wins, stats.best_score, stats.total_score), (5, 3, 25000, 72000));
+        assert_eq!(stats.win_rate(), 60.0);
+        assert_eq!(stats.average_score(), 14400.0);
+        assert_eq!(stats.max_tiles, BTreeMap::from([(128, 1), (256, 1), (2048, 3)]));
+        assert_eq!(stats.scores, vec![(1, 3000), (2, 21000), (4, 22000), (5, 1000), (6, 25000)]);
+    }
+
+    #[test]
+    fn a_loss_breaks_the_streak() {
+        let stats = variant_stats(&history(), "classic_2048");
+        assert_eq!(stats.longest_streak, 2);
+        let mut history = history();
+        history.push(finished("classic_2048", 8, 24000, 2048, true));
+        history.push(finished("classic_2048", 9, 26000, 4096, true));
+        assert_eq!(variant_stats(&history, "classic_2048").longest_streak, 3);
+    }
+
+    #[test]
+    fn no_games_no_rates() {
+        let stats = variant_stats(&history(), "fibonacci_2048");
+        assert_eq!((stats.games, stats.win_rate(), stats.average_score()), (0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn only_the_given_variants_are_compared() {
+        let stats = all_stats(&history(), &["classic_2048", "fibonacci_2048", "bombs_2048"]);
+        let variants: Vec<&str> = stats.iter().map(|s| s.variant.as_str()).collect();
+        assert_eq!(variants, vec!["classic_2048", "bombs_2048"]);
+    }
+}
@@ -0,0 +1,33 @@
+// Helpers shared by the text formats (snapshots, saves, levels, replays, settings, scores, daily
+// results and the multiplayer protocol) and by the options of the command line tools
+use std::str::FromStr;
+
+use crate::game::game_variants::game_variant_1::GameStatus;
+
+// the key names the value in the error, a field of a format or an option of a tool
+pub fn parse_number<N: FromStr>(key: &str, value: &str) -> Result<N, String> {
+    value.parse().map_err(|_| format!("invalid {key}: {value}"))
+}
+
+// `<rows>x<columns>`
+pub fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let (rows, columns) = value.split_once('x').ok_or(format!("invalid size: {value}"))?;
+    Ok((parse_number("size", rows)?, parse_number("size", columns)?))
+}
+
+pub fn status_to_text(status: GameStatus) -> &'static str {
+    match status {
+        GameStatus::INPROGRESS => "in_progress",
+        GameStatus::END_FAIL => "lost",
+        GameStatus::END_SUCCESS => "won",
+    }
+}
+
+pub fn status_from_text(text: &str) -> Result<GameStatus, String> {
+    match text {
+        "in_progress" => Ok(GameStatus::INPROGRESS),
+        "lost" => Ok(GameStatus::END_FAIL),
+        "won" => Ok(GameStatus::END_SUCCESS),
+        _ => Err(format!("invalid status: {text}")),
+    }
+}
@@ -0,0 +1,253 @@
+// Themes of the boards: the colours of the tiles by their rank in the merge sequence, the board
+// and the fonts, and the textures of the obstacles. The first ranks have listed colours, the
+// higher ones follow a hue scale so any tile value gets a colour.
+use crate::game::game_variants::game_variant_1::{SpecificElementType, VariantRules};
+
+// colours of the ranks past the listed ones: the hue turns by a step at every rank
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HueScale {
+    pub start: u32,
+    pub step: u32,
+    pub saturation: u32,
+    pub lightness: u32,
+    pub text: &'static str,
+}
+
+// an 8x8 texture, one digit per cell giving its colour in the palette, the rows are separated
+// by `/`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Texture {
+    pub palette: [&'static str; 3],
+    pub pattern: &'static str,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    // saved in the settings
+    pub name: &'static str,
+    pub label: &'static str,
+    pub board: &'static str,
+    pub empty: &'static str,
+    // (background, text) of the first ranks
+    pub tiles: &'static [(&'static str, &'static str)],
+    pub high_tiles: HueScale,
+    pub joker: (&'static str, &'static str),
+    pub bomb: (&'static str, &'static str),
+    // css border of the tiles, empty for none
+    pub outline: &'static str,
+    pub font: &'static str,
+    pub block: Texture,
+    pub block_fixed: Texture,
+}
+
+const BRICKS_PALETTE: [&str; 3] = ["#b5523b", "#c05a42", "#a44734"];
+// the moving blocks have a smooth bottom
+const BLOCK_PATTERN: &str = "00010201/20122000/01110102/20100010/02220201/20100001/00222200/00000000";
+const BLOCK_FIXED_PATTERN: &str = "00010201/20122000/01110102/20100010/02220201/20100001/00222201/10202222";
+
+pub const CLASSIC: Theme = Theme {
+    name: "classic",
+    label: "Classic",
+    board: "#bbada0",
+    empty: "#cdc1b4",
+    tiles: &[
+        ("#cdc1b4", "#776e65"),
+        ("#eee4da", "#776e65"),
+        ("#ede0c8", "#776e65"),
+        ("#f2b179", "#ffffff"),
+        ("#f59563", "#ffffff"),
+        ("#f67c5f", "#ffffff"),
+        ("#f65e3b", "#ffffff"),
+        ("#edcf72", "#ffffff"),
+        ("#edcc61", "#ffffff"),
+        ("#edc850", "#ffffff"),
+        ("#edc22e", "#ffffff"),
+    ],
+    high_tiles: HueScale { start: 280, step: 35, saturation: 45, lightness: 35, text: "#ffffff" },
+    joker: ("#9b6fc4", "#ffffff"),
+    bomb: ("#3c3a32", "#ffffff"),
+    outline: "",
+    font: "inherit",
+    block: Texture { palette: BRICKS_PALETTE, pattern: BLOCK_PATTERN },
+    block_fixed: Texture { palette: BRICKS_PALETTE, pattern: BLOCK_FIXED_PATTERN },
+};
+
+pub const DARK: Theme = Theme {
+    name: "dark",
+    label: "Dark",
+    board: "#2b2d31",
+    empty: "#3a3d42",
+    tiles: &[
+        ("#4a4e69", "#e0e0e0"),
+        ("#5c5f7f", "#f0f0f0"),
+        ("#22577a", "#ffffff"),
+        ("#38a3a5", "#ffffff"),
+        ("#57cc99", "#1b1b1b"),
+        ("#80ed99", "#1b1b1b"),
+        ("#c7f9cc", "#1b1b1b"),
+        ("#ffb703", "#1b1b1b"),
+        ("#fb8500", "#ffffff"),
+        ("#e63946", "#ffffff"),
+        ("#ffd60a", "#1b1b1b"),
+    ],
+    high_tiles: HueScale { start: 200, step: 40, saturation: 60, lightness: 45, text: "#ffffff" },
+    joker: ("#9d4edd", "#ffffff"),
+    bomb: ("#111111", "#ffffff"),
+    outline: "",
+    font: "ui-monospace, SFMono-Regular, Menlo, monospace",
+    block: Texture { palette: ["#5a5f66", "#6b7078", "#4a4e54"], pattern: BLOCK_PATTERN },
+    block_fixed: Texture { palette: ["#3d4046", "#4a4e54", "#2f3237"], pattern: BLOCK_FIXED_PATTERN },
+};
+
+// the Okabe-Ito colours stay apart for the colour-blind players, the tiles are outlined
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high_contrast",
+    label: "High contrast",
+    board: "#000000",
+    empty: "#2f2f2f",
+    tiles: &[
+        ("#ffffff", "#000000"),
+        ("#f0e442", "#000000"),
+        ("#e69f00", "#000000"),
+        ("#56b4e9", "#000000"),
+        ("#009e73", "#ffffff"),
+        ("#0072b2", "#ffffff"),
+        ("#d55e00", "#ffffff"),
+        ("#cc79a7", "#000000"),
+    ],
+    high_tiles: HueScale { start: 0, step: 55, saturation: 100, lightness: 25, text: "#ffffff" },
+    joker: ("#ffffff", "#0072b2"),
+    bomb: ("#ffffff", "#d55e00"),
+    outline: "3px solid #ffffff",
+    font: "Verdana, Arial, sans-serif",
+    // stripes instead of bricks
+    block: Texture { palette: ["#000000", "#f0e442", "#000000"], pattern: "11001100/01100110/00110011/10011001/11001100/01100110/00110011/10011001" },
+    block_fixed: Texture { palette: ["#ffffff", "#000000", "#ffffff"], pattern: "10101010/01010101/10101010/01010101/10101010/01010101/10101010/01010101" },
+};
+
+pub const THEMES: [Theme; 3] = [CLASSIC, DARK, HIGH_CONTRAST];
+
+// the classic theme for an unknown name
+pub fn theme(name: &str) -> Theme {
+    THEMES.iter().find(|t| t.name == name).copied().unwrap_or(CLASSIC)
+}
+
+// layout of a cell, the numbers get smaller with their digits
+pub fn tile_class(element: Option<SpecificElementType>) -> &'static str {
+    match element {
+        Some(SpecificElementType::Block(_)) | Some(SpecificElementType::BlockFixed) => "aspect-square rounded grid grid-cols-8 grid-rows-8 overflow-hidden",
+        Some(SpecificElementType::Some(a)) | Some(SpecificElementType::Timed(a, _)) => match a.unsigned_abs().checked_ilog10().unwrap_or(0) + 1 {
+            0..=3 => "aspect-square rounded flex items-center justify-center w-[5ch] font-bold text-2xl",
+            4 => "aspect-square rounded flex items-center justify-center w-[5ch] font-bold text-xl",
+            5 => "aspect-square rounded flex items-center justify-center w-[5ch] font-bold text-lg",
+            _ => "aspect-square rounded flex items-center justify-center w-[5ch] font-bold text-sm",
+        },
+        _ => "aspect-square rounded flex items-center justify-center w-[5ch] font-bold text-2xl",
+    }
+}
+
+impl Theme {
+
+    // (background, text) of a rank
+    pub fn tile_colours(&self, rank: usize) -> (String, String) {
+        match self.tiles.get(rank) {
+            Some((background, text)) => (background.to_string(), text.to_string()),
+            None => {
+                let scale = self.high_tiles;
+                let hue = (scale.start + (rank - self.tiles.len()) as u32 * scale.step) % 360;
+                (format!("hsl({hue}, {}%, {}%)", scale.saturation, scale.lightness), scale.text.to_string())
+            }
+        }
+    }
+
+    // (background, text) of a cell, the obstacles take the first colour of their texture
+    pub fn cell_colours<R: VariantRules>(&self, element: Option<SpecificElementType>) -> (String, String) {
+        match element {
+            Some(SpecificElementType::Some(a)) | Some(SpecificElementType::Timed(a, _)) => self.tile_colours(R::tile_rank(a)),
+            Some(SpecificElementType::Joker) => (self.joker.0.to_string(), self.joker.1.to_string()),
+            Some(SpecificElementType::Bomb(_)) => (self.bomb.0.to_string(), self.bomb.1.to_string()),
+            Some(SpecificElementType::Block(_)) => (self.block.palette[0].to_string(), "#ffffff".to_string()),
+            Some(SpecificElementType::BlockFixed) => (self.block_fixed.palette[0].to_string(), "#ffffff".to_string()),
+            None => (self.empty.to_string(), self.tiles[0].1.to_string()),
+        }
+    }
+
+    pub fn tile_style<R: VariantRules>(&self, element: Option<SpecificElementType>) -> String {
+        // the texture covers the obstacles
+        if self.texture(element).is_some() {
+            return format!("font-family: {};", self.font);
+        }
+        let (background, text) = self.cell_colours::<R>(element);
+        let outline = if self.outline.is_empty() || element.is_none() { String::new() } else { format!(" border: {};", self.outline) };
+        format!("background-color: {background}; color: {text}; font-family: {};{outline}", self.font)
+    }
+
+    // (width in pixels, colour) of the css outline of the tiles
+    pub fn outline_stroke(&self) -> Option<(u32, &'static str)> {
+        let mut words = self.outline.split_whitespace();
+        let width = words.next()?.trim_end_matches("px").parse().ok()?;
+        Some((width, words.last()?))
+    }
+
+    pub fn texture(&self, element: Option<SpecificElementType>) -> Option<Texture> {
+        match element {
+            Some(SpecificElementType::Block(_)) => Some(self.block),
+            Some(SpecificElementType::BlockFixed) => Some(self.block_fixed),
+            _ => None,
+        }
+    }
+}
+
+impl Texture {
+
+    // colour of every cell, row by row
+    pub fn cells(&self) -> Vec<&'static str> {
+        self.pattern
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|digit| self.palette.get(digit as usize).copied().unwrap_or(self.palette[0]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::merge_rules::ClassicMergeRule;
+
+    #[test]
+    fn ranks_past_the_listed_tiles_follow_the_hue_scale() {
+        let listed = CLASSIC.tiles.len();
+        assert_eq!(CLASSIC.tile_colours(listed - 1), ("#edc22e".to_string(), "#ffffff".to_string()));
+        let hues: Vec<String> = (listed..listed + 4).map(|rank| CLASSIC.tile_colours(rank).0).collect();
+        assert_eq!(hues, ["hsl(280, 45%, 35%)", "hsl(315, 45%, 35%)", "hsl(350, 45%, 35%)", "hsl(25, 45%, 35%)"]);
+        // 4096 is the first tile without a listed colour in the classic rules
+        assert_eq!(CLASSIC.cell_colours::<ClassicMergeRule>(Some(SpecificElementType::Some(4096))).0, hues[0]);
+        for t in THEMES {
+            let scale = t.high_tiles;
+            assert_eq!(t.tile_colours(t.tiles.len() + 1).1, scale.text);
+            assert_ne!(t.tile_colours(t.tiles.len()).0, t.tile_colours(t.tiles.len() + 1).0, "{}", t.name);
+        }
+    }
+
+    #[test]
+    fn textures_cover_the_8x8_cells() {
+        for t in THEMES {
+            for texture in [t.block, t.block_fixed] {
+                assert_eq!(texture.cells().len(), 64, "{}", t.name);
+                assert_eq!(texture.pattern.split('/').count(), 8);
+                assert!(texture.pattern.chars().all(|c| c == '/' || c.to_digit(10).is_some_and(|d| (d as usize) < texture.palette.len())));
+            }
+        }
+        assert_eq!(CLASSIC.block.cells()[3], BRICKS_PALETTE[1]);
+    }
+
+    #[test]
+    fn unknown_theme_is_the_classic_one() {
+        assert_eq!(theme("unknown"), CLASSIC);
+        assert_eq!(theme(""), CLASSIC);
+        for t in THEMES {
+            assert_eq!(theme(t.name), t);
+        }
+    }
+}
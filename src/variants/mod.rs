@@ -0,0 +1,238 @@
+// Registry of the variants: every variant page declares here its id, its title, the text of its
+// home card, its board, its obstacles and its merge rules. The home grid and the /play/:variant_id
+// route are generated from it, the settings, the replays and the stats find their variant here.
+// Adding a variant is adding an entry.
+use crate::game::game_variants::game_variant_1::{GameSetup, SpawnConfig, TimedTiles};
+
+// merge rules of a variant, each one is the type parameter of its boards
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rules {
+    Classic,
+    Fibonacci,
+    Tripling,
+    Threes,
+}
+
+pub const RULES: [Rules; 4] = [Rules::Classic, Rules::Fibonacci, Rules::Tripling, Rules::Threes];
+
+impl Rules {
+    // name of the rules in the command line options
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rules::Classic => "classic",
+            Rules::Fibonacci => "fibonacci",
+            Rules::Tripling => "tripling",
+            Rules::Threes => "threes",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rules> {
+        RULES.iter().find(|r| r.name() == name).copied()
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        RULES.iter().map(Rules::name).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VariantDef {
+    // key of the route, the saves, the scores, the settings and the replays
+    pub id: &'static str,
+    pub title: &'static str,
+    // text of the home card
+    pub description: &'static str,
+    // (rows, columns) of the board before the player changes it
+    pub size: (usize, usize),
+    pub setup: GameSetup,
+    pub rules: Rules,
+    // the games may never end (the tiles keep vanishing), left out of the daily challenge
+    pub endless: bool,
+}
+
+pub const VARIANTS: [VariantDef; 12] = [
+    VariantDef {
+        id: "classic_2048",
+        title: "Classic 2048",
+        description: "The original 4×4 grid. Combine tiles to reach 2048!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "classic_2048_variant_1",
+        title: "Classic 2048 Variant 1",
+        description: "The original 4×4 grid variant 1 with moving blocks. Combine tiles to reach 2048!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 0, blocks: 1, spawn: SpawnConfig { block_per_mille: 15, block_hp: 3, ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "classic_2048_variant_2",
+        title: "Classic 2048 Variant 2",
+        description: "The original 4×4 grid variant 2 with moving/fixed blocks . Combine tiles to reach 2048!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 1, blocks: 2, spawn: SpawnConfig { block_per_mille: 25, block_hp: 4, ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "classic_2048_variant_3",
+        title: "Classic 2048 Variant 3",
+        description: "A 5×5 grid with moving/fixed blocks, its size is yours to change in the settings. Combine tiles to reach 2048!",
+        size: (5, 5),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 1, blocks: 2, spawn: SpawnConfig { block_per_mille: 25, block_hp: 4, ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "2048_xl",
+        title: "2048 XL",
+        description: "A roomy 6×6 grid without obstacles, more space to plan the merges. Combine tiles to reach 2048!",
+        size: (6, 6),
+        setup: GameSetup { start_tiles: 2, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "fibonacci_2048",
+        title: "Fibonacci 2048",
+        description: "Consecutive Fibonacci numbers merge (1+1, 1+2, 2+3 ...). Combine tiles to reach 610!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 2, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY },
+        rules: Rules::Fibonacci,
+        endless: false,
+    },
+    VariantDef {
+        id: "tripling_2048",
+        title: "Tripling 2048",
+        description: "Powers of 3, two equal tiles merge into their triple. Combine tiles to reach 177147!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY },
+        rules: Rules::Tripling,
+        endless: false,
+    },
+    VariantDef {
+        id: "threes",
+        title: "Threes",
+        description: "Tiles move one cell at a time, 1+2 make 3 and the next tile enters from the edge. Reach 384!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 9, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig::NUMBERS_ONLY },
+        rules: Rules::Threes,
+        endless: false,
+    },
+    VariantDef {
+        id: "joker_2048",
+        title: "Joker 2048",
+        description: "A rare ★ joker merges with any tile and doubles it. Combine tiles to reach 2048!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 0, blocks: 0, spawn: SpawnConfig { joker_per_mille: 40, ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "bombs_2048",
+        title: "Bombs 2048",
+        description: "Blocks are back, but bombs clear their neighbourhood when a tile merges into them or when their countdown ends.",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 1, blocks: 2, spawn: SpawnConfig { bomb_per_mille: 10, bomb_countdown: 10, block_hp: 3, ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: false,
+    },
+    VariantDef {
+        id: "decay_2048",
+        title: "Decay 2048",
+        description: "Tiles vanish when they are not merged within 30 moves. Keep merging for the best score!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 0, blocks: 1, spawn: SpawnConfig { block_per_mille: 15, block_hp: 3, timed: TimedTiles::Decaying(30), ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: true,
+    },
+    VariantDef {
+        id: "petrify_2048",
+        title: "Petrify 2048",
+        description: "Tiles turn into stone when they are not merged within 50 moves. Combine tiles to reach 2048!",
+        size: (4, 4),
+        setup: GameSetup { start_tiles: 1, blocks_fixed: 0, blocks: 1, spawn: SpawnConfig { block_per_mille: 15, block_hp: 3, timed: TimedTiles::Petrifying(50), ..SpawnConfig::NUMBERS_ONLY } },
+        rules: Rules::Classic,
+        endless: false,
+    },
+];
+
+pub fn variant(id: &str) -> Option<&'static VariantDef> {
+    VARIANTS.iter().find(|v| v.id == id)
+}
+
+pub fn variants_ids() -> Vec<&'static str> {
+    VARIANTS.iter().map(|v| v.id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_variants::game_variant_1::{Goal, SpecificElementType, VariantRules};
+    use crate::game::merge_rules::{ClassicMergeRule, FibonacciMergeRule, ThreesMergeRule, TriplingMergeRule};
+    use crate::storage::settings::{VariantSettings, SETTINGS_CAPACITY};
+
+    #[test]
+    fn variant_ids_are_unique() {
+        // the ids are path segments of the routes and keys of the storage
+        for id in variants_ids() {
+            assert!(!id.is_empty() && id.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'_'), "{id}");
+        }
+        let mut ids = variants_ids();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), VARIANTS.len());
+    }
+
+    #[test]
+    fn every_variant_is_found_by_its_id() {
+        for def in VARIANTS.iter() {
+            assert_eq!(variant(def.id), Some(def));
+        }
+        assert_eq!(variant("no_such_variant"), None);
+    }
+
+    #[test]
+    fn rules_names_round_trip() {
+        for rules in RULES {
+            assert_eq!(Rules::from_name(rules.name()), Some(rules));
+        }
+        assert_eq!(Rules::from_name("no_such_rules"), None);
+    }
+
+    // the board of the page of the variant, as the settings build it before any change
+    fn check_setup<R: VariantRules>(def: &VariantDef) {
+        for seed in 0..20 {
+            let g = VariantSettings::defaults::<R>(def.size, def.setup)
+                .build_seeded::<SETTINGS_CAPACITY, SETTINGS_CAPACITY, R>(def.setup, seed)
+                .unwrap_or_else(|e| panic!("{}: {e}", def.id));
+            assert_eq!(g.board_size, def.size, "{}", def.id);
+            assert_eq!(g.game_variant_data.goal, Goal::ReachTile(R::TARGET_VALUE), "{}", def.id);
+            assert_eq!(g.game_variant_data.spawn_config, def.setup.spawn, "{}", def.id);
+            let cells: Vec<SpecificElementType> = g.game_variant_data.array.iter().take(def.size.0).flat_map(|row| row.iter().take(def.size.1)).flatten().copied().collect();
+            let count = |f: fn(&SpecificElementType) -> bool| cells.iter().filter(|cell| f(cell)).count();
+            assert_eq!(count(|cell| matches!(cell, SpecificElementType::Block(_))), def.setup.blocks, "{}", def.id);
+            assert_eq!(count(|cell| matches!(cell, SpecificElementType::BlockFixed)), def.setup.blocks_fixed, "{}", def.id);
+            let numbers: Vec<i32> = cells.iter().filter_map(|cell| cell.number()).collect();
+            assert_eq!(numbers.len(), def.setup.start_tiles, "{}", def.id);
+            // the start tiles are spawns of the rules
+            assert!(numbers.iter().all(|a| R::SPAWN_WEIGHTS.iter().any(|(value, _)| value == a)), "{}: {numbers:?}", def.id);
+        }
+    }
+
+    #[test]
+    fn every_setup_builds_its_board() {
+        for def in VARIANTS.iter() {
+            match def.rules {
+                Rules::Classic => check_setup::<ClassicMergeRule>(def),
+                Rules::Fibonacci => check_setup::<FibonacciMergeRule>(def),
+                Rules::Tripling => check_setup::<TriplingMergeRule>(def),
+                Rules::Threes => check_setup::<ThreesMergeRule>(def),
+            }
+        }
+    }
+}